@@ -64,7 +64,7 @@ async fn main() -> Result<()> {
         anchor_state_registry,
         factory,
         challenger_signer,
-    );
+    )?;
 
     // Initialize challenger gauges.
     ChallengerGauge::register_all();
@@ -0,0 +1,97 @@
+//! The signer's permissions on the game implementation's `AccessManager`.
+//!
+//! Games check `isAllowedProposer` on creation and `isAllowedChallenger` on challenge, so a signer
+//! missing from the allowlist only finds out from reverted transactions. The permissions are read
+//! at startup and on every loop, and game creation and challenging pause while they're missing.
+//! Once no game has been proposed for the fallback timeout, anyone can propose, and the proposer
+//! resumes creating games whether or not it is on the allowlist.
+
+use alloy_primitives::Address;
+use alloy_provider::Provider;
+use anyhow::Result;
+
+use crate::contract::AccessManager::AccessManagerInstance;
+
+/// The proposer's permission to create games.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProposerPermission {
+    /// Whether the proposer can create games, as reported by `isAllowedProposer`.
+    pub allowed: bool,
+    /// Whether the proposer is on the allowlist.
+    pub allowlisted: bool,
+    /// Whether anyone can propose, because the fallback timeout has elapsed or the allowlist is
+    /// open.
+    pub permissionless: bool,
+    /// Timestamp of the last proposal, or of the access manager's deployment if there is none.
+    pub last_proposal_timestamp: u64,
+    /// Seconds without a proposal after which anyone can propose.
+    pub fallback_timeout: u64,
+}
+
+impl ProposerPermission {
+    /// Read the permission of `proposer` from the access manager. The allowlist, permissionless
+    /// mode and timestamps are only read for metrics and logs.
+    pub async fn fetch<P: Provider + Clone>(
+        access_manager: &AccessManagerInstance<P>,
+        proposer: Address,
+    ) -> Result<Self> {
+        let allowed = access_manager.isAllowedProposer(proposer).call().await?;
+        let allowlisted = access_manager.proposers(proposer).call().await?;
+        let permissionless = access_manager.isProposalPermissionlessMode().call().await?;
+        let last_proposal_timestamp =
+            access_manager.getLastProposalTimestamp().call().await?.to::<u64>();
+        let fallback_timeout = access_manager.FALLBACK_TIMEOUT().call().await?.to::<u64>();
+        Ok(Self { allowed, allowlisted, permissionless, last_proposal_timestamp, fallback_timeout })
+    }
+
+    /// Seconds from `now` until anyone can propose, or 0 if anyone already can.
+    ///
+    /// Permissionless proposing opens once strictly more than the fallback timeout has passed
+    /// since the last proposal.
+    pub fn secs_until_permissionless(&self, now: u64) -> u64 {
+        if self.permissionless {
+            return 0;
+        }
+        self.last_proposal_timestamp
+            .saturating_add(self.fallback_timeout)
+            .saturating_add(1)
+            .saturating_sub(now)
+    }
+}
+
+/// Read whether `challenger` can challenge games from the access manager.
+pub async fn fetch_challenger_allowed<P: Provider + Clone>(
+    access_manager: &AccessManagerInstance<P>,
+    challenger: Address,
+) -> Result<bool> {
+    Ok(access_manager.isAllowedChallenger(challenger).call().await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const LAST_PROPOSAL: u64 = 1_000;
+    const TIMEOUT: u64 = 3_600;
+
+    #[rstest]
+    #[case::just_proposed(false, LAST_PROPOSAL, TIMEOUT + 1)]
+    #[case::at_timeout(false, LAST_PROPOSAL + TIMEOUT, 1)]
+    #[case::past_timeout(false, LAST_PROPOSAL + TIMEOUT + 10, 0)]
+    #[case::open(true, LAST_PROPOSAL, 0)]
+    fn test_secs_until_permissionless(
+        #[case] permissionless: bool,
+        #[case] now: u64,
+        #[case] expected: u64,
+    ) {
+        let permission = ProposerPermission {
+            allowed: permissionless,
+            allowlisted: false,
+            permissionless,
+            last_proposal_timestamp: LAST_PROPOSAL,
+            fallback_timeout: TIMEOUT,
+        };
+        assert_eq!(permission.secs_until_permissionless(now), expected);
+    }
+}
@@ -11,10 +11,10 @@ use alloy_primitives::{Address, U256};
 use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 
-use crate::proposer::Game;
+use crate::{pending_proofs::PendingProof, proposer::Game};
 
 /// Current backup format version. Increment when making breaking changes.
-pub const BACKUP_VERSION: u32 = 1;
+pub const BACKUP_VERSION: u32 = 2;
 
 /// Serializable backup of the proposer state.
 #[derive(Serialize, Deserialize)]
@@ -32,6 +32,10 @@ pub struct ProposerBackup {
     /// guard reset. Defaults to Address::ZERO (no guard) for old backups.
     #[serde(default)]
     pub last_created_game_address: Address,
+    /// Proof requests that were outstanding at the time of the backup, re-attached to on
+    /// restart instead of requesting the proofs again. Empty for old backups.
+    #[serde(default)]
+    pub pending_proofs: Vec<PendingProof>,
 }
 
 impl ProposerBackup {
@@ -44,6 +48,7 @@ impl ProposerBackup {
             anchor_game_index,
             last_created_game_l2_block: 0,
             last_created_game_address: Address::ZERO,
+            pending_proofs: Vec::new(),
         }
     }
 
@@ -80,7 +85,12 @@ impl ProposerBackup {
         temp.as_file().sync_all().context("failed to sync proposer backup temp file")?;
         temp.persist(path).context("failed to persist proposer backup file")?;
 
-        tracing::debug!(?path, games = self.games.len(), "Proposer state backed up");
+        tracing::debug!(
+            ?path,
+            games = self.games.len(),
+            pending_proofs = self.pending_proofs.len(),
+            "Proposer state backed up"
+        );
         Ok(())
     }
 
@@ -137,6 +147,8 @@ mod tests {
         let game = Game {
             index: U256::ZERO,
             address: Address::ZERO,
+            creator: Address::ZERO,
+            created_at: 0,
             parent_index: 0,
             l2_block: U256::ZERO,
             status: GameStatus::IN_PROGRESS,
@@ -159,6 +171,8 @@ mod tests {
             vec![
                 "address",
                 "aggregation_vkey",
+                "created_at",
+                "creator",
                 "deadline",
                 "index",
                 "l2_block",
@@ -187,6 +201,7 @@ mod tests {
                 "games",
                 "last_created_game_address",
                 "last_created_game_l2_block",
+                "pending_proofs",
                 "version"
             ],
             "ProposerBackup schema changed! Bump BACKUP_VERSION in backup.rs"
@@ -0,0 +1,187 @@
+//! Balance monitoring for the L1 signer and the prover network requester.
+//!
+//! Creating or challenging a game posts a bond, and proving spends the requester's PROVE. When the
+//! funds run out, those transactions fail one after another. The balances are read periodically
+//! and bond-consuming actions pause while posting a bond would leave the signer less than a
+//! reserve, or the PROVE balance is below a minimum. The reserve keeps cheap actions such as
+//! resolving games and claiming bonds running, and claiming bonds refills the balance. Proof
+//! requests pause while the PROVE balance is below the minimum too, so that the balance left is
+//! kept for proofs that can't wait.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use alloy_primitives::U256;
+
+/// Why bond-consuming actions are paused.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LowBalance {
+    /// Posting the bond would leave the signer less than the reserve.
+    L1 { balance: U256, required: U256 },
+    /// The requester's PROVE balance is below the minimum.
+    Prove { balance: U256, minimum: U256 },
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Balances {
+    l1: Option<U256>,
+    prove: Option<U256>,
+}
+
+/// The latest balances, and whether bond-consuming actions are paused, shared between clones.
+#[derive(Clone, Debug, Default)]
+pub struct BalanceMonitor {
+    balances: Arc<Mutex<Balances>>,
+    paused: Arc<AtomicBool>,
+    proving_paused: Arc<AtomicBool>,
+}
+
+impl BalanceMonitor {
+    /// Record the signer's L1 balance.
+    pub fn record_l1(&self, balance: U256) {
+        self.balances.lock().expect("balance lock poisoned").l1 = Some(balance);
+    }
+
+    /// Record the requester's PROVE balance.
+    pub fn record_prove(&self, balance: U256) {
+        self.balances.lock().expect("balance lock poisoned").prove = Some(balance);
+    }
+
+    /// Deduct a bond posted since the L1 balance was last read, so that several bonds posted
+    /// between reads are accounted for.
+    pub fn record_spent(&self, amount: U256) {
+        let mut balances = self.balances.lock().expect("balance lock poisoned");
+        balances.l1 = balances.l1.map(|balance| balance.saturating_sub(amount));
+    }
+
+    /// Whether bond-consuming actions are currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Whether proof requests are currently paused.
+    pub fn is_proving_paused(&self) -> bool {
+        self.proving_paused.load(Ordering::Relaxed)
+    }
+
+    /// Check that the PROVE balance is at least `min_prove` before requesting proofs. A balance
+    /// that hasn't been read yet passes. Logs when the pause starts and ends.
+    pub fn check_proving(&self, min_prove: U256) -> Result<(), LowBalance> {
+        let result = match self.balances.lock().expect("balance lock poisoned").prove {
+            Some(balance) if balance < min_prove => {
+                Err(LowBalance::Prove { balance, minimum: min_prove })
+            }
+            _ => Ok(()),
+        };
+
+        let was_paused = self.proving_paused.swap(result.is_err(), Ordering::Relaxed);
+        match (&result, was_paused) {
+            (Err(low), false) => tracing::warn!(?low, "PROVE balance too low, pausing proving"),
+            (Ok(()), true) => tracing::info!("PROVE balance restored, resuming proving"),
+            _ => {}
+        }
+        result
+    }
+
+    /// Check that the signer can post `bond` and keep `reserve`, and that the PROVE balance is at
+    /// least `min_prove`. Balances that haven't been read yet pass. Logs when the pause starts
+    /// and ends.
+    pub fn check(&self, bond: U256, reserve: U256, min_prove: U256) -> Result<(), LowBalance> {
+        let balances = *self.balances.lock().expect("balance lock poisoned");
+        let required = bond.saturating_add(reserve);
+        let result = match balances {
+            Balances { l1: Some(balance), .. } if balance < required => {
+                Err(LowBalance::L1 { balance, required })
+            }
+            Balances { prove: Some(balance), .. } if balance < min_prove => {
+                Err(LowBalance::Prove { balance, minimum: min_prove })
+            }
+            _ => Ok(()),
+        };
+
+        let was_paused = self.paused.swap(result.is_err(), Ordering::Relaxed);
+        match (&result, was_paused) {
+            (Err(low), false) => {
+                tracing::warn!(?low, "Balance too low, pausing bond-consuming actions")
+            }
+            (Ok(()), true) => tracing::info!("Balance restored, resuming bond-consuming actions"),
+            _ => {}
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::unread(None, None, Ok(()))]
+    #[case::sufficient(Some(150), Some(10), Ok(()))]
+    #[case::bond_eats_reserve(
+        Some(149),
+        Some(10),
+        Err(LowBalance::L1 { balance: U256::from(149), required: U256::from(150) })
+    )]
+    #[case::low_prove(
+        Some(150),
+        Some(9),
+        Err(LowBalance::Prove { balance: U256::from(9), minimum: U256::from(10) })
+    )]
+    fn test_balance_check(
+        #[case] l1: Option<u64>,
+        #[case] prove: Option<u64>,
+        #[case] expected: Result<(), LowBalance>,
+    ) {
+        let monitor = BalanceMonitor::default();
+        if let Some(balance) = l1 {
+            monitor.record_l1(U256::from(balance));
+        }
+        if let Some(balance) = prove {
+            monitor.record_prove(U256::from(balance));
+        }
+
+        let result = monitor.check(U256::from(100), U256::from(50), U256::from(10));
+        assert_eq!(result, expected);
+        assert_eq!(monitor.is_paused(), expected.is_err());
+    }
+
+    #[test]
+    fn test_proving_check() {
+        let monitor = BalanceMonitor::default();
+        assert!(monitor.check_proving(U256::from(10)).is_ok());
+
+        // The L1 balance doesn't gate proving.
+        monitor.record_l1(U256::ZERO);
+        monitor.record_prove(U256::from(9));
+        assert_eq!(
+            monitor.check_proving(U256::from(10)),
+            Err(LowBalance::Prove { balance: U256::from(9), minimum: U256::from(10) })
+        );
+        assert!(monitor.is_proving_paused());
+
+        monitor.record_prove(U256::from(10));
+        assert!(monitor.check_proving(U256::from(10)).is_ok());
+        assert!(!monitor.is_proving_paused());
+    }
+
+    #[test]
+    fn test_balance_spent_between_reads() {
+        let monitor = BalanceMonitor::default();
+        monitor.record_l1(U256::from(250));
+        assert!(monitor.check(U256::from(100), U256::from(50), U256::ZERO).is_ok());
+
+        // After one bond, 150 is left for another. After two, only 50.
+        monitor.record_spent(U256::from(100));
+        assert!(monitor.check(U256::from(100), U256::from(50), U256::ZERO).is_ok());
+        monitor.record_spent(U256::from(100));
+        assert!(monitor.check(U256::from(100), U256::from(50), U256::ZERO).is_err());
+
+        monitor.record_l1(U256::from(1000));
+        assert!(monitor.check(U256::from(100), U256::from(50), U256::ZERO).is_ok());
+        assert!(!monitor.is_paused());
+    }
+}
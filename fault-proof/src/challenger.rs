@@ -1,28 +1,41 @@
 use std::{
     collections::HashMap,
-    sync::{Arc, OnceLock},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
     time::Duration,
 };
 
 use alloy_eips::{BlockId, BlockNumberOrTag};
-use alloy_primitives::{Address, U256};
-use alloy_provider::{Provider, ProviderBuilder};
+use alloy_primitives::{Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types_eth::TransactionReceipt;
 use anyhow::{bail, Context, Result};
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use tokio::{sync::Mutex, time};
 
 use crate::{
+    access::fetch_challenger_allowed,
+    balance::BalanceMonitor,
+    chaos::{Chaos, TxAction},
     config::ChallengerConfig,
     contract::{
+        AccessManager::{self, AccessManagerInstance},
         AnchorStateRegistry::AnchorStateRegistryInstance,
-        DisputeGameFactory::DisputeGameFactoryInstance, GameStatus, OPSuccinctFaultDisputeGame,
-        ProposalStatus,
+        DisputeGameFactory::DisputeGameFactoryInstance,
+        GameStatus, OPSuccinctFaultDisputeGame, ProposalStatus,
     },
     is_parent_challenger_wins, is_parent_resolved,
     prometheus::ChallengerGauge,
-    FactoryTrait, L1Provider, L2Provider, L2ProviderTrait, TxErrorExt, TX_REVERTED_PREFIX,
+    FactoryTrait, ImproperGame, L1Provider, L2ProviderTrait, L2QuorumProvider, RegistrySettings,
+    TxErrorExt, TX_REVERTED_PREFIX,
+};
+use op_succinct_host_utils::{
+    ledger::{wei_to_eth, Ledger, LedgerTxKind},
+    metrics::MetricsGauge,
+    quorum::QuorumError,
 };
-use op_succinct_host_utils::metrics::MetricsGauge;
 use op_succinct_signer_utils::SignerLock;
 
 pub struct OPSuccinctChallenger<P>
@@ -32,11 +45,23 @@ where
     pub config: ChallengerConfig,
     signer: SignerLock,
     l1_provider: L1Provider,
-    l2_provider: L2Provider,
+    l2_provider: L2QuorumProvider,
     anchor_state_registry: AnchorStateRegistryInstance<P>,
     factory: DisputeGameFactoryInstance<P>,
     challenger_bond: OnceLock<U256>,
+    /// Access manager of the game implementation, deciding who can challenge.
+    access_manager: OnceLock<AccessManagerInstance<P>>,
+    /// Whether the access manager allows the signer to challenge, gating challenges.
+    challenger_allowed: AtomicBool,
+    /// Anchor state registry settings as of the last sync.
+    registry_settings: std::sync::Mutex<Option<RegistrySettings>>,
     state: Arc<Mutex<ChallengerState>>,
+    /// Bonds and gas per game.
+    ledger: Ledger,
+    /// Latest signer balance, gating challenges.
+    balance_monitor: BalanceMonitor,
+    /// Faults to inject, for testing.
+    chaos: Chaos,
 }
 
 impl<P> OPSuccinctChallenger<P>
@@ -50,22 +75,34 @@ where
         anchor_state_registry: AnchorStateRegistryInstance<P>,
         factory: DisputeGameFactoryInstance<P>,
         signer: SignerLock,
-    ) -> Self {
-        let l2_rpc = config.l2_rpc.clone();
+    ) -> Result<Self> {
+        let ledger = Ledger::open(config.ledger_path.clone())?;
+        let chaos = Chaos::open(config.chaos_scenario_path.as_deref())?;
+        let l2_provider = L2QuorumProvider::new(
+            config.l2_rpc.clone(),
+            &config.l2_quorum_rpcs,
+            config.output_root_quorum,
+        );
 
-        OPSuccinctChallenger {
+        Ok(OPSuccinctChallenger {
             config,
             signer,
             l1_provider: l1_provider.clone(),
-            l2_provider: ProviderBuilder::default().connect_http(l2_rpc),
+            l2_provider,
             anchor_state_registry,
             factory,
             challenger_bond: OnceLock::new(),
+            access_manager: OnceLock::new(),
+            challenger_allowed: AtomicBool::new(true),
+            registry_settings: std::sync::Mutex::new(None),
             state: Arc::new(Mutex::new(ChallengerState {
                 cursor: U256::ZERO,
                 games: HashMap::new(),
             })),
-        }
+            ledger,
+            balance_monitor: BalanceMonitor::default(),
+            chaos,
+        })
     }
 
     /// Runs the main challenger loop. On each tick it waits for the configured interval, refreshes
@@ -75,6 +112,10 @@ where
 
         self.try_init().await?;
 
+        // Publish the totals of a ledger restored from disk, and save changes from now on.
+        self.update_ledger_metrics();
+        self.ledger.spawn_writer();
+
         if self.config.malicious_challenge_percentage > 0.0 {
             tracing::warn!(
                 "\x1b[33mMalicious challenging enabled: {}% of valid games will be challenged for testing\x1b[0m",
@@ -97,6 +138,14 @@ where
                 continue
             }
 
+            if let Err(e) = self.refresh_balance().await {
+                tracing::warn!("Failed to refresh signer balance: {:?}", e);
+            }
+
+            if let Err(e) = self.refresh_challenger_permission().await {
+                tracing::warn!("Failed to refresh challenger permission: {:?}", e);
+            }
+
             if let Err(e) = self.handle_game_challenging().await {
                 tracing::warn!("Failed to handle game challenging: {:?}", e);
             }
@@ -139,14 +188,20 @@ where
 
     /// Validates startup and initializes state.
     async fn validate_and_init(&self) -> Result<()> {
-        let bond = self.startup_validations().await?;
-        self.init_state(bond);
+        let (bond, access_manager) = self.startup_validations().await?;
+        self.init_state(bond, access_manager);
+        // Check the signer can challenge, so that a missing allowlist entry shows up at startup
+        // rather than as reverted challenges. A failed read doesn't hold up startup: the
+        // permission is assumed and read again on every loop.
+        if let Err(e) = self.refresh_challenger_permission().await {
+            tracing::warn!("Failed to read challenger permission, assuming allowed: {:?}", e);
+        }
         Ok(())
     }
 
     /// Runs one-time startup validations before the challenger begins normal operations.
-    /// Returns the challenger bond on success.
-    async fn startup_validations(&self) -> Result<U256> {
+    /// Returns the challenger bond and the access manager on success.
+    async fn startup_validations(&self) -> Result<(U256, Address)> {
         // Validate game type is registered and get game implementation.
         let game_impl = self.factory.game_impl(self.config.game_type).await?;
 
@@ -160,15 +215,20 @@ where
             );
         }
 
-        // Fetch challenger bond.
+        // Fetch challenger bond and access manager.
         let bond = game_impl.challengerBond().call().await?;
+        let access_manager = game_impl.accessManager().call().await?;
 
-        Ok(bond)
+        Ok((bond, access_manager))
     }
 
-    /// Initialize challenger state with the validated challenger bond.
-    fn init_state(&self, bond: U256) {
+    /// Initialize challenger state with the validated challenger bond and access manager.
+    fn init_state(&self, bond: U256, access_manager: Address) {
         self.challenger_bond.set(bond).expect("challenger_bond must not already be set");
+        let access_manager = AccessManager::new(access_manager, self.factory.provider().clone());
+        if self.access_manager.set(access_manager).is_err() {
+            panic!("access_manager must not already be set");
+        }
     }
 
     /// Synchronizes the game cache.
@@ -177,13 +237,18 @@ where
     ///    - Incrementally load new games from the factory starting from the cursor.
     /// 2. Synchronize the status of all cached games.
     ///    - Games are marked for challenging if output root is invalid or the parent is challenger
-    ///      wins.
+    ///      wins, unless the registry blacklisted or retired them.
     ///    - Games are marked for resolution if the parent is resolved, the game is over, and it's
     ///      own game.
     ///    - Games are marked for bond claim if they are finalized and there is credit to claim.
     ///    - Games are evicted once finalized with no remaining credit or whenever resolves as
     ///      defender wins.
     pub async fn sync_state(&self) -> Result<()> {
+        self.chaos.rpc_lag().await;
+
+        // Track the registry settings the guardian can change.
+        let registry_settings = self.sync_registry().await?;
+
         // 1. Load new games.
         let mut next_index = {
             let state = self.state.lock().await;
@@ -234,6 +299,7 @@ where
             }
 
             let mut actions = Vec::with_capacity(games.len());
+            let mut improper_skipped = 0;
 
             for game in games {
                 let contract =
@@ -264,6 +330,33 @@ where
                                         .await?;
                                         game.is_invalid || parent_lost
                                     };
+                                    // Improper games can never become the anchor, and close
+                                    // with refunded bonds, so challenging them only costs gas.
+                                    let should_challenge = should_challenge && {
+                                        let improper =
+                                            if registry_settings.is_retired(game.created_at) {
+                                                Some(ImproperGame::Retired)
+                                            } else if self
+                                                .anchor_state_registry
+                                                .isGameBlacklisted(game.address)
+                                                .call()
+                                                .await?
+                                            {
+                                                Some(ImproperGame::Blacklisted)
+                                            } else {
+                                                None
+                                            };
+                                        if let Some(reason) = improper {
+                                            tracing::debug!(
+                                                game_index = %game.index,
+                                                game_address = ?game.address,
+                                                ?reason,
+                                                "Skipping challenge of improper game"
+                                            );
+                                            improper_skipped += 1;
+                                        }
+                                        improper.is_none()
+                                    };
                                     (should_challenge, false)
                                 }
                                 ProposalStatus::Challenged => {
@@ -314,6 +407,7 @@ where
                     _ => unreachable!("Unexpected game status: {:?}", status),
                 }
             }
+            ChallengerGauge::ImproperGamesSkipped.set(improper_skipped as f64);
 
             let mut state = self.state.lock().await;
             for action in actions {
@@ -344,6 +438,22 @@ where
         Ok(())
     }
 
+    /// Synchronizes the registry settings, logging changes and exporting them as metrics.
+    async fn sync_registry(&self) -> Result<RegistrySettings> {
+        let settings =
+            RegistrySettings::fetch(&self.anchor_state_registry, BlockId::latest()).await?;
+
+        let mut current = self.registry_settings.lock().expect("registry settings lock poisoned");
+        settings.log_changes(current.as_ref(), self.config.game_type);
+        *current = Some(settings);
+
+        ChallengerGauge::GameTypeRespected
+            .set((settings.respected_game_type == self.config.game_type) as u8 as f64);
+        ChallengerGauge::RetirementTimestamp.set(settings.retirement_timestamp as f64);
+
+        Ok(settings)
+    }
+
     /// Fetch game from the factory.
     ///
     /// Drop game if the game type is invalid or the game was not respected at the time of creation.
@@ -362,8 +472,8 @@ where
         }
 
         let l2_block_number = contract.l2BlockNumber().call().await?;
-        let computed_output_root =
-            self.l2_provider.compute_output_root_at_block(l2_block_number).await?;
+        // A disagreement fails the sync, so that no game is challenged on a disputed output root.
+        let computed_output_root = self.compute_output_root(l2_block_number).await?;
         let output_root = contract.rootClaim().call().await?;
         let claim_data = contract.claimData().call().await?;
 
@@ -378,6 +488,7 @@ where
                 Game {
                     index,
                     address: game_address,
+                    created_at: U256::from(game.timestamp).to::<u64>(),
                     parent_index: claim_data.parentIndex,
                     l2_block_number,
                     is_invalid: output_root != computed_output_root,
@@ -403,6 +514,18 @@ where
         Ok(())
     }
 
+    /// Computes the output root at `l2_block`, raising an alert if the L2 endpoints disagree on it.
+    async fn compute_output_root(&self, l2_block: U256) -> Result<B256> {
+        let result = self.l2_provider.compute_output_root_at_block(l2_block).await;
+        if let Err(e) = &result {
+            if QuorumError::is_disagreement(e) {
+                tracing::error!(%l2_block, ?e, "L2 endpoints disagree on the output root");
+                ChallengerGauge::OutputRootDisagreement.increment(1.0);
+            }
+        }
+        result
+    }
+
     /// Challenges games flagged for challenging.
     /// Also supports malicious challenging of valid games for testing defense mechanisms when
     /// configured.
@@ -418,8 +541,26 @@ where
                 .collect::<Vec<_>>()
         };
 
-        for game in candidates {
-            if let Err(error) = self.submit_challenge_transaction(&game).await {
+        // Games left unchallenged stay flagged and are challenged once the signer is allowed to.
+        if !candidates.is_empty() && !self.challenger_allowed.load(Ordering::Relaxed) {
+            tracing::error!(
+                unchallenged_games = candidates.len(),
+                "Challenger is not allowed to challenge by the access manager, leaving invalid games unchallenged"
+            );
+            return Ok(());
+        }
+
+        for (i, game) in candidates.iter().enumerate() {
+            // Games left unchallenged stay flagged and are challenged once the balance allows.
+            if !self.bond_affordable()? {
+                tracing::error!(
+                    unchallenged_games = candidates.len() - i,
+                    "Balance too low to post the challenger bond, leaving invalid games unchallenged"
+                );
+                break;
+            }
+
+            if let Err(error) = self.submit_challenge_transaction(game).await {
                 if error.is_revert() {
                     tracing::error!(
                         game_index = %game.index,
@@ -455,7 +596,10 @@ where
             let mut rng = StdRng::from_os_rng();
             let should_challenge: f64 = rng.random_range(0.0..100.0);
 
-            if should_challenge <= self.config.malicious_challenge_percentage {
+            if should_challenge <= self.config.malicious_challenge_percentage &&
+                self.challenger_allowed.load(Ordering::Relaxed) &&
+                self.bond_affordable()?
+            {
                 let candidate = {
                     let state = self.state.lock().await;
                     state
@@ -500,6 +644,7 @@ where
     }
 
     pub async fn submit_challenge_transaction(&self, game: &Game) -> Result<()> {
+        self.chaos.fail_transaction(TxAction::Challenge)?;
         let contract = OPSuccinctFaultDisputeGame::new(game.address, self.l1_provider.clone());
         let challenger_bond = *self
             .challenger_bond
@@ -515,10 +660,14 @@ where
                 self.config.tx_confirmation_timeout,
             )
             .await?;
+        self.record_ledger_tx(game.address, LedgerTxKind::Challenge, &receipt);
 
         if !receipt.status() {
             bail!("{TX_REVERTED_PREFIX} {receipt:?}");
         }
+        self.ledger.record_bond(&game.address.to_string(), challenger_bond);
+        self.balance_monitor.record_spent(challenger_bond);
+        self.update_ledger_metrics();
 
         tracing::info!(
             game_index = %game.index,
@@ -572,6 +721,7 @@ where
     }
 
     pub async fn submit_resolution_transaction(&self, game: &Game) -> Result<()> {
+        self.chaos.fail_transaction(TxAction::Resolve)?;
         let contract = OPSuccinctFaultDisputeGame::new(game.address, self.l1_provider.clone());
         let transaction_request = contract.resolve().into_transaction_request();
         let receipt = self
@@ -582,6 +732,7 @@ where
                 self.config.tx_confirmation_timeout,
             )
             .await?;
+        self.record_ledger_tx(game.address, LedgerTxKind::Resolve, &receipt);
 
         if !receipt.status() {
             bail!("{TX_REVERTED_PREFIX} {receipt:?}");
@@ -640,7 +791,9 @@ where
 
     #[tracing::instrument(name = "[[Claiming Proposer Bonds]]", skip(self, game))]
     async fn submit_bond_claim_transaction(&self, game: &Game) -> Result<()> {
+        self.chaos.fail_transaction(TxAction::Claim)?;
         let contract = OPSuccinctFaultDisputeGame::new(game.address, self.l1_provider.clone());
+        let credit = contract.credit(self.signer.address()).call().await?;
         let transaction_request =
             contract.claimCredit(self.signer.address()).gas(200_000).into_transaction_request();
         let receipt = self
@@ -651,10 +804,13 @@ where
                 self.config.tx_confirmation_timeout,
             )
             .await?;
+        self.record_ledger_tx(game.address, LedgerTxKind::Claim, &receipt);
 
         if !receipt.status() {
             bail!("{TX_REVERTED_PREFIX} {receipt:?}");
         }
+        self.ledger.record_claim(&game.address.to_string(), credit);
+        self.update_ledger_metrics();
 
         tracing::info!(
             game_index = %game.index,
@@ -667,6 +823,75 @@ where
         Ok(())
     }
 
+    /// Read the signer's L1 balance into the balance monitor.
+    async fn refresh_balance(&self) -> Result<()> {
+        let balance = self.l1_provider.get_balance(self.signer.address()).await?;
+        self.balance_monitor.record_l1(balance);
+        ChallengerGauge::SignerBalanceEth.set(wei_to_eth(balance));
+        Ok(())
+    }
+
+    /// Read whether the access manager allows the signer to challenge. See [`crate::access`].
+    async fn refresh_challenger_permission(&self) -> Result<()> {
+        let access_manager = self
+            .access_manager
+            .get()
+            .context("access_manager must be set via startup_validations")?;
+        let allowed = fetch_challenger_allowed(access_manager, self.signer.address()).await?;
+        ChallengerGauge::ChallengerAllowed.set(if allowed { 1.0 } else { 0.0 });
+
+        let was_allowed = self.challenger_allowed.swap(allowed, Ordering::Relaxed);
+        match (was_allowed, allowed) {
+            (true, false) => tracing::error!(
+                challenger = %self.signer.address(),
+                "Challenger is not allowed to challenge by the access manager, pausing challenges"
+            ),
+            (false, true) => tracing::info!("Challenger allowlisted, resuming challenges"),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Whether the signer's balance allows posting a challenger bond. See [`crate::balance`].
+    fn bond_affordable(&self) -> Result<bool> {
+        let challenger_bond = *self
+            .challenger_bond
+            .get()
+            .context("challenger_bond must be set via startup_validations")?;
+        let affordable = self
+            .balance_monitor
+            .check(challenger_bond, self.config.l1_balance_reserve, U256::ZERO)
+            .is_ok();
+        ChallengerGauge::BalancePaused.set(if affordable { 0.0 } else { 1.0 });
+        Ok(affordable)
+    }
+
+    /// Record a transaction sent for `game_address` in the ledger.
+    fn record_ledger_tx(
+        &self,
+        game_address: Address,
+        kind: LedgerTxKind,
+        receipt: &TransactionReceipt,
+    ) {
+        self.ledger.record_tx(
+            &game_address.to_string(),
+            kind,
+            receipt.transaction_hash,
+            receipt.gas_used,
+            receipt.effective_gas_price,
+        );
+        self.update_ledger_metrics();
+    }
+
+    /// Set the ledger gauges from the ledger totals.
+    fn update_ledger_metrics(&self) {
+        let totals = self.ledger.totals();
+        ChallengerGauge::LedgerBondsPostedEth.set(wei_to_eth(totals.bond_posted_wei));
+        ChallengerGauge::LedgerCreditClaimedEth.set(wei_to_eth(totals.credit_claimed_wei));
+        ChallengerGauge::LedgerGasSpentEth.set(wei_to_eth(totals.gas_spent_wei));
+        ChallengerGauge::LedgerNetEth.set(totals.net_eth());
+    }
+
     // ==================== Integration Test Helpers ====================
 
     /// Returns a copy of a game's full internal state for testing.
@@ -695,6 +920,7 @@ where
 pub struct Game {
     pub index: U256,
     pub address: Address,
+    pub created_at: u64,
     pub parent_index: u32,
     pub l2_block_number: U256,
     pub is_invalid: bool,
@@ -0,0 +1,329 @@
+//! Fault injection for testing how the proposer and challenger handle adversarial conditions.
+//!
+//! A scenario file lists the faults to inject, each firing with a probability and at most a
+//! limited number of times. Injection requires the `chaos` feature, which the integration tests
+//! enable. Without it, the hooks compile to no-ops and the proposer and challenger refuse to
+//! start with a scenario, so production builds can't be misconfigured into misbehaving.
+//!
+//! ```json
+//! {
+//!   "seed": 7,
+//!   "faults": [
+//!     { "fault": "invalid_output_root", "limit": 1 },
+//!     { "fault": "delay_proof", "secs": 30, "probability": 0.5 },
+//!     { "fault": "fail_transaction", "action": "resolve", "limit": 2 },
+//!     { "fault": "stale_l1_reads", "depth": 3, "probability": 0.1 }
+//!   ]
+//! }
+//! ```
+
+use std::path::Path;
+#[cfg(feature = "chaos")]
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+#[cfg(feature = "chaos")]
+use alloy_primitives::keccak256;
+use alloy_primitives::B256;
+#[cfg(feature = "chaos")]
+use anyhow::Context;
+use anyhow::{bail, Result};
+#[cfg(feature = "chaos")]
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// A transaction a fault can make fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TxAction {
+    Create,
+    Prove,
+    Resolve,
+    Claim,
+    Challenge,
+}
+
+/// A fault to inject.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "fault", rename_all = "snake_case")]
+pub enum Fault {
+    /// The proposer creates a game with a wrong output root.
+    InvalidOutputRoot,
+    /// The proposer waits before submitting a generated proof.
+    DelayProof { secs: u64 },
+    /// The proposer discards a generated proof, failing the proving task.
+    DropProof,
+    /// A transaction fails before it is sent.
+    FailTransaction { action: TxAction },
+    /// A state sync waits before reading from the RPCs.
+    RpcLag { secs: u64 },
+    /// A proposer state sync reads L1 state from `depth` blocks behind the head, as a lagging
+    /// L1 RPC would serve it.
+    StaleL1Reads { depth: u64 },
+}
+
+/// A fault with how often it fires.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FaultRule {
+    #[serde(flatten)]
+    pub fault: Fault,
+    /// Chance of firing each time the fault could be injected.
+    #[serde(default = "default_probability")]
+    pub probability: f64,
+    /// Maximum number of times the fault fires. Unlimited if unset.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+fn default_probability() -> f64 {
+    1.0
+}
+
+/// The faults to inject, as read from a scenario file.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChaosScenario {
+    /// Seed for the probability rolls, so that runs can be reproduced. Random if unset.
+    #[serde(default)]
+    pub seed: Option<u64>,
+    pub faults: Vec<FaultRule>,
+}
+
+#[cfg(feature = "chaos")]
+struct Injector {
+    rules: Vec<(FaultRule, AtomicU32)>,
+    rng: Mutex<StdRng>,
+}
+
+/// Injects the faults of a scenario, shared between clones. Every hook is a no-op without a
+/// scenario.
+#[cfg(feature = "chaos")]
+#[derive(Clone, Default)]
+pub struct Chaos {
+    injector: Option<Arc<Injector>>,
+}
+
+#[cfg(feature = "chaos")]
+impl Chaos {
+    /// Load the scenario at `path`. Without a path, no faults are injected.
+    pub fn open(path: Option<&Path>) -> Result<Self> {
+        let Some(path) = path else { return Ok(Self::default()) };
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read chaos scenario {}", path.display()))?;
+        let scenario: ChaosScenario = serde_json::from_str(&json)
+            .with_context(|| format!("failed to parse chaos scenario {}", path.display()))?;
+        tracing::warn!(?scenario, "Fault injection enabled");
+        Ok(Self::new(scenario))
+    }
+
+    pub fn new(scenario: ChaosScenario) -> Self {
+        let rng = match scenario.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        let rules = scenario.faults.into_iter().map(|rule| (rule, AtomicU32::new(0))).collect();
+        Self { injector: Some(Arc::new(Injector { rules, rng: Mutex::new(rng) })) }
+    }
+
+    /// The output root to propose: `root`, or a wrong one if `InvalidOutputRoot` fires.
+    pub fn output_root(&self, root: B256) -> B256 {
+        match self.inject(|fault| matches!(fault, Fault::InvalidOutputRoot).then_some(())) {
+            Some(()) => keccak256(root),
+            None => root,
+        }
+    }
+
+    /// Wait before submitting a proof if `DelayProof` fires.
+    pub async fn delay_proof(&self) {
+        let secs = self.inject(|fault| match fault {
+            Fault::DelayProof { secs } => Some(*secs),
+            _ => None,
+        });
+        if let Some(secs) = secs {
+            tokio::time::sleep(Duration::from_secs(secs)).await;
+        }
+    }
+
+    /// Fail if `DropProof` fires.
+    pub fn drop_proof(&self) -> Result<()> {
+        if self.inject(|fault| matches!(fault, Fault::DropProof).then_some(())).is_some() {
+            bail!("chaos: dropped the generated proof");
+        }
+        Ok(())
+    }
+
+    /// Fail if `FailTransaction` fires for `action`.
+    pub fn fail_transaction(&self, action: TxAction) -> Result<()> {
+        let injected = self.inject(|fault| match fault {
+            Fault::FailTransaction { action: target } => (*target == action).then_some(()),
+            _ => None,
+        });
+        if injected.is_some() {
+            bail!("chaos: failed the {action:?} transaction");
+        }
+        Ok(())
+    }
+
+    /// Wait before a state sync if `RpcLag` fires.
+    pub async fn rpc_lag(&self) {
+        let secs = self.inject(|fault| match fault {
+            Fault::RpcLag { secs } => Some(*secs),
+            _ => None,
+        });
+        if let Some(secs) = secs {
+            tokio::time::sleep(Duration::from_secs(secs)).await;
+        }
+    }
+
+    /// The number of L1 blocks a state sync reads behind the head, if `StaleL1Reads` fires, or 0.
+    pub fn stale_l1_depth(&self) -> u64 {
+        self.inject(|fault| match fault {
+            Fault::StaleL1Reads { depth } => Some(*depth),
+            _ => None,
+        })
+        .unwrap_or(0)
+    }
+
+    /// Fire the first rule `select` applies to, if its limit isn't reached and the probability
+    /// roll passes.
+    fn inject<T>(&self, select: impl Fn(&Fault) -> Option<T>) -> Option<T> {
+        let injector = self.injector.as_ref()?;
+        for (rule, fired) in &injector.rules {
+            let Some(value) = select(&rule.fault) else { continue };
+            if rule.limit.is_some_and(|limit| fired.load(Ordering::Relaxed) >= limit) {
+                continue;
+            }
+            let roll: f64 = injector.rng.lock().expect("chaos rng lock poisoned").random();
+            if roll >= rule.probability {
+                continue;
+            }
+
+            fired.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(fault = ?rule.fault, "Injecting fault");
+            return Some(value);
+        }
+        None
+    }
+}
+
+/// Without the `chaos` feature, every hook is a no-op.
+#[cfg(not(feature = "chaos"))]
+#[derive(Clone, Default)]
+pub struct Chaos;
+
+#[cfg(not(feature = "chaos"))]
+impl Chaos {
+    /// Fail if a scenario is configured, since faults can't be injected without the `chaos`
+    /// feature.
+    pub fn open(path: Option<&Path>) -> Result<Self> {
+        if path.is_some() {
+            bail!(
+                "a chaos scenario is configured, but fault injection requires the `chaos` feature"
+            );
+        }
+        Ok(Self)
+    }
+
+    #[inline]
+    pub fn output_root(&self, root: B256) -> B256 {
+        root
+    }
+
+    #[inline]
+    pub async fn delay_proof(&self) {}
+
+    #[inline]
+    pub fn drop_proof(&self) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    pub fn fail_transaction(&self, _action: TxAction) -> Result<()> {
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn rpc_lag(&self) {}
+
+    #[inline]
+    pub fn stale_l1_depth(&self) -> u64 {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(fault: Fault, probability: f64, limit: Option<u32>) -> FaultRule {
+        FaultRule { fault, probability, limit }
+    }
+
+    #[test]
+    fn test_parse_scenario() {
+        let json = r#"{
+            "seed": 7,
+            "faults": [
+                { "fault": "invalid_output_root", "limit": 1 },
+                { "fault": "fail_transaction", "action": "resolve", "probability": 0.5 },
+                { "fault": "stale_l1_reads", "depth": 3 }
+            ]
+        }"#;
+        let scenario: ChaosScenario = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            scenario,
+            ChaosScenario {
+                seed: Some(7),
+                faults: vec![
+                    rule(Fault::InvalidOutputRoot, 1.0, Some(1)),
+                    rule(Fault::FailTransaction { action: TxAction::Resolve }, 0.5, None),
+                    rule(Fault::StaleL1Reads { depth: 3 }, 1.0, None),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "chaos")]
+    fn test_fault_limit() {
+        let chaos = Chaos::new(ChaosScenario {
+            seed: Some(0),
+            faults: vec![rule(Fault::InvalidOutputRoot, 1.0, Some(1))],
+        });
+
+        let root = B256::repeat_byte(1);
+        assert_ne!(chaos.output_root(root), root);
+        assert_eq!(chaos.output_root(root), root);
+    }
+
+    #[test]
+    #[cfg(feature = "chaos")]
+    fn test_fail_transaction_matches_action() {
+        let chaos = Chaos::new(ChaosScenario {
+            seed: Some(0),
+            faults: vec![rule(Fault::FailTransaction { action: TxAction::Resolve }, 1.0, None)],
+        });
+
+        assert!(chaos.fail_transaction(TxAction::Create).is_ok());
+        assert!(chaos.fail_transaction(TxAction::Resolve).is_err());
+        assert_eq!(chaos.stale_l1_depth(), 0);
+    }
+
+    #[test]
+    fn test_no_scenario() {
+        let chaos = Chaos::open(None).unwrap();
+        let root = B256::repeat_byte(1);
+        assert_eq!(chaos.output_root(root), root);
+        assert!(chaos.drop_proof().is_ok());
+    }
+
+    #[test]
+    #[cfg(not(feature = "chaos"))]
+    fn test_scenario_requires_feature() {
+        assert!(Chaos::open(Some(Path::new("scenario.json"))).is_err());
+    }
+}
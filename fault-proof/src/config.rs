@@ -5,10 +5,16 @@ use std::{
     str::FromStr,
 };
 
-use alloy_primitives::Address;
+use alloy_primitives::{utils::parse_ether, Address, U256};
 use alloy_transport_http::reqwest::Url;
-use anyhow::{bail, Result};
-use op_succinct_host_utils::network::parse_fulfillment_strategy;
+use anyhow::{bail, Context, Result};
+use op_succinct_host_utils::{
+    network::parse_fulfillment_strategy,
+    quorum::{parse_endpoints, QuorumPolicy},
+    witness_generation::{
+        shared_preimage_cache::DEFAULT_SHARED_PREIMAGE_CACHE_BYTES, WitnessGenOptions,
+    },
+};
 use serde::{Deserialize, Serialize};
 use sp1_sdk::{network::FulfillmentStrategy, SP1ProofMode};
 
@@ -20,6 +26,12 @@ pub struct ProposerConfig {
     /// The L2 RPC URL.
     pub l2_rpc: Url,
 
+    /// L2 RPC URLs that have to agree with `l2_rpc` on output roots.
+    pub l2_quorum_rpcs: Vec<Url>,
+
+    /// How many L2 RPCs have to agree on an output root before a game is created.
+    pub output_root_quorum: QuorumPolicy,
+
     /// The address of the AnchorStateRegistry contract.
     pub anchor_state_registry_address: Address,
 
@@ -32,9 +44,18 @@ pub struct ProposerConfig {
     /// Whether to use fast finality mode.
     pub fast_finality_mode: bool,
 
-    /// The interval in blocks between proposing new games.
+    /// The interval in blocks between proposing new games. 0 disables the block interval, leaving
+    /// the time interval.
     pub proposal_interval_in_blocks: u64,
 
+    /// The maximum age in seconds of the latest proposed L2 block before the finalized head is
+    /// proposed, whether or not the block interval has elapsed.
+    pub proposal_interval_secs: Option<u64>,
+
+    /// How far in blocks the finalized head can get ahead of the latest proposed L2 block before
+    /// it is proposed immediately.
+    pub proposal_max_lag_blocks: Option<u64>,
+
     /// The interval in seconds between checking for new proposals and game resolution.
     /// During each interval, the proposer:
     /// 1. Checks the safe L2 head block number
@@ -73,12 +94,39 @@ pub struct ProposerConfig {
     /// on observed latency, and system resources before deviating from default.
     pub max_concurrent_range_proofs: NonZeroUsize,
 
+    /// The timeout and preimage limit of each range's witness generation. A range exceeding them
+    /// is split in half and proven as two ranges.
+    pub witness_gen_options: WitnessGenOptions,
+
+    /// The capacity in bytes of the preimage cache shared by the ranges of a game's proof.
+    pub shared_preimage_cache_bytes: usize,
+
     /// Configuration for proof provider operations.
     pub proof_provider: ProofProviderConfig,
 
     /// Optional path to backup file for persisting proposer state across restarts.
     pub backup_path: Option<PathBuf>,
 
+    /// Optional path to the ledger of bonds, gas and proving costs per game. A CSV export is
+    /// written alongside it.
+    pub ledger_path: Option<PathBuf>,
+
+    /// Optional path to a scenario of faults to inject, for testing. Requires the `chaos`
+    /// feature. See [crate::chaos].
+    pub chaos_scenario_path: Option<PathBuf>,
+
+    /// L1 balance (in wei) to keep for resolving games and claiming bonds. Game creation pauses
+    /// while posting the bond would leave the signer less than this. See [crate::balance].
+    pub l1_balance_reserve: U256,
+
+    /// Minimum PROVE balance (in wei) of the network requester. Game creation and proof requests
+    /// pause below it.
+    pub min_prove_balance: U256,
+
+    /// If set, a defense whose deadline is within this many seconds is still proven while the
+    /// PROVE balance is below `min_prove_balance`, since the bond is lost otherwise.
+    pub low_prove_balance_defense_window_secs: Option<u64>,
+
     /// Number of L1 blocks behind `latest` to pin reads during sync cycles.
     /// Provides a safety margin for load-balanced RPCs where backends may lag.
     /// Default: 0 (use latest).
@@ -91,6 +139,20 @@ pub struct ProposerConfig {
     /// the historical signer behavior; raise it (e.g. 180) on networks where mempool inclusion
     /// plus the configured confirmation depth needs more headroom.
     pub tx_confirmation_timeout: u64,
+
+    /// Optional path to a range ELF to switch to when the factory's game implementation is
+    /// updated to its vkey. Defaults to the embedded range ELF when only
+    /// `alternate_agg_elf_path` is set.
+    pub alternate_range_elf_path: Option<PathBuf>,
+
+    /// Optional path to an aggregation ELF to switch to when the factory's game implementation is
+    /// updated to its vkey. Defaults to the embedded aggregation ELF when only
+    /// `alternate_range_elf_path` is set.
+    pub alternate_agg_elf_path: Option<PathBuf>,
+
+    /// Set when running as a guardian, which defends other proposers' challenged games instead
+    /// of creating games. Only the guardian binary sets it.
+    pub guardian: Option<GuardianConfig>,
 }
 
 /// Helper function to parse a comma-separated list of addresses
@@ -115,9 +177,28 @@ fn parse_whitelist(whitelist_str: &str) -> Result<Option<Vec<Address>>> {
 
 impl ProposerConfig {
     pub fn from_env() -> Result<Self> {
+        let proposal_interval_in_blocks: u64 =
+            env::var("PROPOSAL_INTERVAL_IN_BLOCKS").unwrap_or("1800".to_string()).parse()?;
+        let proposal_interval_secs: Option<u64> =
+            env::var("PROPOSAL_INTERVAL_SECS").ok().map(|secs| secs.parse()).transpose()?;
+        if proposal_interval_in_blocks == 0 && proposal_interval_secs.is_none() {
+            bail!("PROPOSAL_INTERVAL_IN_BLOCKS is 0, so PROPOSAL_INTERVAL_SECS must be set");
+        }
+
+        let l2_quorum_rpcs: Vec<Url> =
+            parse_endpoints(&env::var("L2_QUORUM_RPCS").unwrap_or_default())?;
+        let output_root_quorum: QuorumPolicy =
+            env::var("OUTPUT_ROOT_QUORUM").unwrap_or("all".to_string()).parse()?;
+        // The quorum includes the primary L2_RPC.
+        output_root_quorum
+            .validate(l2_quorum_rpcs.len() + 1)
+            .context("Invalid OUTPUT_ROOT_QUORUM")?;
+
         Ok(Self {
             l1_rpc: env::var("L1_RPC")?.parse().expect("L1_RPC not set"),
             l2_rpc: env::var("L2_RPC")?.parse().expect("L2_RPC not set"),
+            l2_quorum_rpcs,
+            output_root_quorum,
             anchor_state_registry_address: env::var("ANCHOR_STATE_REGISTRY_ADDRESS")?
                 .parse()
                 .expect("ANCHOR_STATE_REGISTRY_ADDRESS not set"),
@@ -126,9 +207,12 @@ impl ProposerConfig {
             fast_finality_mode: env::var("FAST_FINALITY_MODE")
                 .unwrap_or("false".to_string())
                 .parse()?,
-            proposal_interval_in_blocks: env::var("PROPOSAL_INTERVAL_IN_BLOCKS")
-                .unwrap_or("1800".to_string())
-                .parse()?,
+            proposal_interval_in_blocks,
+            proposal_interval_secs,
+            proposal_max_lag_blocks: env::var("PROPOSAL_MAX_LAG_BLOCKS")
+                .ok()
+                .map(|max_lag| max_lag.parse())
+                .transpose()?,
             fetch_interval: env::var("FETCH_INTERVAL").unwrap_or("30".to_string()).parse()?,
             game_type: env::var("GAME_TYPE").expect("GAME_TYPE not set").parse()?,
             max_concurrent_defense_tasks: env::var("MAX_CONCURRENT_DEFENSE_TASKS")
@@ -150,14 +234,35 @@ impl ProposerConfig {
             max_concurrent_range_proofs: env::var("MAX_CONCURRENT_RANGE_PROOFS")
                 .unwrap_or("1".to_string())
                 .parse()?,
+            witness_gen_options: WitnessGenOptions::from_env()?,
+            shared_preimage_cache_bytes: env::var("SHARED_PREIMAGE_CACHE_BYTES")
+                .unwrap_or(DEFAULT_SHARED_PREIMAGE_CACHE_BYTES.to_string())
+                .parse()?,
             proof_provider: ProofProviderConfig::from_env()?,
             backup_path: env::var("BACKUP_PATH").ok().map(PathBuf::from),
+            ledger_path: env::var("LEDGER_PATH").ok().map(PathBuf::from),
+            chaos_scenario_path: env::var("CHAOS_SCENARIO_PATH").ok().map(PathBuf::from),
+            l1_balance_reserve: parse_ether(
+                &env::var("L1_BALANCE_RESERVE").unwrap_or("0.05".to_string()),
+            )?,
+            min_prove_balance: parse_ether(
+                &env::var("MIN_PROVE_BALANCE").unwrap_or("0".to_string()),
+            )?,
+            low_prove_balance_defense_window_secs: env::var(
+                "LOW_PROVE_BALANCE_DEFENSE_WINDOW_SECS",
+            )
+            .ok()
+            .map(|secs| secs.parse())
+            .transpose()?,
             sync_l1_confirmations: env::var("SYNC_L1_CONFIRMATIONS")
                 .unwrap_or("0".to_string())
                 .parse()?,
             tx_confirmation_timeout: env::var("TX_CONFIRMATION_TIMEOUT")
                 .unwrap_or("60".to_string())
                 .parse()?,
+            alternate_range_elf_path: env::var("ALTERNATE_RANGE_ELF_PATH").ok().map(PathBuf::from),
+            alternate_agg_elf_path: env::var("ALTERNATE_AGG_ELF_PATH").ok().map(PathBuf::from),
+            guardian: None,
         })
     }
 
@@ -166,11 +271,15 @@ impl ProposerConfig {
         tracing::info!(
             l1_rpc = %self.l1_rpc,
             l2_rpc = %self.l2_rpc,
+            l2_quorum_rpcs = ?self.l2_quorum_rpcs,
+            output_root_quorum = ?self.output_root_quorum,
             factory_address = %self.factory_address,
             mock_mode = self.mock_mode,
             fast_finality_mode = self.fast_finality_mode,
             game_type = self.game_type,
             proposal_interval_in_blocks = self.proposal_interval_in_blocks,
+            proposal_interval_secs = ?self.proposal_interval_secs,
+            proposal_max_lag_blocks = ?self.proposal_max_lag_blocks,
             fetch_interval = self.fetch_interval,
             max_concurrent_defense_tasks = self.max_concurrent_defense_tasks,
             safe_db_fallback = self.safe_db_fallback,
@@ -179,6 +288,9 @@ impl ProposerConfig {
             use_kms_requester = self.use_kms_requester,
             range_split_count = ?self.range_split_count,
             max_concurrent_range_proofs = ?self.max_concurrent_range_proofs,
+            witnessgen_timeout = ?self.witness_gen_options.timeout,
+            max_witness_preimage_bytes = ?self.witness_gen_options.max_preimage_bytes,
+            shared_preimage_cache_bytes = self.shared_preimage_cache_bytes,
             // Proof provider fields
             timeout = self.proof_provider.timeout,
             network_calls_timeout = self.proof_provider.network_calls_timeout,
@@ -194,13 +306,52 @@ impl ProposerConfig {
             min_auction_period = self.proof_provider.min_auction_period,
             whitelist = ?self.proof_provider.whitelist,
             backup_path = ?self.backup_path,
+            ledger_path = ?self.ledger_path,
+            chaos_scenario_path = ?self.chaos_scenario_path,
+            l1_balance_reserve = %self.l1_balance_reserve,
+            min_prove_balance = %self.min_prove_balance,
+            low_prove_balance_defense_window_secs = ?self.low_prove_balance_defense_window_secs,
             sync_l1_confirmations = self.sync_l1_confirmations,
             tx_confirmation_timeout = self.tx_confirmation_timeout,
+            alternate_range_elf_path = ?self.alternate_range_elf_path,
+            alternate_agg_elf_path = ?self.alternate_agg_elf_path,
+            guardian = ?self.guardian,
             "Proposer configuration loaded"
         );
     }
 }
 
+/// Limits of a guardian defending other proposers' games. See [crate::guardian].
+#[derive(Debug, Clone)]
+pub struct GuardianConfig {
+    /// Seconds after a challenge during which the game is left to its own proposer to defend.
+    pub grace_period: u64,
+
+    /// The largest L2 block range of a game the guardian proves. Unbounded if unset.
+    pub max_game_blocks: Option<u64>,
+
+    /// The maximum number of defenses started in any 24 hours. Unbounded if unset.
+    pub max_defenses_per_day: Option<u64>,
+}
+
+impl GuardianConfig {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            grace_period: env::var("GUARDIAN_GRACE_PERIOD")
+                .unwrap_or("3600".to_string()) // 1 hour
+                .parse()?,
+            max_game_blocks: env::var("GUARDIAN_MAX_GAME_BLOCKS")
+                .ok()
+                .map(|blocks| blocks.parse())
+                .transpose()?,
+            max_defenses_per_day: env::var("GUARDIAN_MAX_DEFENSES_PER_DAY")
+                .ok()
+                .map(|defenses| defenses.parse())
+                .transpose()?,
+        })
+    }
+}
+
 /// Configuration for proof provider operations (network calls, timeouts, limits).
 #[derive(Debug, Clone)]
 pub struct ProofProviderConfig {
@@ -300,6 +451,12 @@ pub struct ChallengerConfig {
     pub l1_rpc: Url,
     pub l2_rpc: Url,
 
+    /// L2 RPC URLs that have to agree with `l2_rpc` on output roots.
+    pub l2_quorum_rpcs: Vec<Url>,
+
+    /// How many L2 RPCs have to agree on an output root before a game is judged.
+    pub output_root_quorum: QuorumPolicy,
+
     /// The address of the AnchorStateRegistry contract.
     pub anchor_state_registry_address: Address,
 
@@ -326,13 +483,36 @@ pub struct ChallengerConfig {
     /// signer behavior; raise it (e.g. 180) on networks where mempool inclusion plus the
     /// configured confirmation depth needs more headroom.
     pub tx_confirmation_timeout: u64,
+
+    /// Optional path to the ledger of bonds and gas per game. A CSV export is written alongside
+    /// it.
+    pub ledger_path: Option<PathBuf>,
+
+    /// Optional path to a scenario of faults to inject, for testing. Requires the `chaos`
+    /// feature. See [crate::chaos].
+    pub chaos_scenario_path: Option<PathBuf>,
+
+    /// L1 balance (in wei) to keep for resolving games and claiming bonds. Challenging pauses
+    /// while posting the bond would leave the signer less than this. See [crate::balance].
+    pub l1_balance_reserve: U256,
 }
 
 impl ChallengerConfig {
     pub fn from_env() -> Result<Self> {
+        let l2_quorum_rpcs: Vec<Url> =
+            parse_endpoints(&env::var("L2_QUORUM_RPCS").unwrap_or_default())?;
+        let output_root_quorum: QuorumPolicy =
+            env::var("OUTPUT_ROOT_QUORUM").unwrap_or("all".to_string()).parse()?;
+        // The quorum includes the primary L2_RPC.
+        output_root_quorum
+            .validate(l2_quorum_rpcs.len() + 1)
+            .context("Invalid OUTPUT_ROOT_QUORUM")?;
+
         Ok(Self {
             l1_rpc: env::var("L1_RPC")?.parse().expect("L1_RPC not set"),
             l2_rpc: env::var("L2_RPC")?.parse().expect("L2_RPC not set"),
+            l2_quorum_rpcs,
+            output_root_quorum,
             anchor_state_registry_address: env::var("ANCHOR_STATE_REGISTRY_ADDRESS")?
                 .parse()
                 .expect("ANCHOR_STATE_REGISTRY_ADDRESS not set"),
@@ -348,6 +528,11 @@ impl ChallengerConfig {
             tx_confirmation_timeout: env::var("TX_CONFIRMATION_TIMEOUT")
                 .unwrap_or("60".to_string())
                 .parse()?,
+            ledger_path: env::var("LEDGER_PATH").ok().map(PathBuf::from),
+            chaos_scenario_path: env::var("CHAOS_SCENARIO_PATH").ok().map(PathBuf::from),
+            l1_balance_reserve: parse_ether(
+                &env::var("L1_BALANCE_RESERVE").unwrap_or("0.05".to_string()),
+            )?,
         })
     }
 
@@ -356,6 +541,8 @@ impl ChallengerConfig {
         tracing::info!(
             l1_rpc = %self.l1_rpc,
             l2_rpc = %self.l2_rpc,
+            l2_quorum_rpcs = ?self.l2_quorum_rpcs,
+            output_root_quorum = ?self.output_root_quorum,
             anchor_state_registry_address = %self.anchor_state_registry_address,
             factory_address = %self.factory_address,
             game_type = self.game_type,
@@ -363,6 +550,9 @@ impl ChallengerConfig {
             metrics_port = self.metrics_port,
             malicious_challenge_percentage = self.malicious_challenge_percentage,
             tx_confirmation_timeout = self.tx_confirmation_timeout,
+            ledger_path = ?self.ledger_path,
+            chaos_scenario_path = ?self.chaos_scenario_path,
+            l1_balance_reserve = %self.l1_balance_reserve,
             "Challenger configuration loaded"
         );
     }
@@ -16,6 +16,9 @@ sol! {
         /// @notice Emitted when a new game implementation added to the factory
         event ImplementationSet(address indexed impl, GameType indexed gameType);
 
+        /// @notice Emitted when a game type's initialization bond is updated
+        event InitBondUpdated(GameType indexed gameType, uint256 indexed newBond);
+
         /// @notice `gameImpls` is a mapping that maps `GameType`s to their respective
         ///         `IDisputeGame` implementations.
         mapping(GameType => IDisputeGame) public gameImpls;
@@ -112,6 +115,9 @@ sol! {
         /// @notice Returns the challenger bond amount.
         function challengerBond() external view returns (uint256 challengerBond_);
 
+        /// @notice Returns the access manager contract.
+        function accessManager() external view returns (address accessManager_);
+
         /// @notice Returns the aggregation verification key.
         function aggregationVkey() external view returns (bytes32 aggregationVkey_);
 
@@ -137,6 +143,9 @@ sol! {
     #[allow(missing_docs)]
     #[sol(rpc)]
     contract AnchorStateRegistry {
+        /// @notice Emitted when a dispute game is blacklisted.
+        event DisputeGameBlacklisted(IDisputeGame indexed disputeGame);
+
         /// @notice Returns the current anchor root.
         function getAnchorRoot() public view returns (Hash, uint256);
 
@@ -148,6 +157,37 @@ sol! {
 
         /// @notice Returns the respected game type.
         function respectedGameType() external view returns (GameType);
+
+        /// @notice Games created at or before this timestamp are retired.
+        function retirementTimestamp() external view returns (uint64);
+
+        /// @notice Returns whether a game is blacklisted.
+        function isGameBlacklisted(IDisputeGame _game) public view returns (bool);
+    }
+
+    #[allow(missing_docs)]
+    #[sol(rpc)]
+    contract AccessManager {
+        /// @notice Tracks whitelisted proposers.
+        mapping(address => bool) public proposers;
+
+        /// @notice Tracks whitelisted challengers.
+        mapping(address => bool) public challengers;
+
+        /// @notice The timeout (in seconds) after which permissionless proposing is allowed.
+        function FALLBACK_TIMEOUT() external view returns (uint256);
+
+        /// @notice Returns the last proposal timestamp.
+        function getLastProposalTimestamp() public view returns (uint256);
+
+        /// @notice Returns whether proposal fallback timeout has elapsed.
+        function isProposalPermissionlessMode() public view returns (bool);
+
+        /// @notice Checks if an address is allowed to propose.
+        function isAllowedProposer(address _proposer) external view returns (bool allowed_);
+
+        /// @notice Checks if an address is allowed to challenge.
+        function isAllowedChallenger(address _challenger) external view returns (bool allowed_);
     }
 
     #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,175 @@
+//! Guardian mode: defending other proposers' games.
+//!
+//! `prove()` is permissionless, so a valid game that is challenged while its proposer is offline
+//! can be proven by anyone, who then earns the challenger's bond. A guardian runs the proposer
+//! without creating games and defends challenged games whose claim matches the locally computed
+//! output root. Invalid games never enter the proposer's cache, so only valid games are defended.
+//!
+//! The guardian gives a game's own proposer a grace period to defend it first, and caps how large
+//! and how many games it proves to bound its proving cost.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use alloy_primitives::Address;
+
+use crate::config::GuardianConfig;
+
+/// The window over which [GuardianConfig::max_defenses_per_day] is counted.
+const BUDGET_WINDOW_SECS: u64 = 24 * 60 * 60;
+
+/// Whether the guardian should defend a challenged game.
+#[derive(Debug, PartialEq)]
+pub enum GuardianDecision {
+    Defend,
+    /// The game's proposer may still defend it.
+    Wait {
+        remaining_secs: u64,
+    },
+    /// The game spans more blocks than the guardian proves.
+    TooLarge {
+        blocks: u64,
+    },
+    /// The daily defense budget is spent.
+    OverBudget,
+}
+
+/// The games defended within the budget window and when their defense started, shared between
+/// clones of the proposer.
+#[derive(Clone, Debug, Default)]
+pub struct GuardianBudget(Arc<Mutex<VecDeque<(Address, u64)>>>);
+
+impl GuardianBudget {
+    /// The defenses started in the window ending at `now`.
+    fn started(&self, now: u64) -> MutexGuard<'_, VecDeque<(Address, u64)>> {
+        let mut started = self.0.lock().expect("guardian budget lock poisoned");
+        while started.front().is_some_and(|&(_, at)| at + BUDGET_WINDOW_SECS <= now) {
+            started.pop_front();
+        }
+        started
+    }
+
+    /// The number of games defended in the window ending at `now`.
+    fn spent(&self, now: u64) -> u64 {
+        self.started(now).len() as u64
+    }
+
+    /// Whether the defense of `game` was started in the window ending at `now`.
+    fn is_defending(&self, game: Address, now: u64) -> bool {
+        self.started(now).iter().any(|&(defended, _)| defended == game)
+    }
+
+    /// Record a defense of `game` started at `now`. Retrying a defense of a game already in the
+    /// window doesn't count against the budget again.
+    pub fn record(&self, game: Address, now: u64) {
+        let mut started = self.started(now);
+        if !started.iter().any(|&(defended, _)| defended == game) {
+            started.push_back((game, now));
+        }
+    }
+}
+
+impl GuardianConfig {
+    /// Decide whether to defend `game`, spanning `blocks` L2 blocks and challenged at
+    /// `challenged_at`. The grace period is cut short when `deadline_at_risk`, so that waiting for
+    /// the game's proposer doesn't leave too little time to prove it.
+    pub fn decide(
+        &self,
+        game: Address,
+        now: u64,
+        challenged_at: u64,
+        deadline_at_risk: bool,
+        blocks: u64,
+        budget: &GuardianBudget,
+    ) -> GuardianDecision {
+        if self.max_game_blocks.is_some_and(|max| blocks > max) {
+            return GuardianDecision::TooLarge { blocks };
+        }
+
+        let grace_end = challenged_at.saturating_add(self.grace_period);
+        if now < grace_end && !deadline_at_risk {
+            return GuardianDecision::Wait { remaining_secs: grace_end - now };
+        }
+
+        if self.max_defenses_per_day.is_some_and(|max| budget.spent(now) >= max) &&
+            !budget.is_defending(game, now)
+        {
+            return GuardianDecision::OverBudget;
+        }
+
+        GuardianDecision::Defend
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const HOUR: u64 = 3600;
+
+    fn config() -> GuardianConfig {
+        GuardianConfig {
+            grace_period: HOUR,
+            max_game_blocks: Some(1000),
+            max_defenses_per_day: Some(1),
+        }
+    }
+
+    #[rstest]
+    #[case::after_grace(2 * HOUR, false, 500, GuardianDecision::Defend)]
+    #[case::in_grace(HOUR / 2, false, 500, GuardianDecision::Wait { remaining_secs: HOUR / 2 })]
+    #[case::in_grace_at_risk(HOUR / 2, true, 500, GuardianDecision::Defend)]
+    #[case::too_large(2 * HOUR, false, 1001, GuardianDecision::TooLarge { blocks: 1001 })]
+    fn test_guardian_decision(
+        #[case] now: u64,
+        #[case] deadline_at_risk: bool,
+        #[case] blocks: u64,
+        #[case] expected: GuardianDecision,
+    ) {
+        let decision = config().decide(
+            Address::ZERO,
+            now,
+            0,
+            deadline_at_risk,
+            blocks,
+            &GuardianBudget::default(),
+        );
+        assert_eq!(decision, expected);
+    }
+
+    const GAME: Address = Address::repeat_byte(1);
+    const OTHER_GAME: Address = Address::repeat_byte(2);
+
+    #[test]
+    fn test_guardian_budget_window() {
+        let budget = GuardianBudget::default();
+        budget.record(GAME, 2 * HOUR);
+
+        assert_eq!(
+            config().decide(OTHER_GAME, 3 * HOUR, 0, false, 1, &budget),
+            GuardianDecision::OverBudget
+        );
+        assert_eq!(
+            config().decide(OTHER_GAME, 26 * HOUR, 0, false, 1, &budget),
+            GuardianDecision::Defend,
+            "the defense drops out of the budget a day after it started"
+        );
+    }
+
+    #[test]
+    fn test_guardian_budget_retries() {
+        let budget = GuardianBudget::default();
+        budget.record(GAME, 2 * HOUR);
+        budget.record(GAME, 3 * HOUR);
+
+        assert_eq!(budget.spent(3 * HOUR), 1, "a retried defense is counted once");
+        assert_eq!(
+            config().decide(GAME, 3 * HOUR, 0, false, 1, &budget),
+            GuardianDecision::Defend,
+            "a game already being defended can be retried over budget"
+        );
+    }
+}
@@ -1,22 +1,32 @@
+pub mod access;
 pub mod backup;
+pub mod balance;
 pub mod challenger;
+pub mod chaos;
 pub mod config;
 pub mod contract;
+pub mod guardian;
+pub mod pending_proofs;
 pub mod prometheus;
 pub mod proposer;
 pub mod prover;
+pub mod scheduler;
 
 use alloy_eips::{BlockId, BlockNumberOrTag};
 use alloy_primitives::{address, keccak256, Address, FixedBytes, B256, U256};
-use alloy_provider::{Provider, RootProvider};
+use alloy_provider::{Provider, ProviderBuilder, RootProvider};
 use alloy_rpc_types_eth::Block;
 use alloy_sol_types::SolValue;
-use anyhow::{bail, Result};
+use alloy_transport_http::reqwest::Url;
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use futures::future::join_all;
 use op_alloy_network::Optimism;
 use op_alloy_rpc_types::Transaction;
+use op_succinct_host_utils::quorum::QuorumPolicy;
 
 use crate::contract::{
+    AnchorStateRegistry::AnchorStateRegistryInstance,
     DisputeGameFactory::DisputeGameFactoryInstance, GameStatus, IDisputeGame, L2Output,
     OPSuccinctFaultDisputeGame,
 };
@@ -113,6 +123,67 @@ impl L2ProviderTrait for L2Provider {
     }
 }
 
+/// L2 providers backed by independent endpoints that have to agree on output roots, so that a
+/// single faulty L2 node can't make the proposer or challenger act on a wrong output root.
+///
+/// Block and storage reads go to the primary endpoint only.
+#[derive(Clone)]
+pub struct L2QuorumProvider {
+    /// The primary endpoint first, then the quorum endpoints.
+    providers: Vec<(Url, L2Provider)>,
+    policy: QuorumPolicy,
+}
+
+impl L2QuorumProvider {
+    pub fn new(primary: Url, quorum: &[Url], policy: QuorumPolicy) -> Self {
+        let providers = std::iter::once(primary)
+            .chain(quorum.iter().cloned())
+            .map(|url| (url.clone(), ProviderBuilder::default().connect_http(url)))
+            .collect();
+        Self { providers, policy }
+    }
+
+    fn primary(&self) -> &L2Provider {
+        &self.providers[0].1
+    }
+}
+
+#[async_trait]
+impl L2ProviderTrait for L2QuorumProvider {
+    async fn get_l2_block_by_number(
+        &self,
+        block_number: BlockNumberOrTag,
+    ) -> Result<Block<Transaction>> {
+        self.primary().get_l2_block_by_number(block_number).await
+    }
+
+    async fn get_l2_storage_root(
+        &self,
+        address: Address,
+        block_number: BlockNumberOrTag,
+    ) -> Result<B256> {
+        self.primary().get_l2_storage_root(address, block_number).await
+    }
+
+    /// Compute the output root at a given L2 block number on every endpoint, returning it if
+    /// enough endpoints agree under the quorum policy.
+    async fn compute_output_root_at_block(&self, l2_block_number: U256) -> Result<FixedBytes<32>> {
+        if self.providers.len() == 1 {
+            return self.primary().compute_output_root_at_block(l2_block_number).await;
+        }
+
+        let responses = join_all(self.providers.iter().map(|(url, provider)| async move {
+            (url.to_string(), provider.compute_output_root_at_block(l2_block_number).await)
+        }))
+        .await;
+
+        let output_root = self.policy.reach(responses, |root| *root).with_context(|| {
+            format!("L2 endpoints disagree on the output root at block {l2_block_number}")
+        })?;
+        Ok(output_root)
+    }
+}
+
 #[async_trait]
 pub trait FactoryTrait<P>
 where
@@ -211,6 +282,99 @@ where
         GameStatus::CHALLENGER_WINS)
 }
 
+/// Why the anchor state registry no longer considers a game proper. Improper games never become
+/// the anchor and can't be the parent of a new game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImproperGame {
+    /// The guardian blacklisted the game.
+    Blacklisted,
+    /// The game was created at or before the registry's retirement timestamp.
+    Retired,
+}
+
+/// The anchor state registry settings the guardian can change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegistrySettings {
+    /// Only games of this type can become the anchor or the parent of a new game.
+    pub respected_game_type: u32,
+    /// Games created at or before this timestamp are retired.
+    pub retirement_timestamp: u64,
+}
+
+impl RegistrySettings {
+    /// Read the settings from the registry at `pinned_block`.
+    pub async fn fetch<P>(
+        registry: &AnchorStateRegistryInstance<P>,
+        pinned_block: BlockId,
+    ) -> Result<Self>
+    where
+        P: Provider + Clone,
+    {
+        let respected_game_type = registry.respectedGameType().block(pinned_block).call().await?;
+        let retirement_timestamp =
+            registry.retirementTimestamp().block(pinned_block).call().await?;
+        Ok(Self { respected_game_type, retirement_timestamp })
+    }
+
+    /// Whether a game created at `created_at` is retired. Matches the registry's `isGameRetired`.
+    pub fn is_retired(&self, created_at: u64) -> bool {
+        created_at <= self.retirement_timestamp
+    }
+
+    /// How the settings changed from `previous`, for a service of `game_type`.
+    pub fn changes(&self, previous: Option<&Self>, game_type: u32) -> Vec<RegistryChange> {
+        let mut changes = Vec::new();
+
+        let respected = self.respected_game_type == game_type;
+        let was_respected = previous.map(|previous| previous.respected_game_type == game_type);
+        match (was_respected, respected) {
+            (Some(true) | None, false) => changes.push(RegistryChange::GameTypeNotRespected),
+            (Some(false), true) => changes.push(RegistryChange::GameTypeRespectedAgain),
+            _ => {}
+        }
+
+        if previous
+            .is_some_and(|previous| previous.retirement_timestamp != self.retirement_timestamp)
+        {
+            changes.push(RegistryChange::RetirementTimestampUpdated);
+        }
+
+        changes
+    }
+
+    /// Log how the settings changed from `previous`, for a service of `game_type`.
+    pub fn log_changes(&self, previous: Option<&Self>, game_type: u32) {
+        for change in self.changes(previous, game_type) {
+            match change {
+                RegistryChange::GameTypeNotRespected => tracing::warn!(
+                    game_type,
+                    respected_game_type = self.respected_game_type,
+                    "Game type is not respected by the anchor state registry"
+                ),
+                RegistryChange::GameTypeRespectedAgain => tracing::info!(
+                    game_type,
+                    "Game type is respected by the anchor state registry again"
+                ),
+                RegistryChange::RetirementTimestampUpdated => tracing::warn!(
+                    retirement_timestamp = self.retirement_timestamp,
+                    "Retirement timestamp updated, games created at or before it are retired"
+                ),
+            }
+        }
+    }
+}
+
+/// A change of the registry settings that the proposer and challenger log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistryChange {
+    /// The registry stopped respecting the service's game type, or never did.
+    GameTypeNotRespected,
+    /// The registry respects the service's game type again.
+    GameTypeRespectedAgain,
+    /// The guardian moved the retirement timestamp.
+    RetirementTimestampUpdated,
+}
+
 /// Prefix used for transaction revert errors.
 pub const TX_REVERTED_PREFIX: &str = "transaction reverted:";
 
@@ -225,3 +389,60 @@ impl TxErrorExt for anyhow::Error {
         self.to_string().starts_with(TX_REVERTED_PREFIX)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{RegistryChange, RegistrySettings};
+    use rstest::rstest;
+
+    const GAME_TYPE: u32 = 42;
+
+    fn settings(respected_game_type: u32, retirement_timestamp: u64) -> RegistrySettings {
+        RegistrySettings { respected_game_type, retirement_timestamp }
+    }
+
+    #[rstest]
+    #[case::first_sync_respected(None, settings(GAME_TYPE, 0), vec![])]
+    #[case::first_sync_not_respected(
+        None,
+        settings(1, 0),
+        vec![RegistryChange::GameTypeNotRespected]
+    )]
+    #[case::unchanged(Some(settings(GAME_TYPE, 10)), settings(GAME_TYPE, 10), vec![])]
+    #[case::no_longer_respected(
+        Some(settings(GAME_TYPE, 10)),
+        settings(1, 10),
+        vec![RegistryChange::GameTypeNotRespected]
+    )]
+    #[case::still_not_respected(Some(settings(1, 10)), settings(2, 10), vec![])]
+    #[case::respected_again(
+        Some(settings(1, 10)),
+        settings(GAME_TYPE, 10),
+        vec![RegistryChange::GameTypeRespectedAgain]
+    )]
+    #[case::retirement_updated(
+        Some(settings(GAME_TYPE, 10)),
+        settings(GAME_TYPE, 20),
+        vec![RegistryChange::RetirementTimestampUpdated]
+    )]
+    #[case::both_changed(
+        Some(settings(GAME_TYPE, 10)),
+        settings(1, 20),
+        vec![RegistryChange::GameTypeNotRespected, RegistryChange::RetirementTimestampUpdated]
+    )]
+    fn test_registry_changes(
+        #[case] previous: Option<RegistrySettings>,
+        #[case] current: RegistrySettings,
+        #[case] expected: Vec<RegistryChange>,
+    ) {
+        assert_eq!(current.changes(previous.as_ref(), GAME_TYPE), expected);
+    }
+
+    #[rstest]
+    #[case::before(9, true)]
+    #[case::at(10, true)]
+    #[case::after(11, false)]
+    fn test_is_retired(#[case] created_at: u64, #[case] expected: bool) {
+        assert_eq!(settings(GAME_TYPE, 10).is_retired(created_at), expected);
+    }
+}
@@ -0,0 +1,296 @@
+//! Outstanding proof requests, persisted with the proposer backup.
+//!
+//! A network or cluster proof request is only known to the task waiting on it. If the proposer
+//! restarts while the proof is being fulfilled, the request is lost, the proof is requested and
+//! paid for again, and the game's deadline keeps running. Recording each request by game and
+//! range lets the restarted proposer re-attach to it instead. Requests are persisted as soon as
+//! they are recorded, rather than waiting for the next periodic backup.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use alloy_primitives::Address;
+use op_succinct_proof_utils::ClusterProofHandleJson;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::prover::ProofId;
+
+/// The kind of a proof requested while proving a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PendingProofType {
+    Range,
+    Aggregation,
+}
+
+/// Identifies a proof requested while proving a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PendingProofKey {
+    pub game_address: Address,
+    pub proof_type: PendingProofType,
+    /// The proven block range. An aggregation proof covers the game's full range.
+    pub start_block: u64,
+    pub end_block: u64,
+}
+
+impl PendingProofKey {
+    pub fn range(game_address: Address, start_block: u64, end_block: u64) -> Self {
+        Self { game_address, proof_type: PendingProofType::Range, start_block, end_block }
+    }
+
+    pub fn aggregation(game_address: Address, start_block: u64, end_block: u64) -> Self {
+        Self { game_address, proof_type: PendingProofType::Aggregation, start_block, end_block }
+    }
+}
+
+/// The handle of a submitted proof request.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PendingProofRequest {
+    /// A request to the SP1 prover network.
+    Network { proof_id: ProofId },
+    /// A request to a self-hosted cluster.
+    Cluster { handle: ClusterProofHandleJson },
+}
+
+/// A proof request that hasn't been fulfilled yet.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingProof {
+    pub key: PendingProofKey,
+    pub request: PendingProofRequest,
+    /// Unix timestamp of the request, so the proving timeout keeps counting across restarts.
+    pub requested_at: u64,
+}
+
+/// The outstanding proof requests, shared between clones of a proof provider.
+#[derive(Clone, Debug, Default)]
+pub struct PendingProofs {
+    proofs: Arc<Mutex<HashMap<PendingProofKey, PendingProof>>>,
+    /// Notified whenever a request is recorded.
+    recorded: Arc<Notify>,
+}
+
+impl PendingProofs {
+    /// The outstanding request for `key`, if any.
+    pub fn get(&self, key: &PendingProofKey) -> Option<PendingProof> {
+        self.proofs.lock().expect("pending proofs lock poisoned").get(key).cloned()
+    }
+
+    /// Record an outstanding request, replacing any earlier request for the same key.
+    pub fn insert(&self, proof: PendingProof) {
+        self.proofs.lock().expect("pending proofs lock poisoned").insert(proof.key, proof);
+        self.recorded.notify_one();
+    }
+
+    /// Wait until a request is recorded. A request recorded while nobody is waiting completes
+    /// the next wait.
+    pub async fn recorded(&self) {
+        self.recorded.notified().await;
+    }
+
+    /// Forget the request for `key`, once it was fulfilled or failed.
+    pub fn remove(&self, key: &PendingProofKey) {
+        self.proofs.lock().expect("pending proofs lock poisoned").remove(key);
+    }
+
+    /// Whether any request for `game_address` is outstanding, i.e. proving the game resumes
+    /// earlier work.
+    pub fn has_game(&self, game_address: Address) -> bool {
+        self.proofs
+            .lock()
+            .expect("pending proofs lock poisoned")
+            .keys()
+            .any(|key| key.game_address == game_address)
+    }
+
+    /// Forget and return the outstanding requests for `game_address`.
+    pub fn remove_game(&self, game_address: Address) -> Vec<PendingProof> {
+        let mut pending = self.proofs.lock().expect("pending proofs lock poisoned");
+        let keys: Vec<_> =
+            pending.keys().filter(|key| key.game_address == game_address).copied().collect();
+        keys.into_iter().filter_map(|key| pending.remove(&key)).collect()
+    }
+
+    /// The outstanding network request for `key` and when it was made, if any. A request of
+    /// another kind can't be resumed from the network, so it is dropped.
+    pub fn network_request(&self, key: &PendingProofKey) -> Option<(ProofId, u64)> {
+        let pending = self.get(key)?;
+        match pending.request {
+            PendingProofRequest::Network { proof_id } => Some((proof_id, pending.requested_at)),
+            PendingProofRequest::Cluster { .. } => {
+                tracing::warn!(
+                    ?key,
+                    "Outstanding proof request is not a network request, dropping it"
+                );
+                self.remove(key);
+                None
+            }
+        }
+    }
+
+    /// The outstanding cluster request for `key` and when it was made, if any. A request of
+    /// another kind can't be resumed from the cluster, so it is dropped.
+    pub fn cluster_request(&self, key: &PendingProofKey) -> Option<(ClusterProofHandleJson, u64)> {
+        let pending = self.get(key)?;
+        match pending.request {
+            PendingProofRequest::Cluster { handle } => Some((handle, pending.requested_at)),
+            PendingProofRequest::Network { .. } => {
+                tracing::warn!(
+                    ?key,
+                    "Outstanding proof request is not a cluster request, dropping it"
+                );
+                self.remove(key);
+                None
+            }
+        }
+    }
+
+    /// The outstanding requests for the games `is_tracked` accepts. Requests for other games
+    /// (resolved or dropped from the cache) are no longer worth re-attaching to.
+    pub fn snapshot(&self, is_tracked: impl Fn(Address) -> bool) -> Vec<PendingProof> {
+        let pending = self.proofs.lock().expect("pending proofs lock poisoned");
+        let mut proofs: Vec<_> =
+            pending.values().filter(|proof| is_tracked(proof.key.game_address)).cloned().collect();
+        proofs.sort_by_key(|proof| proof.requested_at);
+        proofs
+    }
+
+    /// Restore the outstanding requests from a backup.
+    pub fn restore(&self, proofs: Vec<PendingProof>) {
+        let mut pending = self.proofs.lock().expect("pending proofs lock poisoned");
+        pending.extend(proofs.into_iter().map(|proof| (proof.key, proof)));
+    }
+
+    pub fn len(&self) -> usize {
+        self.proofs.lock().expect("pending proofs lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    #[test]
+    fn test_remove_game_requests() {
+        let pending = PendingProofs::default();
+        let game = Address::repeat_byte(1);
+        let other = Address::repeat_byte(2);
+        for (game_address, start_block) in [(game, 0), (game, 10), (other, 0)] {
+            pending.insert(PendingProof {
+                key: PendingProofKey::range(game_address, start_block, start_block + 10),
+                request: PendingProofRequest::Network { proof_id: B256::ZERO },
+                requested_at: 0,
+            });
+        }
+
+        assert!(pending.has_game(game));
+        assert_eq!(pending.remove_game(game).len(), 2);
+        assert!(!pending.has_game(game));
+        assert!(pending.has_game(other));
+        assert_eq!(pending.len(), 1);
+    }
+
+    #[test]
+    fn test_pending_proofs_snapshot_and_restore() {
+        let tracked = Address::repeat_byte(1);
+        let resolved = Address::repeat_byte(2);
+        let pending = PendingProofs::default();
+        pending.insert(PendingProof {
+            key: PendingProofKey::range(tracked, 0, 10),
+            request: PendingProofRequest::Network { proof_id: B256::repeat_byte(3) },
+            requested_at: 100,
+        });
+        pending.insert(PendingProof {
+            key: PendingProofKey::aggregation(tracked, 0, 10),
+            request: PendingProofRequest::Cluster {
+                handle: ClusterProofHandleJson {
+                    proof_id: "proof".to_string(),
+                    proof_output_id: "output".to_string(),
+                },
+            },
+            requested_at: 200,
+        });
+        pending.insert(PendingProof {
+            key: PendingProofKey::range(resolved, 10, 20),
+            request: PendingProofRequest::Network { proof_id: B256::repeat_byte(4) },
+            requested_at: 50,
+        });
+
+        let snapshot = pending.snapshot(|game| game == tracked);
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].requested_at, 100);
+
+        // The snapshot survives a JSON round trip, as in the proposer backup.
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored = PendingProofs::default();
+        restored.restore(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.len(), 2);
+        assert_eq!(
+            restored.get(&PendingProofKey::aggregation(tracked, 0, 10)),
+            Some(snapshot[1].clone())
+        );
+
+        restored.remove(&PendingProofKey::range(tracked, 0, 10));
+        assert_eq!(restored.get(&PendingProofKey::range(tracked, 0, 10)), None);
+        assert_eq!(restored.len(), 1);
+    }
+
+    #[test]
+    fn test_resume_requests_by_provider_kind() {
+        let game = Address::repeat_byte(1);
+        let (network_key, cluster_key) =
+            (PendingProofKey::range(game, 0, 10), PendingProofKey::range(game, 10, 20));
+        let handle = ClusterProofHandleJson {
+            proof_id: "proof".to_string(),
+            proof_output_id: "output".to_string(),
+        };
+        let pending = PendingProofs::default();
+        pending.insert(PendingProof {
+            key: network_key,
+            request: PendingProofRequest::Network { proof_id: B256::repeat_byte(3) },
+            requested_at: 100,
+        });
+        pending.insert(PendingProof {
+            key: cluster_key,
+            request: PendingProofRequest::Cluster { handle: handle.clone() },
+            requested_at: 200,
+        });
+
+        assert_eq!(pending.network_request(&network_key), Some((B256::repeat_byte(3), 100)));
+        assert_eq!(pending.cluster_request(&cluster_key), Some((handle, 200)));
+        assert_eq!(pending.len(), 2);
+
+        // A request of the other kind is dropped instead of resumed.
+        assert_eq!(pending.cluster_request(&network_key), None);
+        assert_eq!(pending.network_request(&cluster_key), None);
+        assert!(pending.is_empty());
+        assert_eq!(pending.network_request(&network_key), None);
+    }
+
+    #[tokio::test]
+    async fn test_recorded_request_notifies() {
+        let pending = PendingProofs::default();
+        pending.insert(PendingProof {
+            key: PendingProofKey::range(Address::repeat_byte(1), 0, 10),
+            request: PendingProofRequest::Network { proof_id: B256::repeat_byte(3) },
+            requested_at: 100,
+        });
+
+        // The request was recorded before the wait, so the wait completes right away.
+        tokio::time::timeout(std::time::Duration::from_secs(1), pending.recorded())
+            .await
+            .expect("recorded request should notify");
+
+        // Restoring from a backup doesn't notify.
+        pending.restore(pending.snapshot(|_| true));
+        assert!(tokio::time::timeout(std::time::Duration::from_millis(10), pending.recorded())
+            .await
+            .is_err());
+    }
+}
@@ -61,6 +61,115 @@ pub enum ProposerGauge {
         message = "Duration of last successful game proving in seconds"
     )]
     ProvingDurationSeconds,
+    #[strum(
+        serialize = "op_succinct_fp_estimated_proving_duration_seconds",
+        message = "Estimated duration of game proving in seconds, averaged over past provings"
+    )]
+    EstimatedProvingDurationSeconds,
+    #[strum(
+        serialize = "op_succinct_fp_fast_finality_proving_preempted",
+        message = "Total number of fast finality proving tasks preempted for an at-risk defense"
+    )]
+    FastFinalityProvingPreempted,
+    #[strum(
+        serialize = "op_succinct_fp_game_implementation_compatible",
+        message = "Whether the factory's game implementation matches one of the proposer's key sets (1) or not (0)"
+    )]
+    GameImplementationCompatible,
+    #[strum(
+        serialize = "op_succinct_fp_game_implementation_reloads",
+        message = "Total number of game implementation reloads triggered by factory events"
+    )]
+    GameImplementationReloads,
+    #[strum(
+        serialize = "op_succinct_fp_proof_keys_swapped",
+        message = "Total number of times the proposer switched to a different set of proof keys"
+    )]
+    ProofKeysSwapped,
+    // Balance metrics
+    #[strum(
+        serialize = "op_succinct_fp_signer_balance_eth",
+        message = "L1 balance of the proposer's signer in ETH"
+    )]
+    SignerBalanceEth,
+    #[strum(
+        serialize = "op_succinct_fp_prove_balance",
+        message = "PROVE balance of the prover network requester"
+    )]
+    ProveBalance,
+    #[strum(
+        serialize = "op_succinct_fp_balance_paused",
+        message = "Whether game creation is paused for low balance (1) or not (0)"
+    )]
+    BalancePaused,
+    #[strum(
+        serialize = "op_succinct_fp_proving_balance_paused",
+        message = "Whether proof requests are paused for low PROVE balance (1) or not (0)"
+    )]
+    ProvingBalancePaused,
+    // Permission metrics
+    #[strum(
+        serialize = "op_succinct_fp_proposer_allowlisted",
+        message = "Whether the proposer is on the access manager's allowlist (1) or not (0)"
+    )]
+    ProposerAllowlisted,
+    #[strum(
+        serialize = "op_succinct_fp_permissionless_proposing",
+        message = "Whether anyone can propose after the fallback timeout (1) or not (0)"
+    )]
+    PermissionlessProposing,
+    #[strum(
+        serialize = "op_succinct_fp_seconds_until_permissionless",
+        message = "Seconds until the fallback timeout opens permissionless proposing (0 when open)"
+    )]
+    SecondsUntilPermissionless,
+    // Registry metrics
+    #[strum(
+        serialize = "op_succinct_fp_game_type_respected",
+        message = "Whether the anchor state registry respects the proposer's game type (1) or not (0)"
+    )]
+    GameTypeRespected,
+    #[strum(
+        serialize = "op_succinct_fp_retirement_timestamp",
+        message = "Anchor state registry retirement timestamp"
+    )]
+    RetirementTimestamp,
+    #[strum(
+        serialize = "op_succinct_fp_games_blacklisted",
+        message = "Total number of cached games found blacklisted"
+    )]
+    GamesBlacklisted,
+    #[strum(
+        serialize = "op_succinct_fp_games_retired",
+        message = "Total number of cached games found retired"
+    )]
+    GamesRetired,
+    // Ledger metrics
+    #[strum(
+        serialize = "op_succinct_fp_ledger_bonds_posted_eth",
+        message = "Total bonds posted by the proposer in ETH"
+    )]
+    LedgerBondsPostedEth,
+    #[strum(
+        serialize = "op_succinct_fp_ledger_credit_claimed_eth",
+        message = "Total credit claimed by the proposer in ETH"
+    )]
+    LedgerCreditClaimedEth,
+    #[strum(
+        serialize = "op_succinct_fp_ledger_gas_spent_eth",
+        message = "Total L1 gas spent on the proposer's game transactions in ETH"
+    )]
+    LedgerGasSpentEth,
+    #[strum(
+        serialize = "op_succinct_fp_ledger_net_eth",
+        message = "Net profit or loss of the proposer's games in ETH, excluding proving costs"
+    )]
+    LedgerNetEth,
+    #[strum(
+        serialize = "op_succinct_fp_ledger_proof_cost_prove",
+        message = "Total proving cost of the proposer's games in PROVE"
+    )]
+    LedgerProofCostProve,
     // Error metrics
     #[strum(
         serialize = "op_succinct_fp_game_creation_error",
@@ -117,11 +226,21 @@ pub enum ProposerGauge {
         message = "Total number of deadline approaching warnings"
     )]
     DeadlineApproaching,
+    #[strum(
+        serialize = "op_succinct_fp_deadline_expected_miss",
+        message = "Total number of warnings that proving is expected to finish after the game deadline"
+    )]
+    DeadlineExpectedMiss,
     #[strum(
         serialize = "op_succinct_fp_network_call_timeout",
         message = "Total number of network call timeouts"
     )]
     NetworkCallTimeout,
+    #[strum(
+        serialize = "op_succinct_fp_output_root_disagreement",
+        message = "Total number of times the L2 endpoints disagreed on an output root"
+    )]
+    OutputRootDisagreement,
     // Backup metrics
     #[strum(
         serialize = "op_succinct_fp_backup_save_success",
@@ -166,6 +285,60 @@ pub enum ChallengerGauge {
         message = "Total number of games that bonds were claimed by the challenger"
     )]
     GamesBondsClaimed,
+    // Balance metrics
+    #[strum(
+        serialize = "op_succinct_fp_challenger_signer_balance_eth",
+        message = "L1 balance of the challenger's signer in ETH"
+    )]
+    SignerBalanceEth,
+    #[strum(
+        serialize = "op_succinct_fp_challenger_balance_paused",
+        message = "Whether challenging is paused for low balance (1) or not (0)"
+    )]
+    BalancePaused,
+    // Permission metrics
+    #[strum(
+        serialize = "op_succinct_fp_challenger_allowed",
+        message = "Whether the access manager allows the challenger to challenge (1) or not (0)"
+    )]
+    ChallengerAllowed,
+    // Registry metrics
+    #[strum(
+        serialize = "op_succinct_fp_challenger_game_type_respected",
+        message = "Whether the anchor state registry respects the challenger's game type (1) or not (0)"
+    )]
+    GameTypeRespected,
+    #[strum(
+        serialize = "op_succinct_fp_challenger_retirement_timestamp",
+        message = "Anchor state registry retirement timestamp"
+    )]
+    RetirementTimestamp,
+    #[strum(
+        serialize = "op_succinct_fp_challenger_improper_games_skipped",
+        message = "Number of invalid games not challenged because they are blacklisted or retired"
+    )]
+    ImproperGamesSkipped,
+    // Ledger metrics
+    #[strum(
+        serialize = "op_succinct_fp_challenger_ledger_bonds_posted_eth",
+        message = "Total bonds posted by the challenger in ETH"
+    )]
+    LedgerBondsPostedEth,
+    #[strum(
+        serialize = "op_succinct_fp_challenger_ledger_credit_claimed_eth",
+        message = "Total credit claimed by the challenger in ETH"
+    )]
+    LedgerCreditClaimedEth,
+    #[strum(
+        serialize = "op_succinct_fp_challenger_ledger_gas_spent_eth",
+        message = "Total L1 gas spent on the challenger's game transactions in ETH"
+    )]
+    LedgerGasSpentEth,
+    #[strum(
+        serialize = "op_succinct_fp_challenger_ledger_net_eth",
+        message = "Net profit or loss of the challenger's games in ETH"
+    )]
+    LedgerNetEth,
     // Error metrics
     #[strum(
         serialize = "op_succinct_fp_challenger_game_challenging_error",
@@ -182,6 +355,11 @@ pub enum ChallengerGauge {
         message = "Total number of bond claiming errors encountered by the challenger"
     )]
     BondClaimingError,
+    #[strum(
+        serialize = "op_succinct_fp_challenger_output_root_disagreement",
+        message = "Total number of times the L2 endpoints disagreed on an output root"
+    )]
+    OutputRootDisagreement,
 }
 
 impl MetricsGauge for ChallengerGauge {}
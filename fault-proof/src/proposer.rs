@@ -2,8 +2,8 @@ use std::{
     collections::{HashMap, HashSet},
     path::Path,
     sync::{
-        atomic::{AtomicU64, Ordering},
-        Arc, OnceLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
     },
     time::Duration,
 };
@@ -13,43 +13,63 @@ use tempfile::NamedTempFile;
 use alloy_eips::{BlockId, BlockNumberOrTag};
 use alloy_primitives::{Address, FixedBytes, TxHash, B256, U256};
 use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types_eth::TransactionReceipt;
 use alloy_sol_types::{SolEvent, SolValue};
 use anyhow::{bail, Context, Result};
 use futures::stream::{self, StreamExt, TryStreamExt};
-use op_succinct_client_utils::boot::{hash_rollup_config, BootInfoStruct};
-use op_succinct_elfs::AGGREGATION_ELF;
+use op_succinct_client_utils::boot::hash_rollup_config;
+use op_succinct_elfs::{load_elf, AGGREGATION_ELF};
 use op_succinct_host_utils::{
     fetcher::OPSuccinctDataFetcher,
     get_agg_proof_stdin,
     host::OPSuccinctHost,
+    ledger::{wei_to_eth, Ledger, LedgerTxKind},
     metrics::MetricsGauge,
     network::{determine_network_mode, get_network_signer},
-    witness_generation::WitnessGenerator,
+    quorum::QuorumError,
+    read_range_outputs,
+    witness_generation::{
+        SharedPreimageCache, WitnessGenOptions, WitnessGenerationError, WitnessGenerator,
+    },
+};
+use op_succinct_proof_utils::{
+    cluster_setup_program_keys, get_range_elf_embedded, is_cluster_mode, ClusterProofConfig,
 };
-use op_succinct_proof_utils::{cluster_setup_keys, get_range_elf_embedded, is_cluster_mode};
 use op_succinct_signer_utils::SignerLock;
 use sp1_sdk::{
-    Elf, HashableKey, Prover, ProverClient, ProvingKey, SP1ProofWithPublicValues, SP1Stdin,
+    Elf, HashableKey, NetworkProver, Prover, ProverClient, ProvingKey, SP1ProofWithPublicValues,
+    SP1Stdin,
 };
 use tokio::{
-    sync::{Mutex, RwLock, Semaphore},
+    sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore},
     time,
 };
 
 use crate::{
+    access::ProposerPermission,
     backup::ProposerBackup,
+    balance::BalanceMonitor,
+    chaos::{Chaos, TxAction},
     config::ProposerConfig,
     contract::{
+        AccessManager,
         AnchorStateRegistry::AnchorStateRegistryInstance,
         DisputeGameFactory::{DisputeGameCreated, DisputeGameFactoryInstance},
         GameStatus, OPSuccinctFaultDisputeGame, ProposalStatus,
     },
+    guardian::{GuardianBudget, GuardianDecision},
     is_parent_resolved,
+    pending_proofs::{PendingProof, PendingProofKey},
     prometheus::ProposerGauge,
     prover::{
         ClusterProofProvider, MockProofProvider, NetworkProofProvider, ProofKeys, ProofProvider,
     },
-    FactoryTrait, L1Provider, L2Provider, L2ProviderTrait, TxErrorExt, TX_REVERTED_PREFIX,
+    scheduler::{
+        check_deadline_risk, plan_proving, DeadlineRisk, ProvingCandidate, ProvingCapacity,
+        ProvingTimeEstimator, RunningProving,
+    },
+    FactoryTrait, ImproperGame, L1Provider, L2ProviderTrait, L2QuorumProvider, RegistrySettings,
+    TxErrorExt, TX_REVERTED_PREFIX,
 };
 
 /// Max allowed time (secs) between a game's deadline and the anchor game's deadline.
@@ -61,6 +81,9 @@ use crate::{
 /// ensuring all actionable games are included under normal conditions.
 pub const MAX_GAME_DEADLINE_LAG: u64 = 60 * 60 * 24 * 14; // 14 days
 
+/// Max number of L1 blocks scanned per loop for game implementation changes.
+pub const GAME_IMPL_WATCH_MAX_BLOCK_RANGE: u64 = 10_000;
+
 /// Divisor for calculating deadline warning threshold.
 ///
 /// When less than `max_duration / DEADLINE_WARNING_DIVISOR` time remains,
@@ -76,6 +99,9 @@ pub type TaskHandle = tokio::task::JoinHandle<Result<()>>;
 /// Type alias for a map of task IDs to their join handles and associated task info
 pub type TaskMap = HashMap<TaskId, (TaskHandle, TaskInfo)>;
 
+/// The start block, proof, instruction cycles and SP1 gas of a range proof.
+type RangeProofOutput = (u64, SP1ProofWithPublicValues, u64, u64);
+
 /// Information about a running task
 #[derive(Clone, Debug)]
 pub enum TaskInfo {
@@ -88,7 +114,7 @@ pub enum TaskInfo {
 /// Proposer identity information for version tracking and monitoring.
 /// This helps operators identify which ELF version is running and enables
 /// compatibility checks during hardfork transitions.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ProposerIdentity {
     /// Full version string with DA layer suffix (e.g., "3.4.1-celestia")
     pub version: String,
@@ -124,6 +150,27 @@ impl ProposerIdentity {
         Self { version, aggregation_vkey, range_vkey_commitment, rollup_config_hash }
     }
 
+    /// Creates the identity of a set of proof keys.
+    pub fn from_keys(keys: &ProofKeys, rollup_config_hash: B256) -> Self {
+        Self::new(
+            B256::from(keys.agg_vk.bytes32_raw()),
+            B256::from(keys.range_vk.hash_bytes()),
+            rollup_config_hash,
+        )
+    }
+
+    /// Returns true if the identity matches the given on-chain vkeys and rollup config hash.
+    pub fn matches(
+        &self,
+        aggregation_vkey: B256,
+        range_vkey_commitment: B256,
+        rollup_config_hash: B256,
+    ) -> bool {
+        self.aggregation_vkey == aggregation_vkey &&
+            self.range_vkey_commitment == range_vkey_commitment &&
+            self.rollup_config_hash == rollup_config_hash
+    }
+
     /// Logs the proposer identity at startup.
     pub fn log_startup_info(&self) {
         tracing::info!(
@@ -151,6 +198,8 @@ impl ProposerIdentity {
 pub struct Game {
     pub index: U256,
     pub address: Address,
+    pub creator: Address,
+    pub created_at: u64,
     pub parent_index: u32,
     pub l2_block: U256,
     pub status: GameStatus,
@@ -182,6 +231,8 @@ impl Game {
 /// - `cursor`: Highest factory index processed in the prior sync. Each incremental sync walks
 ///   backward from the latest index to this value, then sets it to the new latest index.
 /// - `games`: cached metadata for every tracked game keyed by index
+/// - `improper_games`: cached games the registry blacklisted or retired, refreshed every sync
+/// - `blacklisted_games`: addresses of cached games the registry blacklisted
 #[derive(Default)]
 struct ProposerState {
     anchor_game: Option<Game>,
@@ -189,6 +240,8 @@ struct ProposerState {
     canonical_head_l2_block: Option<U256>,
     cursor: Cursor,
     games: HashMap<U256, Game>,
+    improper_games: HashSet<U256>,
+    blacklisted_games: HashSet<Address>,
 }
 
 impl ProposerState {
@@ -214,6 +267,52 @@ impl ProposerState {
         reachable
     }
 
+    /// Returns the improper games and all their descendants.
+    ///
+    /// Improper games stay cached so that the proposer still resolves them and claims their
+    /// bonds, but no game in their subtrees can become the canonical head, and so the parent of
+    /// a new game.
+    fn improper_subtrees(&self) -> HashSet<U256> {
+        self.improper_games.iter().flat_map(|&index| self.descendants_of(index)).collect()
+    }
+
+    /// Returns the game to build on.
+    ///
+    /// Canonical head is the game with the highest L2 block number. When an anchor game exists,
+    /// the canonical head is chosen from its descendants, unless a non-descendant has a higher L2
+    /// block number and an earlier lineage (parent is genesis or has a lower parent index than the
+    /// best descendant). Improper games and their subtrees are never chosen.
+    fn select_canonical_head(&self) -> Option<Game> {
+        let improper = self.improper_subtrees();
+        let candidates = || self.games.values().filter(|g| !improper.contains(&g.index));
+
+        match self.anchor_game.as_ref() {
+            None => candidates().max_by_key(|g| g.l2_block).cloned(),
+            Some(anchor_game) => {
+                let reachable = self.descendants_of(anchor_game.index);
+
+                // Best among descendants
+                let anchor_head = candidates()
+                    .filter(|g| reachable.contains(&g.index))
+                    .max_by_key(|g| g.l2_block);
+
+                // Check non-descendants for override (higher block with genesis or lower parent)
+                let override_head = anchor_head.and_then(|anchor| {
+                    candidates()
+                        .filter(|g| !reachable.contains(&g.index))
+                        .filter(|g| {
+                            g.l2_block > anchor.l2_block &&
+                                (g.parent_index == u32::MAX ||
+                                    g.parent_index < anchor.parent_index)
+                        })
+                        .max_by_key(|g| g.l2_block)
+                });
+
+                override_head.or(anchor_head).cloned()
+            }
+        }
+    }
+
     /// Remove a game subtree from the cache.
     ///
     /// Used when a game is invalidated (i.e., `CHALLENGER_WINS`) and its entire subtree must be
@@ -245,6 +344,26 @@ pub struct ContractParams {
     pub max_prove_duration: u64,
 }
 
+/// The proposer's view of the factory's game implementation for its game type.
+///
+/// Loaded at startup and reloaded whenever the factory emits `ImplementationSet` or
+/// `InitBondUpdated` for the game type.
+#[derive(Clone, Debug)]
+pub struct GameImplementation {
+    /// Address of the implementation new games are cloned from.
+    pub address: Address,
+    /// Bond required to create a game.
+    pub init_bond: U256,
+    /// Timing parameters of the implementation.
+    pub contract_params: ContractParams,
+    /// Access manager deciding who can create and challenge games.
+    pub access_manager: Address,
+    /// Whether new games can be created against the implementation: its vkeys and rollup config
+    /// hash match one of the proposer's key sets, and it uses the configured anchor state
+    /// registry.
+    pub compatible: bool,
+}
+
 #[derive(Clone)]
 pub struct OPSuccinctProposer<P, H: OPSuccinctHost>
 where
@@ -252,15 +371,21 @@ where
     H: OPSuccinctHost + Clone + Send + Sync + 'static,
 {
     pub config: ProposerConfig,
-    contract_params: OnceLock<ContractParams>,
+    game_impl: Arc<std::sync::RwLock<Option<GameImplementation>>>,
+    /// Last L1 block scanned for game implementation changes.
+    game_impl_watch_block: Arc<AtomicU64>,
+    /// Last L1 block scanned for blacklisted games, or 0 before the first sync.
+    blacklist_watch_block: Arc<AtomicU64>,
     pub signer: SignerLock,
     pub l1_provider: L1Provider,
-    pub l2_provider: L2Provider,
+    pub l2_provider: L2QuorumProvider,
     pub anchor_state_registry: Arc<AnchorStateRegistryInstance<P>>,
     pub factory: Arc<DisputeGameFactoryInstance<P>>,
-    init_bond: OnceLock<U256>,
     pub safe_db_fallback: bool,
     prover: ProofProvider,
+    /// Proof keys the proposer can switch to, with their identities. The first entry is the
+    /// embedded ELFs.
+    key_sets: Arc<Vec<(ProposerIdentity, ProofKeys)>>,
     fetcher: Arc<OPSuccinctDataFetcher>,
     host: Arc<H>,
     tasks: Arc<Mutex<TaskMap>>,
@@ -268,8 +393,8 @@ where
     state: Arc<RwLock<ProposerState>>,
     backup_semaphore: Arc<Semaphore>,
     /// Proposer identity with version and vkey information for monitoring and compatibility
-    /// checks.
-    pub identity: ProposerIdentity,
+    /// checks. Follows the active key set.
+    identity: Arc<std::sync::RwLock<ProposerIdentity>>,
     /// L1 block number used in the last successful sync cycle. Sync is skipped when the
     /// pinned block hasn't advanced past this value.
     last_synced_l1_block: Arc<AtomicU64>,
@@ -279,6 +404,23 @@ where
     /// Address of the most recently created game. Used to precisely identify
     /// the guarded game for CHALLENGER_WINS subtree removal.
     last_created_game_address: Arc<Mutex<Address>>,
+    /// Estimated game proving time, learned from past provings.
+    proving_time_estimator: ProvingTimeEstimator,
+    /// Set while a challenged game's deadline is at risk. Fast finality proving is preempted for
+    /// the defense and no new games are created until it is on track again.
+    defense_at_risk: Arc<AtomicBool>,
+    /// Defenses started in guardian mode, counted against the daily limit.
+    guardian_budget: GuardianBudget,
+    /// Bonds, gas and proving costs per game.
+    ledger: Ledger,
+    /// Latest signer and requester balances, gating game creation.
+    balance_monitor: BalanceMonitor,
+    /// Latest permission to propose on the access manager, gating game creation.
+    proposer_permission: Arc<std::sync::RwLock<Option<ProposerPermission>>>,
+    /// Anchor state registry settings as of the last sync.
+    registry_settings: Arc<std::sync::RwLock<Option<RegistrySettings>>>,
+    /// Faults to inject, for testing.
+    chaos: Chaos,
 }
 
 impl<P, H> OPSuccinctProposer<P, H>
@@ -303,9 +445,8 @@ where
             "mock and cluster modes are mutually exclusive — set only one of SP1_PROVER=cluster or mock_mode=true"
         );
 
-        let (range_pk, range_vk, agg_pk, agg_vk, network_prover, network_mode) = if is_cluster {
-            let (range_pk, range_vk, agg_pk, agg_vk) = cluster_setup_keys().await?;
-            (range_pk, range_vk, agg_pk, agg_vk, None, None)
+        let (network_prover, network_mode) = if is_cluster {
+            (None, None)
         } else {
             let network_signer = get_network_signer(config.use_kms_requester).await?;
             let nm = determine_network_mode(
@@ -315,34 +456,53 @@ where
             let np = Arc::new(
                 ProverClient::builder().network_for(nm).signer(network_signer).build().await,
             );
-            let range_pk = np.setup(Elf::Static(get_range_elf_embedded())).await?;
-            let range_vk = range_pk.verifying_key().clone();
-            let agg_pk = np.setup(Elf::Static(AGGREGATION_ELF)).await?;
-            let agg_vk = agg_pk.verifying_key().clone();
-            (range_pk, range_vk, agg_pk, agg_vk, Some(np), Some(nm))
+            (Some(np), Some(nm))
         };
 
-        let aggregation_vkey = B256::from(agg_vk.bytes32_raw());
-        let range_vkey_commitment = B256::from(range_vk.hash_bytes());
+        let keys = Self::setup_proof_keys(
+            network_prover.as_deref(),
+            get_range_elf_embedded(),
+            AGGREGATION_ELF,
+        )
+        .await?;
+
         let rollup_config_hash = hash_rollup_config(
             fetcher.rollup_config.as_ref().context("rollup_config required for identity")?,
         );
 
-        let identity =
-            ProposerIdentity::new(aggregation_vkey, range_vkey_commitment, rollup_config_hash);
+        let identity = ProposerIdentity::from_keys(&keys, rollup_config_hash);
         identity.log_startup_info();
 
-        let keys = ProofKeys {
-            range_pk: Arc::new(range_pk),
-            range_vk: Arc::new(range_vk),
-            agg_pk: Arc::new(agg_pk),
-            agg_vk: Arc::new(agg_vk),
-        };
+        let mut key_sets = vec![(identity.clone(), keys.clone())];
+        if config.alternate_range_elf_path.is_some() || config.alternate_agg_elf_path.is_some() {
+            let range_elf = match &config.alternate_range_elf_path {
+                Some(path) => load_elf(path)
+                    .with_context(|| format!("failed to read ELF {}", path.display()))?,
+                None => get_range_elf_embedded(),
+            };
+            let agg_elf = match &config.alternate_agg_elf_path {
+                Some(path) => load_elf(path)
+                    .with_context(|| format!("failed to read ELF {}", path.display()))?,
+                None => AGGREGATION_ELF,
+            };
+            let alternate_keys =
+                Self::setup_proof_keys(network_prover.as_deref(), range_elf, agg_elf).await?;
+            let alternate_identity =
+                ProposerIdentity::from_keys(&alternate_keys, rollup_config_hash);
+            tracing::info!(
+                aggregation_vkey = %alternate_identity.aggregation_vkey,
+                range_vkey_commitment = %alternate_identity.range_vkey_commitment,
+                "Alternate proof keys loaded"
+            );
+            key_sets.push((alternate_identity, alternate_keys));
+        }
 
+        let ledger = Ledger::open(config.ledger_path.clone())?;
         let prover = if is_cluster {
             ProofProvider::Cluster(ClusterProofProvider::new(
                 keys.clone(),
                 config.proof_provider.clone(),
+                Arc::new(ClusterProofConfig::from_env().await?),
             ))
         } else if config.mock_mode {
             ProofProvider::Mock(MockProofProvider::new(
@@ -350,7 +510,6 @@ where
                     .ok_or_else(|| anyhow::anyhow!("network_prover must be set in mock mode"))?,
                 keys.clone(),
                 config.proof_provider.clone(),
-                AGGREGATION_ELF,
             ))
         } else {
             ProofProvider::Network(NetworkProofProvider::new(
@@ -360,38 +519,114 @@ where
                 config.proof_provider.clone(),
                 network_mode
                     .ok_or_else(|| anyhow::anyhow!("network_mode must be set in network mode"))?,
+                ledger.clone(),
             ))
         };
 
         let l1_provider = ProviderBuilder::default().connect_http(config.l1_rpc.clone());
-        let l2_provider = ProviderBuilder::default().connect_http(config.l2_rpc.clone());
+        let l2_provider = L2QuorumProvider::new(
+            config.l2_rpc.clone(),
+            &config.l2_quorum_rpcs,
+            config.output_root_quorum,
+        );
 
         let initial_state = ProposerState::default();
+        let chaos = Chaos::open(config.chaos_scenario_path.as_deref())?;
 
         Ok(Self {
             config: config.clone(),
-            contract_params: OnceLock::new(),
+            game_impl: Arc::new(std::sync::RwLock::new(None)),
+            game_impl_watch_block: Arc::new(AtomicU64::new(0)),
+            blacklist_watch_block: Arc::new(AtomicU64::new(0)),
             signer,
             l1_provider,
             l2_provider,
             anchor_state_registry: Arc::new(anchor_state_registry),
             factory: Arc::new(factory.clone()),
-            init_bond: OnceLock::new(),
             safe_db_fallback: config.safe_db_fallback,
             prover,
+            key_sets: Arc::new(key_sets),
             fetcher: fetcher.clone(),
             host,
             tasks: Arc::new(Mutex::new(HashMap::new())),
             next_task_id: Arc::new(AtomicU64::new(1)),
             state: Arc::new(RwLock::new(initial_state)),
             backup_semaphore: Arc::new(Semaphore::new(1)),
-            identity,
+            identity: Arc::new(std::sync::RwLock::new(identity)),
             last_synced_l1_block: Arc::new(AtomicU64::new(0)),
             last_created_game_l2_block: Arc::new(AtomicU64::new(0)),
             last_created_game_address: Arc::new(Mutex::new(Address::ZERO)),
+            proving_time_estimator: ProvingTimeEstimator::default(),
+            defense_at_risk: Arc::new(AtomicBool::new(false)),
+            guardian_budget: GuardianBudget::default(),
+            ledger,
+            balance_monitor: BalanceMonitor::default(),
+            proposer_permission: Arc::new(std::sync::RwLock::new(None)),
+            registry_settings: Arc::new(std::sync::RwLock::new(None)),
+            chaos,
+        })
+    }
+
+    /// Set up proving keys for a range and aggregation ELF pair, via the network prover if one is
+    /// provided and locally for cluster mode otherwise.
+    async fn setup_proof_keys(
+        network_prover: Option<&NetworkProver>,
+        range_elf: &'static [u8],
+        agg_elf: &'static [u8],
+    ) -> Result<ProofKeys> {
+        let (range_pk, range_vk, agg_pk, agg_vk) = match network_prover {
+            Some(np) => {
+                let range_pk = np.setup(Elf::Static(range_elf)).await?;
+                let range_vk = range_pk.verifying_key().clone();
+                let agg_pk = np.setup(Elf::Static(agg_elf)).await?;
+                let agg_vk = agg_pk.verifying_key().clone();
+                (range_pk, range_vk, agg_pk, agg_vk)
+            }
+            None => cluster_setup_program_keys(range_elf, agg_elf).await?,
+        };
+
+        Ok(ProofKeys {
+            range_elf,
+            agg_elf,
+            range_pk: Arc::new(range_pk),
+            range_vk: Arc::new(range_vk),
+            agg_pk: Arc::new(agg_pk),
+            agg_vk: Arc::new(agg_vk),
         })
     }
 
+    /// Returns the identity of the active key set.
+    pub fn identity(&self) -> ProposerIdentity {
+        self.identity.read().expect("identity lock poisoned").clone()
+    }
+
+    /// Returns true if the game was created with the vkeys of any of the proposer's key sets, so
+    /// that games created before a key swap are still resolved and their bonds claimed.
+    fn owns_game(&self, game: &Game) -> bool {
+        self.key_sets.iter().any(|(identity, _)| game.is_owned(identity))
+    }
+
+    /// Computes the output root at `l2_block`, raising an alert if the L2 endpoints disagree on it.
+    async fn compute_output_root(&self, l2_block: U256) -> Result<B256> {
+        let result = self.l2_provider.compute_output_root_at_block(l2_block).await;
+        if let Err(e) = &result {
+            if QuorumError::is_disagreement(e) {
+                tracing::error!(%l2_block, ?e, "L2 endpoints disagree on the output root");
+                ProposerGauge::OutputRootDisagreement.increment(1.0);
+            }
+        }
+        result
+    }
+
+    /// Returns the last loaded game implementation.
+    pub fn game_implementation(&self) -> Result<GameImplementation> {
+        self.game_impl
+            .read()
+            .expect("game implementation lock poisoned")
+            .clone()
+            .context("game implementation must be loaded via startup_validations")
+    }
+
     /// Returns a lightweight snapshot of the proposer's cached state.
     pub async fn state_snapshot(&self) -> ProposerStateSnapshot {
         let state = self.state.read().await;
@@ -432,17 +667,32 @@ where
     /// Runs the proposer indefinitely.
     pub async fn run(self: Arc<Self>) -> Result<()> {
         tracing::info!("OP Succinct Proposer running...");
+        if self.config.guardian.is_some() {
+            tracing::info!("Guardian mode: defending challenged games without creating games");
+        }
 
         self.try_init().await?;
 
+        // Publish the totals of a ledger restored from disk, and save changes from now on.
+        self.update_ledger_metrics();
+        self.ledger.spawn_writer();
+
         // Spawn a dedicated task for continuous metrics collection
         self.spawn_metrics_collector();
 
+        // Persist proof requests as soon as they are made.
+        self.spawn_pending_proof_backup();
+
         let mut interval = time::interval(Duration::from_secs(self.config.fetch_interval));
         loop {
             interval.tick().await;
 
-            // 1. Synchronize cached dispute state before scheduling work.
+            // 1. Pick up game implementation changes before scheduling work.
+            if let Err(e) = self.watch_game_implementation().await {
+                tracing::warn!("Failed to check for game implementation changes: {:?}", e);
+            }
+
+            // 2. Synchronize cached dispute state before scheduling work.
             if let Err(e) = self.sync_state().await {
                 tracing::warn!("Failed to sync proposer state: {:?}", e);
                 continue
@@ -450,17 +700,25 @@ where
 
             self.backup().await;
 
-            // 2. Handle completed tasks.
+            // 3. Handle completed tasks.
             if let Err(e) = self.handle_completed_tasks().await {
                 tracing::warn!("Failed to handle completed tasks: {:?}", e);
             }
 
-            // 3. Spawn new work (non-blocking).
+            // 4. Refresh the balances and the permission that gate game creation.
+            if let Err(e) = self.refresh_balances().await {
+                tracing::warn!("Failed to refresh balances: {:?}", e);
+            }
+            if let Err(e) = self.refresh_proposer_permission().await {
+                tracing::warn!("Failed to refresh proposer permission: {:?}", e);
+            }
+
+            // 5. Spawn new work (non-blocking).
             if let Err(e) = self.spawn_pending_operations().await {
                 tracing::warn!("Failed to spawn pending operations: {:?}", e);
             }
 
-            // 4. Log task statistics.
+            // 6. Log task statistics.
             self.log_task_stats().await;
         }
     }
@@ -493,13 +751,17 @@ where
 
     /// Validates startup and initializes state.
     pub async fn validate_and_init(&self) -> Result<()> {
-        let (anchor_l2_block, init_bond, contract_params) = self.startup_validations().await?;
-        self.init_state(anchor_l2_block, init_bond, contract_params).await
+        // Game implementation changes are watched from the block the implementation is loaded at.
+        let l1_block = self.l1_provider.get_block_number().await?;
+        let (anchor_l2_block, game_impl) = self.startup_validations().await?;
+        self.init_state(anchor_l2_block, game_impl).await?;
+        self.game_impl_watch_block.store(l1_block, Ordering::Relaxed);
+        Ok(())
     }
 
     /// Runs one-time startup validations before the proposer begins normal operations.
-    /// Returns the validated anchor L2 block number, init bond, and contract params.
-    async fn startup_validations(&self) -> Result<(U256, U256, ContractParams)> {
+    /// Returns the validated anchor L2 block number and the loaded game implementation.
+    async fn startup_validations(&self) -> Result<(U256, GameImplementation)> {
         // Validate anchor state registry matches factory's game implementation.
         Self::validate_anchor_state_registry(
             &self.anchor_state_registry,
@@ -518,33 +780,23 @@ where
         )
         .await?;
 
-        // Fetch init bond.
-        let init_bond = self.factory.fetch_init_bond(self.config.game_type).await?;
+        // Fetch init bond, contract params, and vkeys from the game implementation.
+        let game_impl = self.load_game_implementation().await?;
 
-        // Fetch contract params from game implementation.
-        let game_impl = self.factory.game_impl(self.config.game_type).await?;
-        let max_challenge_duration = game_impl.maxChallengeDuration().call().await?;
-        let max_prove_duration = game_impl.maxProveDuration().call().await?;
-        let contract_params = ContractParams { max_challenge_duration, max_prove_duration };
+        // Check the signer can propose, so that a missing allowlist entry shows up at startup
+        // rather than as reverted game creations.
+        if self.config.guardian.is_none() {
+            let permission = self.fetch_proposer_permission(game_impl.access_manager).await?;
+            self.set_proposer_permission(permission);
+        }
 
-        Ok((anchor_l2_block, init_bond, contract_params))
+        Ok((anchor_l2_block, game_impl))
     }
 
-    /// Initialize proposer state with the validated anchor L2 block, init bond, and contract
-    /// params.
-    async fn init_state(
-        &self,
-        anchor_l2_block: U256,
-        init_bond: U256,
-        contract_params: ContractParams,
-    ) -> Result<()> {
+    /// Initialize proposer state with the validated anchor L2 block and game implementation.
+    async fn init_state(&self, anchor_l2_block: U256, game_impl: GameImplementation) -> Result<()> {
         self.state.write().await.canonical_head_l2_block = Some(anchor_l2_block);
-        self.init_bond
-            .set(init_bond)
-            .map_err(|_| anyhow::anyhow!("init_bond must not already be set"))?;
-        self.contract_params
-            .set(contract_params)
-            .map_err(|_| anyhow::anyhow!("contract_params must not already be set"))?;
+        self.set_game_implementation(game_impl);
 
         // Validate backup path and restore state if available.
         if let Some(path) = &self.config.backup_path {
@@ -561,13 +813,23 @@ where
                 .with_context(|| format!("backup path is not writable: {:?}", path))?;
 
             // Restore state from backup if available.
-            if let Some((restored, last_created_l2, last_created_addr)) =
+            if let Some((restored, last_created_l2, last_created_addr, pending_proofs)) =
                 ProposerState::try_restore(path)
             {
                 // Restore the creation guard so duplicate-sibling protection survives restart.
                 self.last_created_game_l2_block.store(last_created_l2, Ordering::Relaxed);
                 *self.last_created_game_address.lock().await = last_created_addr;
 
+                // Restore the outstanding proof requests, so proving tasks re-attach to them
+                // instead of requesting the proofs again.
+                if let Some(pending) = self.prover.pending_proofs() {
+                    tracing::info!(
+                        pending_proofs = pending_proofs.len(),
+                        "Restored outstanding proof requests"
+                    );
+                    pending.restore(pending_proofs);
+                }
+
                 let mut state = self.state.write().await;
                 state.cursor = restored.cursor;
                 state.games = restored.games;
@@ -616,6 +878,147 @@ where
         Ok(())
     }
 
+    /// Reads the factory's current game implementation for the proposer's game type.
+    ///
+    /// If the implementation's vkeys match a key set other than the active one, the prover is
+    /// switched to that key set.
+    async fn load_game_implementation(&self) -> Result<GameImplementation> {
+        let game_type = self.config.game_type;
+        let game_impl = self.factory.game_impl(game_type).await?;
+        let init_bond = self.factory.fetch_init_bond(game_type).await?;
+        let max_challenge_duration = game_impl.maxChallengeDuration().call().await?;
+        let max_prove_duration = game_impl.maxProveDuration().call().await?;
+        let registry = game_impl.anchorStateRegistry().call().await?;
+        let access_manager = game_impl.accessManager().call().await?;
+        let on_chain_agg = B256::from(game_impl.aggregationVkey().call().await?.0);
+        let on_chain_range = B256::from(game_impl.rangeVkeyCommitment().call().await?.0);
+        let on_chain_rollup_hash = B256::from(game_impl.rollupConfigHash().call().await?.0);
+
+        let registry_matches = registry == *self.anchor_state_registry.address();
+        if !registry_matches {
+            tracing::warn!(
+                configured = %self.anchor_state_registry.address(),
+                game_impl_registry = %registry,
+                "Game implementation uses a different anchor state registry"
+            );
+        }
+
+        let keys_match =
+            self.activate_matching_keys(on_chain_agg, on_chain_range, on_chain_rollup_hash);
+        if !keys_match {
+            tracing::warn!(
+                aggregation_vkey = %on_chain_agg,
+                range_vkey_commitment = %on_chain_range,
+                rollup_config_hash = %on_chain_rollup_hash,
+                "No proof keys match the game implementation"
+            );
+        }
+
+        Ok(GameImplementation {
+            address: *game_impl.address(),
+            init_bond,
+            contract_params: ContractParams { max_challenge_duration, max_prove_duration },
+            access_manager,
+            compatible: registry_matches && keys_match,
+        })
+    }
+
+    /// Switches to the key set matching the given on-chain vkeys and rollup config hash.
+    ///
+    /// Returns false if no key set matches, in which case the active keys are kept.
+    fn activate_matching_keys(
+        &self,
+        aggregation_vkey: B256,
+        range_vkey_commitment: B256,
+        rollup_config_hash: B256,
+    ) -> bool {
+        let Some((identity, keys)) = find_key_set(
+            &self.key_sets,
+            aggregation_vkey,
+            range_vkey_commitment,
+            rollup_config_hash,
+        ) else {
+            return false;
+        };
+
+        let mut active = self.identity.write().expect("identity lock poisoned");
+        if *active != *identity {
+            tracing::info!(
+                aggregation_vkey = %identity.aggregation_vkey,
+                range_vkey_commitment = %identity.range_vkey_commitment,
+                "Switching proof keys to match the game implementation"
+            );
+            self.prover.set_keys(keys.clone());
+            *active = identity.clone();
+            ProposerGauge::ProofKeysSwapped.increment(1.0);
+        }
+        true
+    }
+
+    fn set_game_implementation(&self, game_impl: GameImplementation) {
+        ProposerGauge::GameImplementationCompatible.set(if game_impl.compatible {
+            1.0
+        } else {
+            0.0
+        });
+        *self.game_impl.write().expect("game implementation lock poisoned") = Some(game_impl);
+    }
+
+    /// Scans the factory for `ImplementationSet` and `InitBondUpdated` events for the proposer's
+    /// game type since the last scan, and reloads the game implementation if any are found.
+    ///
+    /// Game creation stays paused while the reloaded implementation is incompatible.
+    async fn watch_game_implementation(&self) -> Result<()> {
+        let latest_block = self.l1_provider.get_block_number().await?;
+        let Some((from_block, to_block)) =
+            game_impl_watch_range(self.game_impl_watch_block.load(Ordering::Relaxed), latest_block)
+        else {
+            return Ok(());
+        };
+
+        // gameType is the second indexed parameter of ImplementationSet and the first of
+        // InitBondUpdated.
+        let game_type = U256::from(self.config.game_type);
+        let implementation_set = self
+            .factory
+            .ImplementationSet_filter()
+            .topic2(game_type)
+            .from_block(from_block)
+            .to_block(to_block)
+            .query()
+            .await?;
+        let init_bond_updated = self
+            .factory
+            .InitBondUpdated_filter()
+            .topic1(game_type)
+            .from_block(from_block)
+            .to_block(to_block)
+            .query()
+            .await?;
+
+        if !implementation_set.is_empty() || !init_bond_updated.is_empty() {
+            tracing::info!(
+                from_block,
+                to_block,
+                implementation_set = implementation_set.len(),
+                init_bond_updated = init_bond_updated.len(),
+                "Game implementation changed on-chain, reloading"
+            );
+            let game_impl = self.load_game_implementation().await?;
+            tracing::info!(
+                address = %game_impl.address,
+                init_bond = %game_impl.init_bond,
+                compatible = game_impl.compatible,
+                "Game implementation reloaded"
+            );
+            self.set_game_implementation(game_impl);
+            ProposerGauge::GameImplementationReloads.increment(1.0);
+        }
+
+        self.game_impl_watch_block.store(to_block, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Validates that the provided anchor state registry matches the factory's game implementation.
     async fn validate_anchor_state_registry(
         anchor_state_registry: &AnchorStateRegistryInstance<P>,
@@ -641,6 +1044,10 @@ where
     /// 2. `sync_anchor_game` aligns the cached anchor pointer with the registry contract.
     /// 3. `compute_canonical_head` recomputes the head game used for proposal selection.
     pub async fn sync_state(&self) -> Result<()> {
+        self.chaos.rpc_lag().await;
+        let stale_l1_depth = self.chaos.stale_l1_depth();
+        let confirmations = self.config.sync_l1_confirmations + stale_l1_depth;
+
         // Pin L1 block for the entire sync cycle so all state reads see a consistent
         // snapshot. Without this, load-balanced RPCs can return data from different block
         // heights, breaking atomicity between related reads (e.g. credit vs anchorGame).
@@ -650,12 +1057,11 @@ where
             .get_block_by_number(BlockNumberOrTag::Latest)
             .await?
             .context("Failed to fetch latest L1 block")?;
-        let confirmed_number =
-            latest_block.header.number.saturating_sub(self.config.sync_l1_confirmations);
+        let confirmed_number = latest_block.header.number.saturating_sub(confirmations);
 
         // If L1 hasn't advanced past the last synced block, all on-chain state is identical.
         let prev = self.last_synced_l1_block.load(Ordering::Relaxed);
-        if confirmed_number > 0 && confirmed_number <= prev {
+        if stale_l1_depth == 0 && confirmed_number > 0 && confirmed_number <= prev {
             tracing::debug!(
                 confirmed_number,
                 last_synced = prev,
@@ -667,7 +1073,7 @@ where
         // When no confirmation offset, use the latest block directly (single RPC response).
         // When offset > 0, fetch the confirmed block separately; if the backend hasn't
         // caught up, skip this cycle rather than pinning forward.
-        let (pinned_block, pinned_timestamp) = if self.config.sync_l1_confirmations == 0 {
+        let (pinned_block, pinned_timestamp) = if confirmations == 0 {
             (BlockId::number(latest_block.header.number), latest_block.header.timestamp)
         } else {
             match self
@@ -686,6 +1092,9 @@ where
             }
         };
 
+        // Track the registry settings the guardian can change.
+        self.sync_registry(pinned_block).await?;
+
         // Pull new games and synchronize cached game statuses.
         self.sync_games(pinned_block, pinned_timestamp).await?;
 
@@ -709,6 +1118,9 @@ where
     ///    - Games are validated (correct type, valid output root) before being added.
     /// 2. Synchronize the status of all cached games.
     ///    - Games are removed (along with their subtree) if their parent is not in the cache.
+    ///    - Games the registry blacklisted or retired are marked improper, which excludes them
+    ///      (along with their subtree) from canonical head selection, since they can't become the
+    ///      anchor or parent a valid game.
     ///    - Games are marked for resolution if the parent is resolved, the game is over, and it's
     ///      own game.
     ///    - Games are marked for bond claim if they are finalized and there is credit to claim.
@@ -851,12 +1263,27 @@ where
         // 2. Synchronize the status of all cached games.
         let games = {
             let state = self.state.read().await;
-            state.games.values().map(|game| (game.index, game.address)).collect::<Vec<_>>()
+            state
+                .games
+                .values()
+                .map(|game| (game.index, game.address, game.creator, game.created_at))
+                .collect::<Vec<_>>()
         };
 
         if !games.is_empty() {
             let now_ts = pinned_timestamp;
             let signer_address = self.signer.address();
+            self.sync_blacklist(pinned_block).await?;
+            let registry_settings = self
+                .registry_settings
+                .read()
+                .expect("registry settings lock poisoned")
+                .context("Registry settings not synced")?;
+            let (previously_improper, blacklisted_games) = {
+                let state = self.state.read().await;
+                (state.improper_games.clone(), state.blacklisted_games.clone())
+            };
+            let mut improper_games = HashSet::new();
 
             enum GameSyncAction {
                 Update {
@@ -873,7 +1300,7 @@ where
 
             let mut actions = Vec::with_capacity(games.len());
 
-            for (index, game_address) in games {
+            for (index, game_address, creator, created_at) in games {
                 let contract =
                     OPSuccinctFaultDisputeGame::new(game_address, self.l1_provider.clone());
                 let claim_data = contract.claimData().block(pinned_block).call().await?;
@@ -881,6 +1308,31 @@ where
                 let deadline = U256::from(claim_data.deadline).to::<u64>();
                 let parent_index = claim_data.parentIndex;
 
+                let improper = if blacklisted_games.contains(&game_address) {
+                    Some(ImproperGame::Blacklisted)
+                } else if registry_settings.is_retired(created_at) {
+                    Some(ImproperGame::Retired)
+                } else {
+                    None
+                };
+                if let Some(reason) = improper {
+                    if !previously_improper.contains(&index) {
+                        tracing::warn!(
+                            game_index = %index,
+                            ?game_address,
+                            ?reason,
+                            "Excluding improper game and its subtree from canonical head selection"
+                        );
+                        match reason {
+                            ImproperGame::Blacklisted => {
+                                ProposerGauge::GamesBlacklisted.increment(1.0)
+                            }
+                            ImproperGame::Retired => ProposerGauge::GamesRetired.increment(1.0),
+                        }
+                    }
+                    improper_games.insert(index);
+                }
+
                 let is_finalized = self
                     .anchor_state_registry
                     .isGameFinalized(game_address)
@@ -900,7 +1352,6 @@ where
                             ProposalStatus::ChallengedAndValidProofProvided => true,
                             _ => false,
                         };
-                        let creator = contract.gameCreator().block(pinned_block).call().await?;
                         let is_own_game = match claim_data.status {
                             ProposalStatus::Unchallenged => creator == signer_address,
                             ProposalStatus::UnchallengedAndValidProofProvided |
@@ -1022,7 +1473,7 @@ where
                                 tracing::info!(
                                     ?guarded_addr,
                                     root_index = %index,
-                                    "Reset creation guard: tracked game removed by CHALLENGER_WINS"
+                                    "Reset creation guard: tracked game's subtree removed"
                                 );
                             }
                         }
@@ -1030,7 +1481,95 @@ where
                     }
                 }
             }
+            improper_games.retain(|index| state.games.contains_key(index));
+            state.improper_games = improper_games;
+
+            // The canonical head never advances past an improper subtree, so reset the
+            // duplicate-creation guard if it tracks a game in one.
+            let guarded_addr = *self.last_created_game_address.lock().await;
+            if guarded_addr != Address::ZERO {
+                let guard_improper = state
+                    .improper_subtrees()
+                    .iter()
+                    .any(|idx| state.games.get(idx).is_some_and(|g| g.address == guarded_addr));
+                if guard_improper {
+                    self.last_created_game_l2_block.store(0, Ordering::Relaxed);
+                    *self.last_created_game_address.lock().await = Address::ZERO;
+                    tracing::info!(?guarded_addr, "Reset creation guard: tracked game is improper");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Synchronizes the addresses of blacklisted cached games.
+    ///
+    /// Blacklisting can't be undone, so the cached games are checked with `isGameBlacklisted` only
+    /// on the first sync. Later syncs scan `DisputeGameBlacklisted` events since the last one,
+    /// which also covers games loaded since, as they can only be blacklisted once created.
+    async fn sync_blacklist(&self, pinned_block: BlockId) -> Result<()> {
+        let pinned_number =
+            pinned_block.as_u64().context("Sync is not pinned to a block number")?;
+        let last_scanned = self.blacklist_watch_block.load(Ordering::Relaxed);
+
+        let blacklisted = if last_scanned == 0 {
+            let games = {
+                let state = self.state.read().await;
+                state.games.values().map(|game| game.address).collect::<Vec<_>>()
+            };
+            let mut blacklisted = Vec::new();
+            for game in games {
+                if self
+                    .anchor_state_registry
+                    .isGameBlacklisted(game)
+                    .block(pinned_block)
+                    .call()
+                    .await?
+                {
+                    blacklisted.push(game);
+                }
+            }
+            blacklisted
+        } else if pinned_number > last_scanned {
+            self.anchor_state_registry
+                .DisputeGameBlacklisted_filter()
+                .from_block(last_scanned + 1)
+                .to_block(pinned_number)
+                .query()
+                .await?
+                .into_iter()
+                .map(|(event, _)| event.disputeGame)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        {
+            let mut state = self.state.write().await;
+            state.blacklisted_games.extend(blacklisted);
+            let cached = state.games.values().map(|game| game.address).collect::<HashSet<_>>();
+            state.blacklisted_games.retain(|game| cached.contains(game));
         }
+        self.blacklist_watch_block.fetch_max(pinned_number, Ordering::Relaxed);
+
+        Ok(())
+    }
+
+    /// Synchronizes the registry settings, logging changes and exporting them as metrics.
+    ///
+    /// Game creation checks the respected game type itself before each proposal, so this only
+    /// surfaces changes.
+    async fn sync_registry(&self, pinned_block: BlockId) -> Result<()> {
+        let settings = RegistrySettings::fetch(&self.anchor_state_registry, pinned_block).await?;
+
+        let mut current = self.registry_settings.write().expect("registry settings lock poisoned");
+        settings.log_changes(current.as_ref(), self.config.game_type);
+        *current = Some(settings);
+
+        ProposerGauge::GameTypeRespected
+            .set((settings.respected_game_type == self.config.game_type) as u8 as f64);
+        ProposerGauge::RetirementTimestamp.set(settings.retirement_timestamp as f64);
 
         Ok(())
     }
@@ -1059,44 +1598,12 @@ where
         Ok(())
     }
 
-    /// Computes the canonical head by scanning all cached games.
-    ///
-    /// Canonical head is the game with the highest L2 block number. When an anchor game exists,
-    /// the canonical head is chosen from its descendants, unless a non-descendant has a higher L2
-    /// block number and an earlier lineage (parent is genesis or has a lower parent index than the
-    /// best descendant).
+    /// Computes the canonical head by scanning all cached games. See
+    /// [`ProposerState::select_canonical_head`].
     async fn compute_canonical_head(&self) {
         let mut state = self.state.write().await;
 
-        let canonical_head = match state.anchor_game.as_ref() {
-            None => state.games.values().max_by_key(|g| g.l2_block).cloned(),
-            Some(anchor_game) => {
-                let reachable = state.descendants_of(anchor_game.index);
-
-                // Best among descendants
-                let anchor_head = state
-                    .games
-                    .values()
-                    .filter(|g| reachable.contains(&g.index))
-                    .max_by_key(|g| g.l2_block);
-
-                // Check non-descendants for override (higher block with genesis or lower parent)
-                let override_head = anchor_head.and_then(|anchor| {
-                    state
-                        .games
-                        .values()
-                        .filter(|g| !reachable.contains(&g.index))
-                        .filter(|g| {
-                            g.l2_block > anchor.l2_block &&
-                                (g.parent_index == u32::MAX ||
-                                    g.parent_index < anchor.parent_index)
-                        })
-                        .max_by_key(|g| g.l2_block)
-                });
-
-                override_head.or(anchor_head).cloned()
-            }
-        };
+        let canonical_head = state.select_canonical_head();
 
         let previous_canonical_index = state.canonical_head_index;
 
@@ -1132,24 +1639,6 @@ where
         }
     }
 
-    /// Returns true if on-chain vkeys match ours (safe to create games).
-    /// Checks all 3 identity fields: aggregation_vkey, range_vkey_commitment, rollup_config_hash.
-    async fn on_chain_vkeys_match(&self) -> Result<bool> {
-        let game_impl = self.factory.game_impl(self.config.game_type).await?;
-        let on_chain_agg = B256::from(game_impl.aggregationVkey().call().await?.0);
-        let on_chain_range = B256::from(game_impl.rangeVkeyCommitment().call().await?.0);
-        let on_chain_rollup_hash = B256::from(game_impl.rollupConfigHash().call().await?.0);
-
-        let matches = on_chain_agg == self.identity.aggregation_vkey &&
-            on_chain_range == self.identity.range_vkey_commitment &&
-            on_chain_rollup_hash == self.identity.rollup_config_hash;
-
-        if !matches {
-            tracing::info!("Proposer vkeys mismatch with on-chain vkeys - skipping game creation (hardfork detected)");
-        }
-        Ok(matches)
-    }
-
     /// Proves a dispute game at the given address.
     ///
     /// # Returns
@@ -1170,6 +1659,64 @@ where
         let l1_head_hash = game.l1Head().call().await?.0;
         tracing::debug!("L1 head hash: {:?}", hex::encode(l1_head_hash));
 
+        // Re-attach to an aggregation proof requested before a restart, skipping the range proofs
+        // it was built from.
+        let agg_key = PendingProofKey::aggregation(game_address, start_block, end_block);
+        let (agg_proof, total_instruction_cycles, total_sp1_gas) =
+            match self.prover.resume_proof(agg_key).await {
+                Some(agg_proof) => (agg_proof, 0, 0),
+                None => {
+                    self.generate_game_proof(
+                        game_address,
+                        start_block,
+                        end_block,
+                        l1_head_hash.into(),
+                        agg_key,
+                    )
+                    .await?
+                }
+            };
+
+        self.chaos.drop_proof()?;
+        self.chaos.delay_proof().await;
+
+        // Network proofs are recorded at the price they were fulfilled at by the prover. Mock
+        // proofs have no price, so only their SP1 gas is recorded. Cluster proofs aren't paid for
+        // in PROVE.
+        if matches!(self.prover, ProofProvider::Mock(_)) {
+            self.ledger.record_proof(&game_address.to_string(), total_sp1_gas, None);
+        }
+
+        self.chaos.fail_transaction(TxAction::Prove)?;
+        let transaction_request = game.prove(agg_proof.bytes().into()).into_transaction_request();
+        let receipt = self
+            .signer
+            .send_transaction_request_with_timeout(
+                self.config.l1_rpc.clone(),
+                transaction_request,
+                self.config.tx_confirmation_timeout,
+            )
+            .await?;
+        self.record_ledger_tx(game_address, LedgerTxKind::Prove, &receipt);
+
+        if !receipt.status() {
+            bail!("{TX_REVERTED_PREFIX} {receipt:?}");
+        }
+
+        Ok((receipt.transaction_hash, total_instruction_cycles, total_sp1_gas))
+    }
+
+    /// Generates the aggregation proof for a game, from range proofs over its split range.
+    ///
+    /// Returns the proof with the total instruction cycles and SP1 gas of the range proofs.
+    async fn generate_game_proof(
+        &self,
+        game_address: Address,
+        start_block: u64,
+        end_block: u64,
+        l1_head_hash: B256,
+        agg_key: PendingProofKey,
+    ) -> Result<(SP1ProofWithPublicValues, u64, u64)> {
         let ranges = self
             .config
             .range_split_count
@@ -1178,34 +1725,44 @@ where
         let num_ranges = ranges.len();
         tracing::info!("Proving over {num_ranges} ranges");
 
-        let tasks = ranges.into_iter().enumerate().map(|(idx, (start, end))| {
+        // The split ranges are adjacent, so share the preimages fetched for each range's witness
+        // with the others.
+        let witness_gen_options =
+            self.config.witness_gen_options.clone().with_shared_cache(SharedPreimageCache::new(
+                self.config.shared_preimage_cache_bytes,
+            ));
+
+        let tasks = ranges.into_iter().map(|(start, end)| {
             let this = self.clone();
+            let witness_gen_options = witness_gen_options.clone();
             async move {
-                tracing::info!("Generating Range Proof for blocks {start} to {end}");
-                let sp1_stdin = this.range_proof_stdin(start, end, l1_head_hash.into()).await?;
-                let (range_proof, inst_cycles, sp1_gas) =
-                    this.prover.generate_range_proof(sp1_stdin).await?;
-                Ok::<_, anyhow::Error>((idx, range_proof, inst_cycles, sp1_gas))
+                this.range_proofs(game_address, start, end, l1_head_hash, &witness_gen_options)
+                    .await
             }
         });
 
         let max_concurrent = self.config.max_concurrent_range_proofs.get().min(num_ranges);
         let prove_stream = stream::iter(tasks);
-        let results: Vec<(usize, SP1ProofWithPublicValues, u64, u64)> =
+        let results: Vec<Vec<RangeProofOutput>> =
             prove_stream.buffer_unordered(max_concurrent).try_collect().await?;
 
-        let mut proofs = vec![None; num_ranges];
-        let mut boot_infos = vec![None; num_ranges];
+        // Ranges that were split during witness generation yield several proofs, so order the
+        // proofs by their start block.
+        let mut results = results.into_iter().flatten().collect::<Vec<_>>();
+        results.sort_by_key(|(start, ..)| *start);
+
+        let mut proofs = Vec::with_capacity(results.len());
+        let mut boot_infos = Vec::with_capacity(results.len());
+        let mut extended_outputs = Vec::with_capacity(results.len());
         let mut total_instruction_cycles: u64 = 0;
         let mut total_sp1_gas: u64 = 0;
 
-        for (idx, range_proof, inst_cycles, sp1_gas) in results {
-            let proof = range_proof.proof.clone();
-            let mut public_values = range_proof.public_values.clone();
-            let boot_info: BootInfoStruct = public_values.read();
+        for (_, range_proof, inst_cycles, sp1_gas) in results {
+            let (boot_info, extended) = read_range_outputs(range_proof.public_values.as_slice())?;
 
-            proofs[idx] = Some(proof);
-            boot_infos[idx] = Some(boot_info);
+            proofs.push(range_proof.proof);
+            boot_infos.push(boot_info);
+            extended_outputs.push(extended);
             total_instruction_cycles = total_instruction_cycles
                 .checked_add(inst_cycles)
                 .ok_or_else(|| anyhow::anyhow!("Instruction cycles overflow"))?;
@@ -1214,22 +1771,6 @@ where
                 .ok_or_else(|| anyhow::anyhow!("SP1 gas overflow"))?;
         }
 
-        let proofs = proofs
-            .into_iter()
-            .enumerate()
-            .map(|(idx, proof)| {
-                proof.ok_or_else(|| anyhow::anyhow!("missing proof for range index {idx}"))
-            })
-            .collect::<Result<Vec<_>>>()?;
-
-        let boot_infos = boot_infos
-            .into_iter()
-            .enumerate()
-            .map(|(idx, boot)| {
-                boot.ok_or_else(|| anyhow::anyhow!("missing boot info for range index {idx}"))
-            })
-            .collect::<Result<Vec<_>>>()?;
-
         let latest_l1_head = boot_infos.last().context("No boot infos generated")?.l1Head;
 
         let headers = match self.fetcher.get_header_preimages(&boot_infos, latest_l1_head).await {
@@ -1244,6 +1785,7 @@ where
         let sp1_stdin = match get_agg_proof_stdin(
             proofs,
             boot_infos,
+            extended_outputs,
             headers,
             &self.prover.keys().range_vk,
             latest_l1_head,
@@ -1256,23 +1798,70 @@ where
             }
         };
 
-        let agg_proof = self.prover.generate_agg_proof(sp1_stdin).await?;
+        let agg_proof = self.prover.generate_agg_proof(agg_key, sp1_stdin).await?;
 
-        let transaction_request = game.prove(agg_proof.bytes().into()).into_transaction_request();
-        let receipt = self
-            .signer
-            .send_transaction_request_with_timeout(
-                self.config.l1_rpc.clone(),
-                transaction_request,
-                self.config.tx_confirmation_timeout,
-            )
-            .await?;
+        Ok((agg_proof, total_instruction_cycles, total_sp1_gas))
+    }
 
-        if !receipt.status() {
-            bail!("{TX_REVERTED_PREFIX} {receipt:?}");
+    /// Generates the range proofs for blocks `start_block` to `end_block`.
+    ///
+    /// If the range's witness generation exceeds its timeout or preimage limit, the range is split
+    /// in half and each half is proven on its own, so this may return several proofs.
+    async fn range_proofs(
+        &self,
+        game_address: Address,
+        start_block: u64,
+        end_block: u64,
+        l1_head_hash: B256,
+        witness_gen_options: &WitnessGenOptions,
+    ) -> Result<Vec<RangeProofOutput>> {
+        // Re-attach to a range proof requested before a restart, skipping witness generation.
+        let key = PendingProofKey::range(game_address, start_block, end_block);
+        if let Some(range_proof) = self.prover.resume_proof(key).await {
+            return Ok(vec![(start_block, range_proof, 0, 0)]);
+        }
+
+        tracing::info!("Generating Range Proof for blocks {start_block} to {end_block}");
+        match self
+            .range_proof_stdin(start_block, end_block, l1_head_hash, witness_gen_options)
+            .await
+        {
+            Ok(sp1_stdin) => {
+                let (range_proof, inst_cycles, sp1_gas) =
+                    self.prover.generate_range_proof(key, sp1_stdin).await?;
+                Ok(vec![(start_block, range_proof, inst_cycles, sp1_gas)])
+            }
+            Err(e) if end_block - start_block > 1 && WitnessGenerationError::find(&e).is_some() => {
+                let mid_block = start_block + (end_block - start_block) / 2;
+                tracing::warn!(
+                    start_block,
+                    end_block,
+                    mid_block,
+                    error = ?e,
+                    "Range too large for witness generation, splitting it in two"
+                );
+                let mut proofs = Box::pin(self.range_proofs(
+                    game_address,
+                    start_block,
+                    mid_block,
+                    l1_head_hash,
+                    witness_gen_options,
+                ))
+                .await?;
+                proofs.extend(
+                    Box::pin(self.range_proofs(
+                        game_address,
+                        mid_block,
+                        end_block,
+                        l1_head_hash,
+                        witness_gen_options,
+                    ))
+                    .await?,
+                );
+                Ok(proofs)
+            }
+            Err(e) => Err(e),
         }
-
-        Ok((receipt.transaction_hash, total_instruction_cycles, total_sp1_gas))
     }
 
     async fn range_proof_stdin(
@@ -1280,6 +1869,7 @@ where
         start_block: u64,
         end_block: u64,
         l1_head_hash: B256,
+        witness_gen_options: &WitnessGenOptions,
     ) -> Result<SP1Stdin> {
         let host_args = self
             .host
@@ -1287,11 +1877,12 @@ where
             .await
             .context("Failed to get host CLI args")?;
 
-        let witness_data = match self.host.run(&host_args).await {
+        let witness_data = match self.host.run_with_options(&host_args, witness_gen_options).await {
             Ok(witness) => witness,
             Err(e) => {
                 tracing::error!("Failed to generate witness: {}", e);
-                return Err(anyhow::anyhow!("Failed to generate witness: {}", e));
+                // Keep the error's type for the range splitting in `range_proofs`.
+                return Err(e.context("Failed to generate witness"));
             }
         };
 
@@ -1315,8 +1906,8 @@ where
         output_root: FixedBytes<32>,
         extra_data: Vec<u8>,
     ) -> Result<Address> {
-        let init_bond =
-            *self.init_bond.get().context("init_bond must be set via startup_validations")?;
+        self.chaos.fail_transaction(TxAction::Create)?;
+        let init_bond = self.game_implementation()?.init_bond;
         let transaction_request = self
             .factory
             .create(self.config.game_type, output_root, extra_data.into())
@@ -1333,6 +1924,8 @@ where
             .await?;
 
         if !receipt.status() {
+            // A reverted creation has no game, so its gas is booked against the factory.
+            self.record_ledger_tx(*self.factory.address(), LedgerTxKind::Create, &receipt);
             bail!("{TX_REVERTED_PREFIX} {receipt:?}");
         }
 
@@ -1344,6 +1937,9 @@ where
                 DisputeGameCreated::decode_log(&log.inner).ok().map(|event| event.disputeProxy)
             })
             .context("Could not find DisputeGameCreated event in transaction receipt logs")?;
+        self.ledger.record_bond(&game_address.to_string(), init_bond);
+        self.balance_monitor.record_spent(init_bond);
+        self.record_ledger_tx(game_address, LedgerTxKind::Create, &receipt);
 
         tracing::info!(
             game_address = ?game_address,
@@ -1370,8 +1966,8 @@ where
             state
                 .games
                 .values()
-                // Only resolve owned games (vkeys match).
-                .filter(|game| game.is_owned(&self.identity))
+                // Only resolve owned games (vkeys match one of our key sets).
+                .filter(|game| self.owns_game(game))
                 .filter(|game| game.should_attempt_to_resolve)
                 .cloned()
                 .collect::<Vec<_>>()
@@ -1413,8 +2009,8 @@ where
             state
                 .games
                 .values()
-                // Only claim bonds for owned games (vkeys match).
-                .filter(|game| game.is_owned(&self.identity))
+                // Only claim bonds for owned games (vkeys match one of our key sets).
+                .filter(|game| self.owns_game(game))
                 .filter(|game| game.should_attempt_to_claim_bond)
                 .cloned()
                 .collect::<Vec<_>>()
@@ -1450,6 +2046,7 @@ where
     }
 
     pub async fn submit_resolution_transaction(&self, game: &Game) -> Result<()> {
+        self.chaos.fail_transaction(TxAction::Resolve)?;
         let contract = OPSuccinctFaultDisputeGame::new(game.address, self.l1_provider.clone());
         let transaction_request = contract.resolve().into_transaction_request();
         let receipt = self
@@ -1460,6 +2057,7 @@ where
                 self.config.tx_confirmation_timeout,
             )
             .await?;
+        self.record_ledger_tx(game.address, LedgerTxKind::Resolve, &receipt);
 
         if !receipt.status() {
             bail!("{TX_REVERTED_PREFIX} {receipt:?}");
@@ -1479,7 +2077,9 @@ where
     /// Submit the on-chain transaction to claim the proposer's bond for a given game.
     #[tracing::instrument(name = "[[Claiming Proposer Bonds]]", skip(self, game))]
     pub async fn submit_bond_claim_transaction(&self, game: &Game) -> Result<()> {
+        self.chaos.fail_transaction(TxAction::Claim)?;
         let contract = OPSuccinctFaultDisputeGame::new(game.address, self.l1_provider.clone());
+        let credit = contract.credit(self.signer.address()).call().await?;
         let transaction_request =
             contract.claimCredit(self.signer.address()).gas(200_000).into_transaction_request();
         let receipt = self
@@ -1490,10 +2090,13 @@ where
                 self.config.tx_confirmation_timeout,
             )
             .await?;
+        self.record_ledger_tx(game.address, LedgerTxKind::Claim, &receipt);
 
         if !receipt.status() {
             bail!("{TX_REVERTED_PREFIX} {receipt:?}");
         }
+        self.ledger.record_claim(&game.address.to_string(), credit);
+        self.update_ledger_metrics();
 
         tracing::info!(
             game_index = %game.index,
@@ -1525,6 +2128,7 @@ where
         let game = self.factory.gameAtIndex(index).block(pinned_block).call().await?;
         let game_address = game.proxy;
         let game_type = game.gameType;
+        let created_at = U256::from(game.timestamp).to::<u64>();
 
         // Drop unsupported game types.
         if game_type != self.config.game_type {
@@ -1555,12 +2159,13 @@ where
         }
 
         let l2_block = contract.l2BlockNumber().block(pinned_block).call().await?;
-        let output_root = self.l2_provider.compute_output_root_at_block(l2_block).await?;
+        let output_root = self.compute_output_root(l2_block).await?;
         let claim = contract.rootClaim().block(pinned_block).call().await?;
         let was_respected =
             contract.wasRespectedGameTypeWhenCreated().block(pinned_block).call().await?;
         let status = contract.status().block(pinned_block).call().await?;
         let claim_data = contract.claimData().block(pinned_block).call().await?;
+        let creator = contract.gameCreator().block(pinned_block).call().await?;
 
         let (parent_index, proposal_status, deadline) = (
             claim_data.parentIndex,
@@ -1613,6 +2218,8 @@ where
         let game = Game {
             index,
             address: game_address,
+            creator,
+            created_at,
             parent_index,
             l2_block,
             status,
@@ -1625,7 +2232,7 @@ where
             rollup_config_hash,
         };
 
-        if !game.is_owned(&self.identity) {
+        if !self.owns_game(&game) {
             tracing::info!(game_index = %index, "Discovered foreign game (proposer's identity params don't match on-chain params) - tracking for DAG but not proving/resolving/claiming");
         }
 
@@ -1642,10 +2249,7 @@ where
         mut next_l2_block_number_for_proposal: U256,
         parent_game_index: u32,
     ) -> Result<()> {
-        let mut output_root = self
-            .l2_provider
-            .compute_output_root_at_block(next_l2_block_number_for_proposal)
-            .await?;
+        let mut output_root = self.compute_output_root(next_l2_block_number_for_proposal).await?;
         let mut extra_data =
             (next_l2_block_number_for_proposal, parent_game_index).abi_encode_packed();
         let mut maybe_existing_game = self
@@ -1659,10 +2263,7 @@ where
         // block number by 1
         while maybe_existing_game != Address::ZERO {
             next_l2_block_number_for_proposal += U256::from(1);
-            output_root = self
-                .l2_provider
-                .compute_output_root_at_block(next_l2_block_number_for_proposal)
-                .await?;
+            output_root = self.compute_output_root(next_l2_block_number_for_proposal).await?;
             extra_data = (next_l2_block_number_for_proposal, parent_game_index).abi_encode_packed();
             maybe_existing_game = self
                 .factory
@@ -1672,6 +2273,7 @@ where
                 .proxy;
         }
 
+        let output_root = self.chaos.output_root(output_root);
         tracing::info!(
             l2_block_number = %next_l2_block_number_for_proposal,
             parent_game_index = %parent_game_index,
@@ -1739,15 +2341,6 @@ where
             as u64
     }
 
-    /// Count active defense tasks
-    async fn count_active_defense_tasks(&self) -> u64 {
-        let tasks = self.tasks.lock().await;
-        tasks
-            .iter()
-            .filter(|(_, (_, info))| matches!(info, TaskInfo::GameProving { is_defense: true, .. }))
-            .count() as u64
-    }
-
     /// Spawn a dedicated metrics collection task
     fn spawn_metrics_collector(&self) {
         let proposer_metrics = self.clone();
@@ -1763,6 +2356,23 @@ where
         });
     }
 
+    /// Spawn a task backing up the proposer state whenever a proof request is recorded, so a
+    /// restart right after the request re-attaches to it instead of paying for the proof again.
+    fn spawn_pending_proof_backup(&self) {
+        let (Some(_), Some(pending)) = (&self.config.backup_path, self.prover.pending_proofs())
+        else {
+            return;
+        };
+        let pending = pending.clone();
+        let proposer = self.clone();
+        tokio::spawn(async move {
+            loop {
+                pending.recorded().await;
+                proposer.save_backup().await;
+            }
+        });
+    }
+
     /// Handle completed tasks and clean them up
     async fn handle_completed_tasks(&self) -> Result<()> {
         let mut tasks = self.tasks.lock().await;
@@ -1818,8 +2428,25 @@ where
 
     /// Spawn pending operations if not already running
     async fn spawn_pending_operations(&self) -> Result<()> {
-        // Check if we should create a game and spawn task if needed
-        if !self.has_active_task_of_type(&TaskInfo::GameCreation { block_number: U256::ZERO }).await
+        // Schedule defense and fast finality proving. Proving goes first so that games already
+        // created are proven before game creation spawns more proving.
+        match self.spawn_game_proving_tasks().await {
+            Ok(true) => tracing::info!("Successfully spawned game proving tasks"),
+            Ok(false) => tracing::debug!("No games need proving or no proving slot free"),
+            Err(e) => tracing::warn!("Failed to spawn game proving tasks: {:?}", e),
+        }
+
+        // Check if we should create a game and spawn task if needed. A guardian never creates
+        // games, and creation pauses while the balances are too low to post the bond.
+        if self.config.guardian.is_some() {
+            tracing::debug!("Guardian mode, skipping game creation");
+        } else if !self.bond_affordable() {
+            tracing::debug!("Balance too low, skipping game creation");
+        } else if !self.proposal_permitted() {
+            tracing::debug!("Not allowed to propose, skipping game creation");
+        } else if !self
+            .has_active_task_of_type(&TaskInfo::GameCreation { block_number: U256::ZERO })
+            .await
         {
             match self.spawn_game_creation_task().await {
                 Ok(true) => tracing::info!("Successfully spawned game creation task"),
@@ -1832,13 +2459,6 @@ where
             tracing::info!("Game creation task already active");
         }
 
-        // Check if we should defend games
-        match self.spawn_game_defense_tasks().await {
-            Ok(true) => tracing::info!("Successfully spawned game defense tasks"),
-            Ok(false) => tracing::debug!("No games need defense or task already active"),
-            Err(e) => tracing::warn!("Failed to spawn game defense tasks: {:?}", e),
-        }
-
         // Spawn game resolution task (only operates on owned games via is_owned() filter)
         if !self.has_active_task_of_type(&TaskInfo::GameResolution).await {
             if let Err(e) = self.spawn_game_resolution_task().await {
@@ -1953,119 +2573,27 @@ where
 
     /// Check if we should create a game
     ///
-    /// In fast finality mode, prioritizes proving existing games over creating new ones, preventing
-    /// the proposer from abandoning in-progress games after a restart.
+    /// In fast finality mode, waits for a free fast finality proving slot and for no defense to be
+    /// at risk, so in-progress games are proven before new ones are created.
     ///
-    /// Then compares the next L2 block number for proposal with the finalized L2 block number.
-    /// If the finalized L2 block number is greater than or equal to the next L2 block number for
-    /// proposal, we should create a game.
+    /// Then decides from the finalized L2 block number and the age of the canonical head whether
+    /// a proposal is due and for which L2 block, see [`next_proposal`].
     ///
     /// Returns boolean indicating if a game should be created, the next L2 block number for
     /// proposal, and the parent game index.
     /// If a game should not be created, dummy values are returned for the next L2 block number for
     /// proposal and parent game index.
     pub async fn should_create_game(&self) -> Result<(bool, U256, u32)> {
-        // In fast finality mode, resume proving for existing games before creating new ones
-        // TODO(fakedev9999): Consider unifying proving concurrency control for both fast finality
-        // and defense proving with a priority system.
+        // In fast finality mode, a new game is proven right away, so creation waits for a free
+        // fast finality proving slot. Games that still need proving are resumed first, see
+        // [`Self::spawn_game_proving_tasks`], and creation waits while a defense is at risk.
         if self.config.fast_finality_mode {
-            let mut active_proving = self.count_active_proving_tasks().await;
-
-            // Resume proving for existing unproven games before creating new ones.
-            if active_proving < self.config.fast_finality_proving_limit {
-                let signer_address = self.signer.address();
-
-                let unproven_games = {
-                    let state = self.state.read().await;
-                    let tasks = self.tasks.lock().await;
-
-                    let candidates = state
-                        .games
-                        .values()
-                        .filter(|game| game.status == GameStatus::IN_PROGRESS)
-                        .filter(|game| game.proposal_status == ProposalStatus::Unchallenged)
-                        .map(|game| (game.index, game.address, game.deadline))
-                        .collect::<Vec<_>>();
-
-                    let proving_set = tasks
-                        .values()
-                        .filter_map(|(_, info)| match info {
-                            TaskInfo::GameProving { game_address, .. } => Some(*game_address),
-                            _ => None,
-                        })
-                        .collect::<HashSet<_>>();
-
-                    // Filter games being proven
-                    candidates
-                        .into_iter()
-                        .filter(|(_, address, _)| !proving_set.contains(address))
-                        .collect::<Vec<_>>()
-                };
-
-                let mut spawned_count = 0;
-
-                for (index, game_address, deadline) in unproven_games {
-                    if active_proving >= self.config.fast_finality_proving_limit {
-                        tracing::debug!(
-                            "Reached fast finality proving capacity ({}/{}) while resuming games",
-                            active_proving,
-                            self.config.fast_finality_proving_limit
-                        );
-                        break;
-                    }
-
-                    // Check if we own this game
-                    let contract =
-                        OPSuccinctFaultDisputeGame::new(game_address, self.l1_provider.clone());
-                    let creator = match contract.gameCreator().call().await {
-                        Ok(c) => c,
-                        Err(e) => {
-                            tracing::warn!(
-                                ?game_address,
-                                ?e,
-                                "Failed to check game creator, skipping"
-                            );
-                            continue;
-                        }
-                    };
-
-                    if creator != signer_address {
-                        continue;
-                    }
-
-                    // Spawn proving task
-                    match self.spawn_game_proving_task(game_address, false, Some(deadline)).await {
-                        Ok(true) => {
-                            tracing::info!(
-                                game_address = ?game_address,
-                                game_index = %index,
-                                "Resumed fast finality proving for existing game"
-                            );
-                            spawned_count += 1;
-                            active_proving += 1;
-                        }
-                        Ok(false) => {}
-                        Err(e) => {
-                            tracing::warn!(
-                                ?game_address,
-                                ?e,
-                                "Failed to spawn proving task, continuing"
-                            );
-                        }
-                    }
-                }
-
-                if spawned_count > 0 {
-                    tracing::info!(
-                        "Resumed proving for {} existing game(s), now at {}/{} capacity",
-                        spawned_count,
-                        active_proving,
-                        self.config.fast_finality_proving_limit
-                    );
-                }
+            if self.defense_at_risk.load(Ordering::Relaxed) {
+                tracing::info!("Skipping game creation: defense deadline at risk");
+                return Ok((false, U256::ZERO, u32::MAX));
             }
 
-            // Check capacity after resuming existing games
+            let active_proving = self.count_active_proving_tasks().await;
             if active_proving >= self.config.fast_finality_proving_limit {
                 tracing::info!(
                     "Skipping game creation: at proving capacity ({}/{})",
@@ -2088,8 +2616,9 @@ where
             return Ok((false, U256::ZERO, u32::MAX));
         }
 
-        // Skip creation if on-chain vkeys don't match (hardfork detected).
-        if !self.on_chain_vkeys_match().await? {
+        // Skip creation while the game implementation is incompatible (e.g. hardfork detected).
+        if !self.game_implementation()?.compatible {
+            tracing::info!("Proposer vkeys mismatch with on-chain vkeys - skipping game creation (hardfork detected)");
             return Ok((false, U256::ZERO, u32::MAX));
         }
 
@@ -2114,50 +2643,216 @@ where
             (canonical_head_l2_block, parent_game_index)
         };
 
-        let next_l2_block_number_for_proposal =
-            canonical_head_l2_block + U256::from(self.config.proposal_interval_in_blocks);
+        let canonical_head_l2_block = canonical_head_l2_block.to::<u64>();
 
         // Guard against duplicate creation when the pinned cache lags behind the tip.
-        // If we recently created a game at or beyond this L2 block, skip until the
+        // If we recently created a game beyond the canonical head, skip until the
         // cache catches up and advances canonical_head_l2_block.
         let last_created = self.last_created_game_l2_block.load(Ordering::Relaxed);
-        if last_created > 0 && next_l2_block_number_for_proposal.to::<u64>() <= last_created {
+        if created_game_pending(last_created, canonical_head_l2_block) {
             tracing::debug!(
-                next_l2_block = %next_l2_block_number_for_proposal,
+                canonical_head_l2_block,
                 last_created,
                 "Skipping game creation: recently created game not yet visible in pinned cache"
             );
             return Ok((false, U256::ZERO, u32::MAX));
         }
 
-        let finalized_l2_head_block_number = self
-            .host
-            .get_finalized_l2_block_number(&self.fetcher, canonical_head_l2_block.to::<u64>())
-            .await?;
+        let Some(finalized_l2_block) =
+            self.host.get_finalized_l2_block_number(&self.fetcher, canonical_head_l2_block).await?
+        else {
+            return Ok((false, U256::ZERO, u32::MAX));
+        };
 
-        Ok((
-            finalized_l2_head_block_number
-                .map(|finalized_block| {
-                    U256::from(finalized_block) >= next_l2_block_number_for_proposal
-                })
-                .unwrap_or(false),
-            next_l2_block_number_for_proposal,
-            parent_game_index,
-        ))
+        // The age of the canonical head only matters for the time interval.
+        let head_age_secs = match self.config.proposal_interval_secs {
+            Some(_) => {
+                let head = self
+                    .l2_provider
+                    .get_l2_block_by_number(BlockNumberOrTag::Number(canonical_head_l2_block))
+                    .await?;
+                let now =
+                    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+                now.saturating_sub(head.header.timestamp)
+            }
+            None => 0,
+        };
+
+        let Some((next_l2_block, trigger)) = next_proposal(
+            self.config.proposal_interval_in_blocks,
+            self.config.proposal_interval_secs,
+            self.config.proposal_max_lag_blocks,
+            canonical_head_l2_block,
+            head_age_secs,
+            finalized_l2_block,
+        ) else {
+            return Ok((false, U256::ZERO, u32::MAX));
+        };
+
+        tracing::info!(
+            ?trigger,
+            canonical_head_l2_block,
+            head_age_secs,
+            finalized_l2_block,
+            next_l2_block,
+            "Proposal due"
+        );
+        Ok((true, U256::from(next_l2_block), parent_game_index))
+    }
+
+    /// Read the signer's L1 balance and the requester's PROVE balance into the balance monitor.
+    async fn refresh_balances(&self) -> Result<()> {
+        let l1_balance = self.l1_provider.get_balance(self.signer.address()).await?;
+        self.balance_monitor.record_l1(l1_balance);
+        ProposerGauge::SignerBalanceEth.set(wei_to_eth(l1_balance));
+
+        if let Some(prove_balance) = self.prover.prove_balance().await? {
+            self.balance_monitor.record_prove(prove_balance);
+            ProposerGauge::ProveBalance.set(wei_to_eth(prove_balance));
+        }
+
+        Ok(())
+    }
+
+    /// Whether the balances allow posting a game's bond. See [`crate::balance`].
+    fn bond_affordable(&self) -> bool {
+        // Without a loaded game implementation there's no bond to check; creation fails on its
+        // own.
+        let Ok(game_impl) = self.game_implementation() else { return true };
+        let affordable = self
+            .balance_monitor
+            .check(
+                game_impl.init_bond,
+                self.config.l1_balance_reserve,
+                self.config.min_prove_balance,
+            )
+            .is_ok();
+        ProposerGauge::BalancePaused.set(if affordable { 0.0 } else { 1.0 });
+        affordable
+    }
+
+    /// Read the signer's permission to propose from the game implementation's access manager.
+    async fn refresh_proposer_permission(&self) -> Result<()> {
+        // A guardian never creates games, so it needs no permission to propose.
+        if self.config.guardian.is_some() {
+            return Ok(());
+        }
+        let access_manager = self.game_implementation()?.access_manager;
+        let permission = self.fetch_proposer_permission(access_manager).await?;
+        self.set_proposer_permission(permission);
+        Ok(())
+    }
+
+    async fn fetch_proposer_permission(
+        &self,
+        access_manager: Address,
+    ) -> Result<ProposerPermission> {
+        let access_manager = AccessManager::new(access_manager, self.factory.provider().clone());
+        ProposerPermission::fetch(&access_manager, self.signer.address()).await
+    }
+
+    /// Store the permission to propose, logging when game creation pauses or resumes because of
+    /// it. See [`crate::access`].
+    fn set_proposer_permission(&self, permission: ProposerPermission) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        let secs_until_permissionless = permission.secs_until_permissionless(now);
+        ProposerGauge::ProposerAllowlisted.set(if permission.allowlisted { 1.0 } else { 0.0 });
+        ProposerGauge::PermissionlessProposing.set(if permission.permissionless {
+            1.0
+        } else {
+            0.0
+        });
+        ProposerGauge::SecondsUntilPermissionless.set(secs_until_permissionless as f64);
+
+        let previous = self
+            .proposer_permission
+            .write()
+            .expect("proposer permission lock poisoned")
+            .replace(permission);
+        let was_allowed = previous.map(|previous| previous.allowed);
+        match (was_allowed, permission.allowed) {
+            (Some(true) | None, false) => tracing::error!(
+                proposer = %self.signer.address(),
+                secs_until_permissionless,
+                "Proposer is not allowed to propose by the access manager, pausing game creation until it is allowlisted or permissionless proposing opens"
+            ),
+            (Some(false) | None, true) if !permission.allowlisted => tracing::info!(
+                "No game proposed within the fallback timeout, proposing permissionlessly"
+            ),
+            (Some(false), true) => {
+                tracing::info!("Proposer allowlisted, resuming game creation")
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether the signer may create games. An unread permission passes, since creation fails on
+    /// its own.
+    fn proposal_permitted(&self) -> bool {
+        self.proposer_permission
+            .read()
+            .expect("proposer permission lock poisoned")
+            .is_none_or(|permission| permission.allowed)
+    }
+
+    /// Record a transaction sent for `game_address` in the ledger.
+    fn record_ledger_tx(
+        &self,
+        game_address: Address,
+        kind: LedgerTxKind,
+        receipt: &TransactionReceipt,
+    ) {
+        self.ledger.record_tx(
+            &game_address.to_string(),
+            kind,
+            receipt.transaction_hash,
+            receipt.gas_used,
+            receipt.effective_gas_price,
+        );
+        self.update_ledger_metrics();
+    }
+
+    /// Set the ledger gauges from the ledger totals.
+    fn update_ledger_metrics(&self) {
+        let totals = self.ledger.totals();
+        ProposerGauge::LedgerBondsPostedEth.set(wei_to_eth(totals.bond_posted_wei));
+        ProposerGauge::LedgerCreditClaimedEth.set(wei_to_eth(totals.credit_claimed_wei));
+        ProposerGauge::LedgerGasSpentEth.set(wei_to_eth(totals.gas_spent_wei));
+        ProposerGauge::LedgerNetEth.set(totals.net_eth());
+        ProposerGauge::LedgerProofCostProve.set(wei_to_eth(totals.proof_cost_prove_wei));
     }
 
     /// Backup proposer state to disk in background. Skips if backup already in progress.
     async fn backup(&self) {
-        let Some(path) = &self.config.backup_path else { return };
-
         let Ok(permit) = self.backup_semaphore.clone().try_acquire_owned() else {
             tracing::debug!("Skipping backup: previous backup still in progress");
             return;
         };
+        self.write_backup(permit).await;
+    }
+
+    /// Backup proposer state to disk in background, waiting for a backup in progress to finish
+    /// instead of skipping, so the state saved includes the latest changes.
+    async fn save_backup(&self) {
+        let Ok(permit) = self.backup_semaphore.clone().acquire_owned().await else { return };
+        self.write_backup(permit).await;
+    }
+
+    /// Write the proposer state to the backup file on a blocking thread, holding `permit` until
+    /// it is written.
+    async fn write_backup(&self, permit: OwnedSemaphorePermit) {
+        let Some(path) = &self.config.backup_path else { return };
 
         let mut backup = self.state.read().await.to_backup();
         backup.last_created_game_l2_block = self.last_created_game_l2_block.load(Ordering::Relaxed);
         backup.last_created_game_address = *self.last_created_game_address.lock().await;
+        if let Some(pending) = self.prover.pending_proofs() {
+            let games: HashSet<Address> = backup.games.iter().map(|g| g.address).collect();
+            backup.pending_proofs = pending.snapshot(|game| games.contains(&game));
+        }
         let path = path.clone();
         tokio::task::spawn_blocking(move || {
             if let Err(e) = backup.save(&path) {
@@ -2170,53 +2865,139 @@ where
         });
     }
 
-    /// Spawn game defense tasks if needed
+    /// Spawn defense and fast finality proving tasks, sharing the proving slots between them
+    /// earliest deadline first. See [`crate::scheduler`].
     ///
     /// Returns:
-    /// - Ok(true): Defense task was successfully spawned
-    /// - Ok(false): No work needed (no defensible games or task already exists)
+    /// - Ok(true): A proving task was successfully spawned
+    /// - Ok(false): No work needed (no provable games or no free slot)
     /// - Err: Actual error occurred during task spawning
-    #[tracing::instrument(name = "[[Defending]]", skip(self))]
-    async fn spawn_game_defense_tasks(&self) -> Result<bool> {
-        // Check if there are games needing defense
-        let mut candidates = {
+    #[tracing::instrument(name = "[[Proving]]", skip(self))]
+    async fn spawn_game_proving_tasks(&self) -> Result<bool> {
+        let capacity = ProvingCapacity {
+            defense: self.config.max_concurrent_defense_tasks,
+            fast_finality: if self.config.fast_finality_mode {
+                self.config.fast_finality_proving_limit
+            } else {
+                0
+            },
+        };
+
+        let (mut candidates, running) = {
             let state = self.state.read().await;
-            state
+            let tasks = self.tasks.lock().await;
+
+            let running = tasks
+                .values()
+                .filter_map(|(_, info)| match info {
+                    TaskInfo::GameProving { game_address, is_defense } => Some(RunningProving {
+                        game_address: *game_address,
+                        deadline: state
+                            .games
+                            .values()
+                            .find(|game| game.address == *game_address)
+                            .map_or(u64::MAX, |game| game.deadline),
+                        is_defense: *is_defense,
+                    }),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            let proving_set =
+                running.iter().map(|proving| proving.game_address).collect::<HashSet<_>>();
+
+            // Challenged games are defended. Unchallenged games we created are proven for fast
+            // finality, resuming games whose proving was interrupted, e.g. by a restart or a
+            // preemption.
+            let signer_address = self.signer.address();
+            let candidates = state
                 .games
                 .values()
                 .filter(|game| game.status == GameStatus::IN_PROGRESS)
-                .filter(|game| matches!(game.proposal_status, ProposalStatus::Challenged))
-                .map(|game| (game.index, game.address, game.deadline))
-                .collect::<Vec<_>>()
+                .filter(|game| !proving_set.contains(&game.address))
+                .filter_map(|game| {
+                    let is_defense = match game.proposal_status {
+                        ProposalStatus::Challenged => true,
+                        ProposalStatus::Unchallenged if game.creator == signer_address => false,
+                        _ => return None,
+                    };
+                    Some(ProvingCandidate {
+                        index: game.index,
+                        game_address: game.address,
+                        deadline: game.deadline,
+                        is_defense,
+                    })
+                })
+                .collect::<Vec<_>>();
+            (candidates, running)
         };
-        // Sort by deadline ascending to prioritize games closest to expiring
-        candidates.sort_unstable_by_key(|(_, _, deadline)| *deadline);
 
-        let mut active_defense_tasks_count = self.count_active_defense_tasks().await;
-        let max_concurrent = self.config.max_concurrent_defense_tasks;
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+
+        // Proof requests pause while the PROVE balance is below the minimum, except for defenses
+        // within LOW_PROVE_BALANCE_DEFENSE_WINDOW_SECS of their deadline, which lose their bond
+        // otherwise. See [`crate::balance`].
+        let proving_paused =
+            self.balance_monitor.check_proving(self.config.min_prove_balance).is_err();
+        ProposerGauge::ProvingBalancePaused.set(if proving_paused { 1.0 } else { 0.0 });
+        if proving_paused {
+            let window = self.config.low_prove_balance_defense_window_secs;
+            candidates.retain(|candidate| {
+                candidate.is_defense &&
+                    window.is_some_and(|window| candidate.deadline.saturating_sub(now) <= window)
+            });
+        }
 
-        let mut tasks_spawned = false;
+        // Fast finality proving never preempts, so its candidates only matter with a free slot.
+        let fast_finality_running =
+            running.iter().filter(|proving| !proving.is_defense).count() as u64;
+        if fast_finality_running >= capacity.fast_finality {
+            candidates.retain(|candidate| candidate.is_defense);
+        }
 
-        for (index, game_address, deadline) in candidates {
-            if active_defense_tasks_count >= max_concurrent {
-                tracing::debug!(
-                    "The max concurrent defense tasks count ({}) has been reached",
-                    max_concurrent
-                );
-                break;
+        // A defense already being proven is judged as if it started now, which errs on the side
+        // of keeping fast finality proving out of its way. Games past their deadline can't be
+        // saved and don't preempt fast finality proving.
+        let estimate = self.proving_time_estimator.estimate();
+        let is_at_risk = |candidate: &ProvingCandidate| {
+            candidate.is_defense &&
+                candidate.deadline > now &&
+                check_deadline_risk(now, candidate.deadline, estimate).is_urgent()
+        };
+        let at_risk = candidates.iter().any(is_at_risk) ||
+            running.iter().any(|proving| {
+                proving.is_defense &&
+                    proving.deadline > now &&
+                    check_deadline_risk(now, proving.deadline, estimate).is_urgent()
+            });
+        if at_risk != self.defense_at_risk.swap(at_risk, Ordering::Relaxed) {
+            if at_risk {
+                tracing::warn!("Defense deadline at risk, prioritizing defense over fast finality");
+            } else {
+                tracing::info!("Defense deadlines on track");
             }
+        }
 
-            if self.has_active_proving_for_game(game_address).await {
-                continue;
-            }
+        let plan = plan_proving(candidates, &running, capacity, is_at_risk);
+        if !plan.preempt.is_empty() {
+            self.preempt_fast_finality_proving(&plan.preempt).await;
+        }
 
-            if self.spawn_game_proving_task(game_address, true, Some(deadline)).await? {
-                tracing::info!(
-                    game_address = ?game_address,
-                    game_index = %index,
-                    "Spawned defense for challenged game"
-                );
-                active_defense_tasks_count += 1;
+        let mut tasks_spawned = false;
+        for ProvingCandidate { index, game_address, deadline, is_defense } in plan.spawn {
+            if self.spawn_game_proving_task(game_address, is_defense, Some(deadline)).await? {
+                if is_defense {
+                    tracing::info!(
+                        game_address = ?game_address,
+                        game_index = %index,
+                        "Spawned defense for challenged game"
+                    );
+                } else {
+                    tracing::info!(
+                        game_address = ?game_address,
+                        game_index = %index,
+                        "Resumed fast finality proving for existing game"
+                    );
+                }
                 tasks_spawned = true;
             }
         }
@@ -2224,6 +3005,46 @@ where
         Ok(tasks_spawned)
     }
 
+    /// Abort the fast finality proving of `games` to free their slots for defense, cancelling
+    /// their outstanding network proof requests so the network stops proving them. The cluster
+    /// can't be asked to cancel a request, so cluster requests are kept and re-attached to when
+    /// the game is resumed. Mock proving runs on blocking threads that can't be aborted, so it is
+    /// left to finish.
+    async fn preempt_fast_finality_proving(&self, games: &[Address]) {
+        if self.config.mock_mode {
+            return;
+        }
+
+        let preempted = {
+            let mut tasks = self.tasks.lock().await;
+            let ids = tasks
+                .iter()
+                .filter_map(|(id, (_, info))| match info {
+                    TaskInfo::GameProving { game_address, is_defense: false }
+                        if games.contains(game_address) =>
+                    {
+                        Some(*id)
+                    }
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            ids.into_iter()
+                .filter_map(|id| tasks.remove(&id))
+                .map(|(handle, info)| {
+                    handle.abort();
+                    info
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for info in preempted {
+            let TaskInfo::GameProving { game_address, .. } = info else { continue };
+            tracing::warn!(?game_address, "Preempted fast finality proving for defense");
+            ProposerGauge::FastFinalityProvingPreempted.increment(1.0);
+            self.prover.cancel_pending_proofs(game_address).await;
+        }
+    }
+
     /// Check if there's an active proving task for a specific game
     async fn has_active_proving_for_game(&self, game_address: Address) -> bool {
         let tasks = self.tasks.lock().await;
@@ -2255,6 +3076,17 @@ where
         let start_block = starting_l2_block_number.to::<u64>();
         let end_block = l2_block_number.to::<u64>();
 
+        if is_defense &&
+            !self.should_guardian_defend(game_address, deadline, start_block, end_block)?
+        {
+            return Ok(false);
+        }
+
+        // A resumed proving only waits for part of the work, so its duration would skew the
+        // proving time estimate.
+        let resumed =
+            self.prover.pending_proofs().is_some_and(|pending| pending.has_game(game_address));
+
         tracing::info!(
             "Spawning game proving task {} for game {:?} (blocks {}-{})",
             task_id,
@@ -2277,6 +3109,9 @@ where
                     // Record successful proving
                     ProposerGauge::GamesProven.increment(1.0);
                     ProposerGauge::ProvingDurationSeconds.set(start_time.elapsed().as_secs_f64());
+                    if !resumed {
+                        proposer.proving_time_estimator.record(start_time.elapsed());
+                    }
 
                     tracing::info!(
                         game_address = ?game_address,
@@ -2300,6 +3135,9 @@ where
                 // Record successful proving
                 ProposerGauge::GamesProven.increment(1.0);
                 ProposerGauge::ProvingDurationSeconds.set(start_time.elapsed().as_secs_f64());
+                if !resumed {
+                    proposer.proving_time_estimator.record(start_time.elapsed());
+                }
 
                 tracing::info!(
                     game_address = ?game_address,
@@ -2320,6 +3158,68 @@ where
         Ok(true)
     }
 
+    /// Check whether a guardian should defend a challenged game now, recording the defense against
+    /// its budget if so. Retries of a defense are only counted once. Always true outside guardian
+    /// mode.
+    fn should_guardian_defend(
+        &self,
+        game_address: Address,
+        deadline: Option<u64>,
+        start_block: u64,
+        end_block: u64,
+    ) -> Result<bool> {
+        let (Some(guardian), Some(deadline)) = (&self.config.guardian, deadline) else {
+            return Ok(true);
+        };
+
+        let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
+        // A challenge resets the deadline to the challenge time plus the max prove duration.
+        let max_prove_duration = self.game_implementation()?.contract_params.max_prove_duration;
+        let challenged_at = deadline.saturating_sub(max_prove_duration);
+        let deadline_at_risk =
+            check_deadline_risk(now, deadline, self.proving_time_estimator.estimate()).is_urgent();
+
+        match guardian.decide(
+            game_address,
+            now,
+            challenged_at,
+            deadline_at_risk,
+            end_block - start_block,
+            &self.guardian_budget,
+        ) {
+            GuardianDecision::Defend => {
+                tracing::info!(?game_address, "Guardian defending challenged game");
+                self.guardian_budget.record(game_address, now);
+                Ok(true)
+            }
+            GuardianDecision::Wait { remaining_secs } => {
+                tracing::debug!(
+                    ?game_address,
+                    remaining_secs,
+                    "Guardian leaving challenged game to its proposer during the grace period"
+                );
+                Ok(false)
+            }
+            GuardianDecision::TooLarge { blocks } => {
+                tracing::warn!(
+                    ?game_address,
+                    blocks,
+                    max_game_blocks = ?guardian.max_game_blocks,
+                    "Guardian not defending challenged game, range exceeds the limit"
+                );
+                Ok(false)
+            }
+            GuardianDecision::OverBudget => {
+                tracing::warn!(
+                    ?game_address,
+                    max_defenses_per_day = ?guardian.max_defenses_per_day,
+                    "Guardian not defending challenged game, daily defense limit reached"
+                );
+                Ok(false)
+            }
+        }
+    }
+
     /// Check if proving should be skipped for any reason.
     ///
     /// Returns `Ok(true)` if proving should be skipped:
@@ -2328,7 +3228,7 @@ where
     /// - Deadline has passed
     ///
     /// Returns `Ok(false)` if proving should proceed.
-    /// Logs a warning if the deadline is approaching.
+    /// Logs a warning if the deadline is approaching or proving is expected to miss it.
     async fn should_skip_proving(
         &self,
         game_address: Address,
@@ -2341,7 +3241,9 @@ where
             let game = state.games.values().find(|g| g.address == game_address);
 
             match game {
-                Some(game) if !game.is_owned(&self.identity) => {
+                // Only the active key set can prove, so games of a previous implementation are
+                // skipped even if they were ours.
+                Some(game) if !game.is_owned(&self.identity()) => {
                     tracing::info!(?game_address, "Skipping foreign game (vkey mismatch)");
                     return Ok(true);
                 }
@@ -2357,8 +3259,7 @@ where
         if let Some(deadline) = deadline {
             let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_secs();
 
-            let contract_params =
-                self.contract_params.get().context("contract_params must be set via try_init")?;
+            let contract_params = self.game_implementation()?.contract_params;
             let max_duration = if is_defense {
                 contract_params.max_prove_duration
             } else {
@@ -2388,6 +3289,20 @@ where
                 }
                 DeadlineStatus::Ok => {}
             }
+
+            let estimate = self.proving_time_estimator.estimate();
+            if let DeadlineRisk::ExpectedMiss { shortfall_secs } =
+                check_deadline_risk(now, deadline, estimate)
+            {
+                tracing::warn!(
+                    game_address = ?game_address,
+                    is_defense = is_defense,
+                    estimated_proving_secs = estimate.as_secs(),
+                    shortfall_secs = shortfall_secs,
+                    "Proving is expected to finish after the game deadline"
+                );
+                ProposerGauge::DeadlineExpectedMiss.increment(1.0);
+            }
         }
 
         Ok(false)
@@ -2486,6 +3401,72 @@ impl From<U256> for Cursor {
     }
 }
 
+/// The key set whose identity matches the given on-chain vkeys and rollup config hash.
+fn find_key_set<K>(
+    key_sets: &[(ProposerIdentity, K)],
+    aggregation_vkey: B256,
+    range_vkey_commitment: B256,
+    rollup_config_hash: B256,
+) -> Option<&(ProposerIdentity, K)> {
+    key_sets.iter().find(|(identity, _)| {
+        identity.matches(aggregation_vkey, range_vkey_commitment, rollup_config_hash)
+    })
+}
+
+/// The L1 block range to scan for game implementation changes after `last_scanned`, capped at
+/// [`GAME_IMPL_WATCH_MAX_BLOCK_RANGE`] blocks. None if there is no new block.
+fn game_impl_watch_range(last_scanned: u64, latest_block: u64) -> Option<(u64, u64)> {
+    let from_block = last_scanned + 1;
+    let to_block = latest_block.min(from_block.saturating_add(GAME_IMPL_WATCH_MAX_BLOCK_RANGE - 1));
+    (from_block <= to_block).then_some((from_block, to_block))
+}
+
+/// Why a proposal is due. See [`next_proposal`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProposalTrigger {
+    /// The finalized head is `proposal_interval_in_blocks` past the canonical head.
+    Blocks,
+    /// The finalized head is `proposal_max_lag_blocks` past the canonical head.
+    MaxLag,
+    /// The canonical head is `proposal_interval_secs` old.
+    Time,
+}
+
+/// Decide which L2 block to propose next, if any.
+///
+/// The block interval proposes exactly `interval_blocks` past the canonical head, keeping
+/// proposals evenly spaced. Otherwise, the max-lag guard and the time interval propose the
+/// finalized head: when it is at least `max_lag_blocks` ahead of the canonical head, or when the
+/// canonical head is at least `interval_secs` old. A block interval of 0 disables it.
+pub fn next_proposal(
+    interval_blocks: u64,
+    interval_secs: Option<u64>,
+    max_lag_blocks: Option<u64>,
+    head: u64,
+    head_age_secs: u64,
+    finalized: u64,
+) -> Option<(u64, ProposalTrigger)> {
+    let lag = finalized.checked_sub(head).filter(|&lag| lag > 0)?;
+
+    if interval_blocks > 0 && lag >= interval_blocks {
+        return Some((head + interval_blocks, ProposalTrigger::Blocks));
+    }
+    if max_lag_blocks.is_some_and(|max_lag| lag >= max_lag) {
+        return Some((finalized, ProposalTrigger::MaxLag));
+    }
+    if interval_secs.is_some_and(|interval| head_age_secs >= interval) {
+        return Some((finalized, ProposalTrigger::Time));
+    }
+    None
+}
+
+/// Whether the game created last, for `last_created_l2_block`, is beyond the canonical head and
+/// so not yet visible in the pinned sync cache. Creating a game then would propose the same block
+/// again.
+pub fn created_game_pending(last_created_l2_block: u64, canonical_head_l2_block: u64) -> bool {
+    last_created_l2_block > canonical_head_l2_block
+}
+
 /// Result of checking a game's deadline status.
 #[derive(Debug, Clone, PartialEq)]
 pub enum DeadlineStatus {
@@ -2538,14 +3519,19 @@ impl ProposerState {
             // state.
             canonical_head_index: None,
             canonical_head_l2_block: None,
+            improper_games: HashSet::new(),
+            blacklisted_games: HashSet::new(),
         }
     }
 
     /// Try to restore state from a backup file. Returns None if file doesn't exist or is invalid.
-    pub fn try_restore(path: &Path) -> Option<(Self, u64, Address)> {
-        let backup = ProposerBackup::load(path)?;
+    ///
+    /// Also returns the creation guard and the outstanding proof requests.
+    pub fn try_restore(path: &Path) -> Option<(Self, u64, Address, Vec<PendingProof>)> {
+        let mut backup = ProposerBackup::load(path)?;
         let last_created_l2 = backup.last_created_game_l2_block;
         let last_created_addr = backup.last_created_game_address;
+        let pending_proofs = std::mem::take(&mut backup.pending_proofs);
         let state = Self::from_backup(backup);
         tracing::info!(
             ?path,
@@ -2555,7 +3541,7 @@ impl ProposerState {
             ?last_created_addr,
             "Proposer state restored from backup"
         );
-        Some((state, last_created_l2, last_created_addr))
+        Some((state, last_created_l2, last_created_addr, pending_proofs))
     }
 }
 
@@ -2628,6 +3614,103 @@ mod tests {
         }
     }
 
+    mod proposal_schedule_tests {
+        use super::super::{created_game_pending, next_proposal, ProposalTrigger};
+        use rstest::rstest;
+
+        const HEAD: u64 = 1000;
+        const MINUTE: u64 = 60;
+
+        #[rstest]
+        #[case::blocks_due(100, None, None, 0, HEAD + 150, Some((HEAD + 100, ProposalTrigger::Blocks)))]
+        #[case::blocks_not_due(100, None, None, 0, HEAD + 99, None)]
+        #[case::time_due(0, Some(30 * MINUTE), None, 30 * MINUTE, HEAD + 10, Some((HEAD + 10, ProposalTrigger::Time)))]
+        #[case::time_not_due(0, Some(30 * MINUTE), None, 29 * MINUTE, HEAD + 10, None)]
+        #[case::hybrid_blocks_first(100, Some(30 * MINUTE), None, 10 * MINUTE, HEAD + 100, Some((HEAD + 100, ProposalTrigger::Blocks)))]
+        #[case::hybrid_time_first(100, Some(30 * MINUTE), None, 30 * MINUTE, HEAD + 50, Some((HEAD + 50, ProposalTrigger::Time)))]
+        #[case::max_lag(0, Some(30 * MINUTE), Some(500), MINUTE, HEAD + 500, Some((HEAD + 500, ProposalTrigger::MaxLag)))]
+        #[case::max_lag_not_reached(0, Some(30 * MINUTE), Some(500), MINUTE, HEAD + 499, None)]
+        #[case::finalized_at_head(0, Some(30 * MINUTE), None, 60 * MINUTE, HEAD, None)]
+        #[case::finalized_behind_head(100, Some(30 * MINUTE), Some(1), 60 * MINUTE, HEAD - 1, None)]
+        fn test_next_proposal(
+            #[case] interval_blocks: u64,
+            #[case] interval_secs: Option<u64>,
+            #[case] max_lag_blocks: Option<u64>,
+            #[case] head_age_secs: u64,
+            #[case] finalized: u64,
+            #[case] expected: Option<(u64, ProposalTrigger)>,
+        ) {
+            let next = next_proposal(
+                interval_blocks,
+                interval_secs,
+                max_lag_blocks,
+                HEAD,
+                head_age_secs,
+                finalized,
+            );
+            assert_eq!(next, expected);
+        }
+
+        #[rstest]
+        #[case::none_created(0, false)]
+        #[case::created_behind_head(HEAD - 100, false)]
+        #[case::created_at_head(HEAD, false)]
+        #[case::created_beyond_head(HEAD + 100, true)]
+        fn test_created_game_pending(#[case] last_created: u64, #[case] expected: bool) {
+            assert_eq!(created_game_pending(last_created, HEAD), expected);
+        }
+    }
+
+    mod game_implementation_tests {
+        use super::super::{
+            find_key_set, game_impl_watch_range, ProposerIdentity, GAME_IMPL_WATCH_MAX_BLOCK_RANGE,
+        };
+        use alloy_primitives::B256;
+        use rstest::rstest;
+
+        fn identity(byte: u8) -> ProposerIdentity {
+            ProposerIdentity::new(B256::repeat_byte(byte), B256::repeat_byte(byte), B256::ZERO)
+        }
+
+        #[rstest]
+        #[case::embedded(1, Some("embedded"))]
+        #[case::alternate(2, Some("alternate"))]
+        #[case::unknown(3, None)]
+        fn test_find_key_set(#[case] byte: u8, #[case] expected: Option<&str>) {
+            let key_sets = [(identity(1), "embedded"), (identity(2), "alternate")];
+            let found = find_key_set(
+                &key_sets,
+                B256::repeat_byte(byte),
+                B256::repeat_byte(byte),
+                B256::ZERO,
+            );
+            assert_eq!(found.map(|(_, keys)| *keys), expected);
+        }
+
+        #[test]
+        fn test_find_key_set_requires_all_commitments() {
+            let key_sets = [(identity(1), "embedded")];
+            let one = B256::repeat_byte(1);
+            assert!(find_key_set(&key_sets, one, B256::repeat_byte(2), B256::ZERO).is_none());
+            assert!(find_key_set(&key_sets, B256::repeat_byte(2), one, B256::ZERO).is_none());
+            assert!(find_key_set(&key_sets, one, one, B256::repeat_byte(1)).is_none());
+        }
+
+        #[rstest]
+        #[case::new_blocks(100, 150, Some((101, 150)))]
+        #[case::single_block(100, 101, Some((101, 101)))]
+        #[case::no_new_blocks(100, 100, None)]
+        #[case::behind(100, 90, None)]
+        #[case::capped(0, 50_000, Some((1, GAME_IMPL_WATCH_MAX_BLOCK_RANGE)))]
+        fn test_game_impl_watch_range(
+            #[case] last_scanned: u64,
+            #[case] latest_block: u64,
+            #[case] expected: Option<(u64, u64)>,
+        ) {
+            assert_eq!(game_impl_watch_range(last_scanned, latest_block), expected);
+        }
+    }
+
     mod proving_deadline_tests {
         use super::super::{check_deadline_status, DeadlineStatus};
         use rstest::rstest;
@@ -2665,4 +3748,74 @@ mod tests {
             }
         }
     }
+
+    mod canonical_head_tests {
+        use super::super::{Game, ProposerState};
+        use crate::contract::{GameStatus, ProposalStatus};
+        use alloy_primitives::{Address, B256, U256};
+
+        fn game(index: u64, parent_index: u32, l2_block: u64) -> Game {
+            Game {
+                index: U256::from(index),
+                address: Address::with_last_byte(index as u8),
+                creator: Address::ZERO,
+                created_at: 0,
+                parent_index,
+                l2_block: U256::from(l2_block),
+                status: GameStatus::IN_PROGRESS,
+                proposal_status: ProposalStatus::Unchallenged,
+                deadline: 0,
+                should_attempt_to_resolve: false,
+                should_attempt_to_claim_bond: false,
+                aggregation_vkey: B256::ZERO,
+                range_vkey_commitment: B256::ZERO,
+                rollup_config_hash: B256::ZERO,
+            }
+        }
+
+        /// Anchor 0, with a chain 0 <- 1 <- 2 <- 3 and a fork 1 <- 4.
+        fn state(improper: &[u64]) -> ProposerState {
+            let games = [
+                game(0, u32::MAX, 10),
+                game(1, 0, 20),
+                game(2, 1, 30),
+                game(3, 2, 40),
+                game(4, 1, 25),
+            ]
+            .into_iter()
+            .map(|game| (game.index, game))
+            .collect();
+            ProposerState {
+                anchor_game: Some(game(0, u32::MAX, 10)),
+                games,
+                improper_games: improper.iter().map(|&index| U256::from(index)).collect(),
+                ..Default::default()
+            }
+        }
+
+        fn head(state: &ProposerState) -> Option<u64> {
+            state.select_canonical_head().map(|game| game.index.to::<u64>())
+        }
+
+        #[test]
+        fn test_canonical_head_without_improper_games() {
+            assert_eq!(head(&state(&[])), Some(3));
+        }
+
+        #[test]
+        fn test_improper_subtree_excluded_from_canonical_head() {
+            let state = state(&[2]);
+            assert_eq!(
+                state.improper_subtrees(),
+                [2, 3].into_iter().map(U256::from).collect(),
+                "the improper game's descendants can't be built on either"
+            );
+            assert_eq!(head(&state), Some(4));
+        }
+
+        #[test]
+        fn test_improper_child_of_anchor_leaves_anchor_as_head() {
+            assert_eq!(head(&state(&[1])), Some(0));
+        }
+    }
 }
@@ -1,9 +1,16 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
-use alloy_primitives::B256;
+use alloy_primitives::{Address, B256, U256};
 use anyhow::{bail, Context, Result};
-use op_succinct_host_utils::metrics::MetricsGauge;
-use op_succinct_proof_utils::{cluster_agg_proof, cluster_range_proof, get_range_elf_embedded};
+use op_succinct_host_utils::{ledger::Ledger, metrics::MetricsGauge};
+use op_succinct_proof_utils::{
+    cluster_poll_proof, cluster_submit_agg_proof, cluster_submit_range_proof,
+    reconstruct_proof_request, ClusterProofConfig, ClusterProofHandleJson,
+};
+use sp1_cluster_utils::ProofRequest;
 use sp1_sdk::{
     network::{proto::types::FulfillmentStatus, NetworkMode},
     Elf, NetworkProver, ProveRequest, Prover, SP1ProofMode, SP1ProofWithPublicValues,
@@ -11,13 +18,20 @@ use sp1_sdk::{
 };
 use tokio::time::sleep;
 
-use crate::{config::ProofProviderConfig, prometheus::ProposerGauge};
+use crate::{
+    config::ProofProviderConfig,
+    pending_proofs::{PendingProof, PendingProofKey, PendingProofRequest, PendingProofs},
+    prometheus::ProposerGauge,
+};
 
 /// Polling interval (in seconds) for checking proof status.
 /// Matches the SP1 SDK's internal polling interval:
 /// https://github.com/succinctlabs/sp1/blob/dev/crates/sdk/src/network/prover.rs#L551
 pub const PROOF_STATUS_POLL_INTERVAL: u64 = 2;
 
+/// Number of consecutive failed polls before a cluster proof is considered failed.
+const MAX_CLUSTER_POLL_FAILURES: u32 = 3;
+
 /// Unique identifier for a proof request.
 pub type ProofId = B256;
 
@@ -31,15 +45,38 @@ fn current_timestamp() -> u64 {
         .as_secs()
 }
 
-/// Container for proving and verifying keys.
+/// Container for proving and verifying keys, along with the ELFs they were set up from.
 #[derive(Clone)]
 pub struct ProofKeys {
+    pub range_elf: &'static [u8],
+    pub agg_elf: &'static [u8],
     pub range_pk: Arc<SP1ProvingKey>,
     pub range_vk: Arc<SP1VerifyingKey>,
     pub agg_pk: Arc<SP1ProvingKey>,
     pub agg_vk: Arc<SP1VerifyingKey>,
 }
 
+/// Proof keys shared between clones of a provider, so that a key swap is seen by every task
+/// started after it.
+#[derive(Clone)]
+pub struct SharedProofKeys(Arc<RwLock<ProofKeys>>);
+
+impl SharedProofKeys {
+    pub fn new(keys: ProofKeys) -> Self {
+        Self(Arc::new(RwLock::new(keys)))
+    }
+
+    /// A snapshot of the current keys.
+    pub fn get(&self) -> ProofKeys {
+        self.0.read().expect("proof keys lock poisoned").clone()
+    }
+
+    /// Replace the keys used for proofs requested from now on.
+    pub fn set(&self, keys: ProofKeys) {
+        *self.0.write().expect("proof keys lock poisoned") = keys;
+    }
+}
+
 /// Proof provider abstraction for generating range and aggregation proofs.
 ///
 /// This enum wraps the concrete provider implementations, allowing the proposer
@@ -60,15 +97,18 @@ impl ProofProvider {
     /// In mock mode: executes locally and returns execution stats.
     /// In network mode: submits to network, waits for completion, returns (proof, 0, 0).
     ///
+    /// The request is recorded under `key` until it completes (see [`Self::resume_proof`]).
+    ///
     /// Returns: (proof, instruction_cycles, sp1_gas)
     pub async fn generate_range_proof(
         &self,
+        key: PendingProofKey,
         stdin: SP1Stdin,
     ) -> Result<(SP1ProofWithPublicValues, u64, u64)> {
         match self {
-            ProofProvider::Network(p) => p.generate_range_proof(stdin).await,
+            ProofProvider::Network(p) => p.generate_range_proof(key, stdin).await,
             ProofProvider::Mock(p) => p.generate_range_proof(stdin).await,
-            ProofProvider::Cluster(p) => p.generate_range_proof(stdin).await,
+            ProofProvider::Cluster(p) => p.generate_range_proof(key, stdin).await,
         }
     }
 
@@ -77,16 +117,66 @@ impl ProofProvider {
     /// In mock mode: executes locally and creates mock proof.
     /// In network mode: submits to network, waits for completion.
     /// In cluster mode: submits to self-hosted cluster, waits for completion.
-    pub async fn generate_agg_proof(&self, stdin: SP1Stdin) -> Result<SP1ProofWithPublicValues> {
+    ///
+    /// The request is recorded under `key` until it completes (see [`Self::resume_proof`]).
+    pub async fn generate_agg_proof(
+        &self,
+        key: PendingProofKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1ProofWithPublicValues> {
         match self {
-            ProofProvider::Network(p) => p.generate_agg_proof(stdin).await,
+            ProofProvider::Network(p) => p.generate_agg_proof(key, stdin).await,
             ProofProvider::Mock(p) => p.generate_agg_proof(stdin).await,
-            ProofProvider::Cluster(p) => p.generate_agg_proof(stdin).await,
+            ProofProvider::Cluster(p) => p.generate_agg_proof(key, stdin).await,
         }
     }
 
-    /// Access to proving keys.
-    pub fn keys(&self) -> &ProofKeys {
+    /// Re-attach to the outstanding request for `key`, e.g. one restored from the proposer backup
+    /// after a restart, and wait for its proof.
+    ///
+    /// Returns `None` if there is no outstanding request, or if it failed, in which case the proof
+    /// has to be requested again.
+    pub async fn resume_proof(&self, key: PendingProofKey) -> Option<SP1ProofWithPublicValues> {
+        match self {
+            ProofProvider::Network(p) => p.resume_proof(key).await,
+            ProofProvider::Mock(_) => None,
+            ProofProvider::Cluster(p) => p.resume_proof(key).await,
+        }
+    }
+
+    /// Cancel the outstanding requests for `game_address`, e.g. when its proving was preempted.
+    ///
+    /// Network requests are cancelled so they aren't paid for. The cluster API has no
+    /// cancellation, so cluster requests are kept and re-attached to when the game is proven
+    /// again.
+    pub async fn cancel_pending_proofs(&self, game_address: Address) {
+        match self {
+            ProofProvider::Network(p) => p.cancel_pending_proofs(game_address).await,
+            ProofProvider::Mock(_) | ProofProvider::Cluster(_) => {}
+        }
+    }
+
+    /// The outstanding proof requests. Mock proofs are never outstanding.
+    pub fn pending_proofs(&self) -> Option<&PendingProofs> {
+        match self {
+            ProofProvider::Network(p) => Some(&p.pending),
+            ProofProvider::Mock(_) => None,
+            ProofProvider::Cluster(p) => Some(&p.pending),
+        }
+    }
+
+    /// Snapshot of the current proving keys.
+    pub fn keys(&self) -> ProofKeys {
+        self.shared_keys().get()
+    }
+
+    /// Swap the proving keys, e.g. after the on-chain game implementation changed to one proven
+    /// by a different ELF. Proofs already in flight keep the keys they were requested with.
+    pub fn set_keys(&self, keys: ProofKeys) {
+        self.shared_keys().set(keys)
+    }
+
+    fn shared_keys(&self) -> &SharedProofKeys {
         match self {
             ProofProvider::Network(p) => &p.keys,
             ProofProvider::Mock(p) => &p.keys,
@@ -94,6 +184,15 @@ impl ProofProvider {
         }
     }
 
+    /// The requester's PROVE balance on the prover network. `None` when proofs aren't paid for
+    /// on the network, in mock and cluster modes.
+    pub async fn prove_balance(&self) -> Result<Option<U256>> {
+        match self {
+            ProofProvider::Network(p) => Ok(Some(p.balance().await?)),
+            ProofProvider::Mock(_) | ProofProvider::Cluster(_) => Ok(None),
+        }
+    }
+
     /// Access to configuration.
     pub fn config(&self) -> &ProofProviderConfig {
         match self {
@@ -108,9 +207,12 @@ impl ProofProvider {
 #[derive(Clone)]
 pub struct NetworkProofProvider {
     prover: Arc<NetworkProver>,
-    keys: ProofKeys,
+    keys: SharedProofKeys,
     config: ProofProviderConfig,
     network_mode: NetworkMode,
+    pending: PendingProofs,
+    /// Records the cost of each fulfilled proof request against its game.
+    ledger: Ledger,
 }
 
 impl NetworkProofProvider {
@@ -119,8 +221,16 @@ impl NetworkProofProvider {
         keys: ProofKeys,
         config: ProofProviderConfig,
         network_mode: NetworkMode,
+        ledger: Ledger,
     ) -> Self {
-        Self { prover, keys, config, network_mode }
+        Self {
+            prover,
+            keys: SharedProofKeys::new(keys),
+            config,
+            network_mode,
+            pending: PendingProofs::default(),
+            ledger,
+        }
     }
 
     /// Get a reference to the underlying network prover.
@@ -128,29 +238,128 @@ impl NetworkProofProvider {
         &self.prover
     }
 
+    /// The requester's PROVE balance.
+    pub async fn balance(&self) -> Result<U256> {
+        let timeout_secs = self.config.network_calls_timeout;
+        match tokio::time::timeout(Duration::from_secs(timeout_secs), self.prover.get_balance())
+            .await
+        {
+            Ok(result) => result,
+            Err(_) => {
+                ProposerGauge::NetworkCallTimeout.increment(1.0);
+                bail!("Network timeout after {timeout_secs}s for get_balance")
+            }
+        }
+    }
+
+    /// Cancel and forget the outstanding requests for `game_address`.
+    pub async fn cancel_pending_proofs(&self, game_address: Address) {
+        for pending in self.pending.remove_game(game_address) {
+            let PendingProofRequest::Network { proof_id } = pending.request else {
+                continue;
+            };
+            tracing::info!(?game_address, proof_id = %proof_id, "Cancelling proof request");
+            if let Err(e) = self
+                .network_call_with_timeout(
+                    self.prover.cancel_request(proof_id),
+                    "cancel_request",
+                    proof_id,
+                )
+                .await
+            {
+                tracing::error!(proof_id = %proof_id, error = %e, "Failed to cancel proof request");
+            }
+        }
+    }
+
     /// Generate a range proof via network.
     pub async fn generate_range_proof(
         &self,
+        key: PendingProofKey,
         stdin: SP1Stdin,
     ) -> Result<(SP1ProofWithPublicValues, u64, u64)> {
         tracing::info!("Generating range proof via network");
         let proof_id = self.request_range_proof(stdin).await?;
-        let proof = self.wait_for_proof(proof_id).await?;
+        let proof = self.wait_for_pending_proof(key, proof_id, current_timestamp()).await?;
         Ok((proof, 0, 0))
     }
 
     /// Generate an aggregation proof via network.
-    pub async fn generate_agg_proof(&self, stdin: SP1Stdin) -> Result<SP1ProofWithPublicValues> {
+    pub async fn generate_agg_proof(
+        &self,
+        key: PendingProofKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1ProofWithPublicValues> {
         tracing::info!("Generating aggregation proof via network");
         let proof_id = self.request_agg_proof(stdin).await?;
-        self.wait_for_proof(proof_id).await
+        self.wait_for_pending_proof(key, proof_id, current_timestamp()).await
+    }
+
+    /// Re-attach to the outstanding network request for `key`, if any.
+    async fn resume_proof(&self, key: PendingProofKey) -> Option<SP1ProofWithPublicValues> {
+        let (proof_id, requested_at) = self.pending.network_request(&key)?;
+
+        tracing::info!(proof_id = %proof_id, ?key, "Re-attaching to outstanding proof request");
+        match self.wait_for_pending_proof(key, proof_id, requested_at).await {
+            Ok(proof) => Some(proof),
+            Err(e) => {
+                tracing::warn!(proof_id = %proof_id, error = %e, "Outstanding proof request failed, requesting the proof again");
+                None
+            }
+        }
+    }
+
+    /// Wait for a proof, recording its request under `key` until it is fulfilled or fails.
+    async fn wait_for_pending_proof(
+        &self,
+        key: PendingProofKey,
+        proof_id: ProofId,
+        requested_at: u64,
+    ) -> Result<SP1ProofWithPublicValues> {
+        self.pending.insert(PendingProof {
+            key,
+            request: PendingProofRequest::Network { proof_id },
+            requested_at,
+        });
+        let result = self.wait_for_proof(proof_id, requested_at).await;
+        self.pending.remove(&key);
+        if result.is_ok() {
+            self.record_proof_cost(key, proof_id).await;
+        }
+        result
+    }
+
+    /// Record the SP1 gas of a fulfilled request and the price it was fulfilled at in the ledger,
+    /// leaving the cost unpriced if the network doesn't report it.
+    async fn record_proof_cost(&self, key: PendingProofKey, proof_id: ProofId) {
+        let details = match self
+            .network_call_with_timeout(
+                self.prover.get_proof_request(proof_id),
+                "get_proof_request",
+                proof_id,
+            )
+            .await
+        {
+            Ok(Some(details)) => details,
+            Ok(None) => {
+                tracing::warn!(proof_id = %proof_id, "Fulfilled proof request not found, proof cost not recorded");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(proof_id = %proof_id, error = %e, "Failed to fetch proof request, proof cost not recorded");
+                return;
+            }
+        };
+
+        let price_per_pgu = Some(details.gas_price()).filter(|&price| price != 0);
+        self.ledger.record_proof(&key.game_address.to_string(), details.gas_used(), price_per_pgu);
     }
 
     /// Submit a range proof request to the network.
     async fn request_range_proof(&self, stdin: SP1Stdin) -> Result<ProofId> {
         let proof_id = self
             .prover
-            .prove(&self.keys.range_pk, stdin)
+            .prove(&self.keys.get().range_pk, stdin)
             .compressed()
             .skip_simulation(true)
             .strategy(self.config.range_proof_strategy)
@@ -171,7 +380,7 @@ impl NetworkProofProvider {
     async fn request_agg_proof(&self, stdin: SP1Stdin) -> Result<ProofId> {
         let proof_id = self
             .prover
-            .prove(&self.keys.agg_pk, stdin)
+            .prove(&self.keys.get().agg_pk, stdin)
             .mode(self.config.agg_proof_mode)
             .strategy(self.config.agg_proof_strategy)
             .timeout(Duration::from_secs(self.config.timeout))
@@ -190,20 +399,24 @@ impl NetworkProofProvider {
     /// Wait for a proof to be fulfilled by polling the network.
     ///
     /// Timeout behavior:
-    /// - **Proving timeout** (`config.timeout`): Overall maximum wait time from start.
+    /// - **Proving timeout** (`config.timeout`): Overall maximum wait time from `requested_at`.
     /// - **Network call timeout** (`config.network_calls_timeout`): Per-call timeout; retries on
     ///   failure.
     /// - **Auction timeout** (`config.auction_timeout`): Cancels if no prover picks up the request.
     /// - **Server deadline** (`status.deadline()`): Server-side proving deadline.
-    async fn wait_for_proof(&self, proof_id: ProofId) -> Result<SP1ProofWithPublicValues> {
-        let start_time = std::time::Instant::now();
+    async fn wait_for_proof(
+        &self,
+        proof_id: ProofId,
+        requested_at: u64,
+    ) -> Result<SP1ProofWithPublicValues> {
         let proving_timeout = Duration::from_secs(self.config.timeout);
         let is_mainnet = self.network_mode == NetworkMode::Mainnet;
 
         loop {
             // Proving timeout - ensures we don't wait forever if network calls keep failing.
+            let elapsed = Duration::from_secs(current_timestamp().saturating_sub(requested_at));
             if let ProvingTimeout::Exceeded { elapsed_secs } =
-                check_timeout(start_time.elapsed(), proving_timeout)
+                check_timeout(elapsed, proving_timeout)
             {
                 tracing::warn!(
                     proof_id = %proof_id,
@@ -372,19 +585,13 @@ impl NetworkProofProvider {
 #[derive(Clone)]
 pub struct MockProofProvider {
     prover: Arc<NetworkProver>,
-    keys: ProofKeys,
+    keys: SharedProofKeys,
     config: ProofProviderConfig,
-    agg_elf: &'static [u8],
 }
 
 impl MockProofProvider {
-    pub fn new(
-        prover: Arc<NetworkProver>,
-        keys: ProofKeys,
-        config: ProofProviderConfig,
-        agg_elf: &'static [u8],
-    ) -> Self {
-        Self { prover, keys, config, agg_elf }
+    pub fn new(prover: Arc<NetworkProver>, keys: ProofKeys, config: ProofProviderConfig) -> Self {
+        Self { prover, keys: SharedProofKeys::new(keys), config }
     }
 
     /// Generate a range proof in mock mode.
@@ -394,9 +601,10 @@ impl MockProofProvider {
     ) -> Result<(SP1ProofWithPublicValues, u64, u64)> {
         tracing::info!("Generating range proof in mock mode");
 
+        let keys = self.keys.get();
         let (public_values, report) = self
             .prover
-            .execute(Elf::Static(get_range_elf_embedded()), stdin)
+            .execute(Elf::Static(keys.range_elf), stdin)
             .calculate_gas(true)
             .deferred_proof_verification(false)
             .await
@@ -415,7 +623,7 @@ impl MockProofProvider {
         );
 
         let proof = SP1ProofWithPublicValues::create_mock_proof(
-            &self.keys.range_vk,
+            &keys.range_vk,
             public_values,
             SP1ProofMode::Compressed,
             SP1_CIRCUIT_VERSION,
@@ -428,15 +636,16 @@ impl MockProofProvider {
     pub async fn generate_agg_proof(&self, stdin: SP1Stdin) -> Result<SP1ProofWithPublicValues> {
         tracing::info!("Generating aggregation proof in mock mode");
 
+        let keys = self.keys.get();
         let (public_values, _) = self
             .prover
-            .execute(Elf::Static(self.agg_elf), stdin)
+            .execute(Elf::Static(keys.agg_elf), stdin)
             .deferred_proof_verification(false)
             .await
             .context("Mock aggregation proof execution failed")?;
 
         Ok(SP1ProofWithPublicValues::create_mock_proof(
-            &self.keys.agg_vk,
+            &keys.agg_vk,
             public_values,
             self.config.agg_proof_mode,
             SP1_CIRCUIT_VERSION,
@@ -447,26 +656,134 @@ impl MockProofProvider {
 /// Cluster-based proof provider using a self-hosted sp1-cluster.
 #[derive(Clone)]
 pub struct ClusterProofProvider {
-    keys: ProofKeys,
+    keys: SharedProofKeys,
     config: ProofProviderConfig,
+    cluster_config: Arc<ClusterProofConfig>,
+    pending: PendingProofs,
 }
 
 impl ClusterProofProvider {
-    pub fn new(keys: ProofKeys, config: ProofProviderConfig) -> Self {
-        Self { keys, config }
+    pub fn new(
+        keys: ProofKeys,
+        config: ProofProviderConfig,
+        cluster_config: Arc<ClusterProofConfig>,
+    ) -> Self {
+        Self {
+            keys: SharedProofKeys::new(keys),
+            config,
+            cluster_config,
+            pending: PendingProofs::default(),
+        }
     }
 
     pub async fn generate_range_proof(
         &self,
+        key: PendingProofKey,
         stdin: SP1Stdin,
     ) -> Result<(SP1ProofWithPublicValues, u64, u64)> {
-        let proof = cluster_range_proof(self.config.timeout, stdin).await?;
+        let proof_request = cluster_submit_range_proof(
+            &self.cluster_config,
+            self.keys.get().range_elf,
+            self.config.timeout,
+            stdin,
+        )
+        .await?;
+        let proof = self.wait_for_pending_proof(key, proof_request, current_timestamp()).await?;
         // Cluster API does not report execution cycle or gas metrics.
         Ok((proof, 0, 0))
     }
 
-    pub async fn generate_agg_proof(&self, stdin: SP1Stdin) -> Result<SP1ProofWithPublicValues> {
-        cluster_agg_proof(self.config.timeout, self.config.agg_proof_mode, stdin).await
+    pub async fn generate_agg_proof(
+        &self,
+        key: PendingProofKey,
+        stdin: SP1Stdin,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let proof_request = cluster_submit_agg_proof(
+            &self.cluster_config,
+            self.keys.get().agg_elf,
+            self.config.timeout,
+            self.config.agg_proof_mode,
+            stdin,
+        )
+        .await?;
+        self.wait_for_pending_proof(key, proof_request, current_timestamp()).await
+    }
+
+    /// Re-attach to the outstanding cluster request for `key`, if any.
+    async fn resume_proof(&self, key: PendingProofKey) -> Option<SP1ProofWithPublicValues> {
+        let (handle, requested_at) = self.pending.cluster_request(&key)?;
+
+        let elapsed = current_timestamp().saturating_sub(requested_at);
+        let remaining = Duration::from_secs(self.config.timeout.saturating_sub(elapsed));
+        let proof_request = reconstruct_proof_request(&handle, remaining);
+
+        tracing::info!(
+            proof_id = %handle.proof_id,
+            ?key,
+            remaining_timeout_secs = remaining.as_secs(),
+            "Re-attaching to outstanding cluster proof request"
+        );
+        match self.wait_for_pending_proof(key, proof_request, requested_at).await {
+            Ok(proof) => Some(proof),
+            Err(e) => {
+                tracing::warn!(proof_id = %handle.proof_id, error = %e, "Outstanding cluster proof request failed, requesting the proof again");
+                None
+            }
+        }
+    }
+
+    /// Wait for a proof, recording its request under `key` until it completes or fails.
+    async fn wait_for_pending_proof(
+        &self,
+        key: PendingProofKey,
+        proof_request: ProofRequest,
+        requested_at: u64,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let handle = ClusterProofHandleJson {
+            proof_id: proof_request.proof_id.clone(),
+            proof_output_id: proof_request.proof_output_id.clone().to_id(),
+        };
+        self.pending.insert(PendingProof {
+            key,
+            request: PendingProofRequest::Cluster { handle },
+            requested_at,
+        });
+        let result = self.wait_for_proof(proof_request).await;
+        self.pending.remove(&key);
+        result
+    }
+
+    /// Poll the cluster until the proof completes. The request's deadline bounds the wait.
+    async fn wait_for_proof(
+        &self,
+        proof_request: ProofRequest,
+    ) -> Result<SP1ProofWithPublicValues> {
+        let mut consecutive_poll_failures = 0;
+        loop {
+            match cluster_poll_proof(&self.cluster_config, proof_request.clone()).await {
+                Ok(Some(results)) => {
+                    tracing::info!(proof_id = %proof_request.proof_id, "Cluster proof completed");
+                    return Ok(SP1ProofWithPublicValues::from(results.proof));
+                }
+                Ok(None) => consecutive_poll_failures = 0,
+                Err(e) => {
+                    consecutive_poll_failures += 1;
+                    if consecutive_poll_failures >= MAX_CLUSTER_POLL_FAILURES {
+                        return Err(e.context(format!(
+                            "cluster proof {} failed after {consecutive_poll_failures} consecutive poll failures",
+                            proof_request.proof_id
+                        )));
+                    }
+                    tracing::warn!(
+                        proof_id = %proof_request.proof_id,
+                        consecutive_poll_failures,
+                        error = %e,
+                        "Cluster proof poll failed, retrying..."
+                    );
+                }
+            }
+            sleep(Duration::from_secs(PROOF_STATUS_POLL_INTERVAL)).await;
+        }
     }
 }
 
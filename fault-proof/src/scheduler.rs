@@ -0,0 +1,303 @@
+//! Deadline-aware scheduling of game proving.
+//!
+//! Defense and fast finality proving share the proposer's proving slots, and games are proven
+//! earliest deadline first across both. A challenged game loses its bond if it isn't proven before
+//! its deadline, while fast finality proving only speeds up finality, so a defense whose deadline
+//! is at risk preempts fast finality proving when no slot is free. Whether a deadline is at risk
+//! is judged against an estimate of the proving time learned from past proofs.
+
+use std::{
+    cmp::Ordering,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use alloy_primitives::{Address, U256};
+use op_succinct_host_utils::metrics::MetricsGauge;
+
+use crate::prometheus::ProposerGauge;
+
+/// The proving time assumed before any game has been proven.
+pub const DEFAULT_PROVING_TIME_ESTIMATE: Duration = Duration::from_secs(60 * 60);
+
+/// The weight of the latest proving time in the estimate.
+const PROVING_TIME_SMOOTHING: f64 = 0.3;
+
+/// A deadline is at risk when the time left after the estimated proving time is less than this
+/// fraction of the estimate.
+const AT_RISK_SLACK_FRACTION: f64 = 0.5;
+
+/// An exponentially weighted moving average of the time it takes to prove a game, shared between
+/// clones of the proposer.
+#[derive(Clone, Debug, Default)]
+pub struct ProvingTimeEstimator(Arc<Mutex<Option<f64>>>);
+
+impl ProvingTimeEstimator {
+    /// Record the duration of a successful game proving.
+    pub fn record(&self, duration: Duration) {
+        let mut estimate = self.0.lock().expect("proving time estimator lock poisoned");
+        let secs = duration.as_secs_f64();
+        let updated = match *estimate {
+            Some(prev) => PROVING_TIME_SMOOTHING * secs + (1.0 - PROVING_TIME_SMOOTHING) * prev,
+            None => secs,
+        };
+        *estimate = Some(updated);
+        ProposerGauge::EstimatedProvingDurationSeconds.set(updated);
+    }
+
+    /// The estimated time to prove a game.
+    pub fn estimate(&self) -> Duration {
+        self.0
+            .lock()
+            .expect("proving time estimator lock poisoned")
+            .map_or(DEFAULT_PROVING_TIME_ESTIMATE, Duration::from_secs_f64)
+    }
+}
+
+/// How a game's deadline compares to the time it is expected to take to prove it.
+#[derive(Debug, PartialEq)]
+pub enum DeadlineRisk {
+    /// Proving is expected to finish well before the deadline.
+    OnTrack,
+    /// Proving is expected to finish before the deadline, but with little slack.
+    AtRisk { slack_secs: u64 },
+    /// Proving is expected to finish after the deadline.
+    ExpectedMiss { shortfall_secs: u64 },
+}
+
+impl DeadlineRisk {
+    /// Whether proving should take priority over lower priority work.
+    pub fn is_urgent(&self) -> bool {
+        !matches!(self, Self::OnTrack)
+    }
+}
+
+/// Check whether proving started at `now` is expected to finish before `deadline`.
+pub fn check_deadline_risk(now: u64, deadline: u64, estimate: Duration) -> DeadlineRisk {
+    let estimate_secs = estimate.as_secs();
+    let expected_finish = now.saturating_add(estimate_secs);
+    if expected_finish > deadline {
+        return DeadlineRisk::ExpectedMiss { shortfall_secs: expected_finish - deadline };
+    }
+
+    let slack_secs = deadline - expected_finish;
+    if (slack_secs as f64) < estimate_secs as f64 * AT_RISK_SLACK_FRACTION {
+        DeadlineRisk::AtRisk { slack_secs }
+    } else {
+        DeadlineRisk::OnTrack
+    }
+}
+
+/// A game waiting to be proven.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProvingCandidate {
+    pub index: U256,
+    pub game_address: Address,
+    pub deadline: u64,
+    pub is_defense: bool,
+}
+
+impl ProvingCandidate {
+    /// Earliest deadline first, with defense ahead of fast finality proving on equal deadlines.
+    fn priority_cmp(&self, other: &Self) -> Ordering {
+        self.deadline.cmp(&other.deadline).then(other.is_defense.cmp(&self.is_defense))
+    }
+}
+
+/// Order the candidates earliest deadline first.
+pub fn schedule(candidates: &mut [ProvingCandidate]) {
+    candidates.sort_by(ProvingCandidate::priority_cmp);
+}
+
+/// A game being proven.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunningProving {
+    pub game_address: Address,
+    pub deadline: u64,
+    pub is_defense: bool,
+}
+
+/// The proving slots shared by defense and fast finality proving. Each kind may use up to its own
+/// limit, and both together up to the larger of the two.
+#[derive(Clone, Copy, Debug)]
+pub struct ProvingCapacity {
+    pub defense: u64,
+    pub fast_finality: u64,
+}
+
+impl ProvingCapacity {
+    fn total(&self) -> u64 {
+        self.defense.max(self.fast_finality)
+    }
+}
+
+/// The proving to start and the fast finality proving to preempt for it.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ProvingPlan {
+    pub spawn: Vec<ProvingCandidate>,
+    pub preempt: Vec<Address>,
+}
+
+/// Plan the proving of `candidates`, earliest deadline first, next to the `running` proving.
+///
+/// A candidate is started while a slot is free. A defense whose deadline `is_at_risk` that finds
+/// no free slot preempts the running fast finality proving with the latest deadline, if that
+/// deadline is later than its own. Other candidates wait for a slot.
+pub fn plan_proving(
+    mut candidates: Vec<ProvingCandidate>,
+    running: &[RunningProving],
+    capacity: ProvingCapacity,
+    is_at_risk: impl Fn(&ProvingCandidate) -> bool,
+) -> ProvingPlan {
+    schedule(&mut candidates);
+
+    let mut defense = running.iter().filter(|proving| proving.is_defense).count() as u64;
+    let mut fast_finality = running.len() as u64 - defense;
+    // Preempt the fast finality proving with the latest deadline first.
+    let mut preemptible = running.iter().filter(|proving| !proving.is_defense).collect::<Vec<_>>();
+    preemptible.sort_by_key(|proving| proving.deadline);
+
+    let mut plan = ProvingPlan::default();
+    for candidate in candidates {
+        let (used, limit) = if candidate.is_defense {
+            (defense, capacity.defense)
+        } else {
+            (fast_finality, capacity.fast_finality)
+        };
+        if used >= limit {
+            continue;
+        }
+
+        if defense + fast_finality >= capacity.total() {
+            if !candidate.is_defense || !is_at_risk(&candidate) {
+                continue;
+            }
+            match preemptible.last() {
+                Some(proving) if proving.deadline > candidate.deadline => {
+                    plan.preempt.push(proving.game_address);
+                    preemptible.pop();
+                    fast_finality -= 1;
+                }
+                _ => continue,
+            }
+        }
+
+        if candidate.is_defense {
+            defense += 1;
+        } else {
+            fast_finality += 1;
+        }
+        plan.spawn.push(candidate);
+    }
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const HOUR: u64 = 3600;
+
+    #[rstest]
+    #[case::on_track(1000, 1000 + 3 * HOUR, DeadlineRisk::OnTrack)]
+    #[case::at_risk(1000, 1000 + HOUR + HOUR / 4, DeadlineRisk::AtRisk { slack_secs: HOUR / 4 })]
+    #[case::exactly_in_time(1000, 1000 + HOUR, DeadlineRisk::AtRisk { slack_secs: 0 })]
+    #[case::expected_miss(1000, 1000 + HOUR / 2, DeadlineRisk::ExpectedMiss { shortfall_secs: HOUR / 2 })]
+    fn test_deadline_risk(#[case] now: u64, #[case] deadline: u64, #[case] expected: DeadlineRisk) {
+        assert_eq!(check_deadline_risk(now, deadline, Duration::from_secs(HOUR)), expected);
+    }
+
+    #[test]
+    fn test_proving_time_estimator() {
+        let estimator = ProvingTimeEstimator::default();
+        assert_eq!(estimator.estimate(), DEFAULT_PROVING_TIME_ESTIMATE);
+
+        estimator.record(Duration::from_secs(1000));
+        assert_eq!(estimator.estimate(), Duration::from_secs(1000));
+
+        estimator.record(Duration::from_secs(2000));
+        assert!((estimator.estimate().as_secs_f64() - 1300.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_schedule_earliest_deadline_first() {
+        let candidate = |index: u64, deadline: u64, is_defense: bool| ProvingCandidate {
+            index: U256::from(index),
+            game_address: Address::ZERO,
+            deadline,
+            is_defense,
+        };
+        let mut candidates = vec![
+            candidate(0, 300, true),
+            candidate(1, 100, false),
+            candidate(2, 200, false),
+            candidate(3, 200, true),
+        ];
+
+        schedule(&mut candidates);
+        let order: Vec<_> = candidates.iter().map(|c| c.index.to::<u64>()).collect();
+        assert_eq!(order, vec![1, 3, 2, 0]);
+    }
+
+    fn candidate(index: u64, deadline: u64, is_defense: bool) -> ProvingCandidate {
+        ProvingCandidate {
+            index: U256::from(index),
+            game_address: Address::with_last_byte(index as u8),
+            deadline,
+            is_defense,
+        }
+    }
+
+    fn running(index: u64, deadline: u64, is_defense: bool) -> RunningProving {
+        RunningProving { game_address: Address::with_last_byte(index as u8), deadline, is_defense }
+    }
+
+    fn spawned(plan: &ProvingPlan) -> Vec<u64> {
+        plan.spawn.iter().map(|c| c.index.to::<u64>()).collect()
+    }
+
+    #[test]
+    fn test_plan_shares_slots_earliest_deadline_first() {
+        // One slot for either kind: the earliest deadline wins across defense and fast finality.
+        let capacity = ProvingCapacity { defense: 1, fast_finality: 1 };
+        let candidates = vec![candidate(0, 300, true), candidate(1, 100, false)];
+        let plan = plan_proving(candidates, &[], capacity, |_| false);
+        assert_eq!(spawned(&plan), vec![1]);
+        assert!(plan.preempt.is_empty());
+
+        // Each kind stays within its own limit, and both together within the larger one.
+        let capacity = ProvingCapacity { defense: 3, fast_finality: 1 };
+        let candidates = vec![
+            candidate(0, 100, false),
+            candidate(1, 200, false),
+            candidate(2, 300, true),
+            candidate(3, 400, true),
+            candidate(4, 500, true),
+        ];
+        let plan = plan_proving(candidates, &[running(9, 50, true)], capacity, |_| false);
+        assert_eq!(spawned(&plan), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_plan_preempts_fast_finality_for_at_risk_defense() {
+        let capacity = ProvingCapacity { defense: 2, fast_finality: 2 };
+        let running = [running(7, 400, false), running(8, 900, false)];
+        let candidates = vec![candidate(0, 500, true), candidate(1, 600, true)];
+
+        // Defenses on track wait for a slot.
+        let plan = plan_proving(candidates.clone(), &running, capacity, |_| false);
+        assert_eq!(plan, ProvingPlan::default());
+
+        // At-risk defenses preempt fast finality proving with a later deadline only, latest first.
+        let plan = plan_proving(candidates, &running, capacity, |_| true);
+        assert_eq!(spawned(&plan), vec![0]);
+        assert_eq!(plan.preempt, vec![Address::with_last_byte(8)]);
+
+        // Fast finality proving never preempts.
+        let plan = plan_proving(vec![candidate(2, 100, false)], &running[..1], capacity, |_| true);
+        assert_eq!(spawned(&plan), vec![2]);
+        let plan = plan_proving(vec![candidate(2, 100, false)], &running, capacity, |_| true);
+        assert!(plan.spawn.is_empty());
+    }
+}
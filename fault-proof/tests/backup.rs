@@ -15,6 +15,8 @@ fn test_game(index: u64, parent_index: u32) -> Game {
     Game {
         index: U256::from(index),
         address: Address::ZERO,
+        creator: Address::ZERO,
+        created_at: 0,
         parent_index,
         l2_block: U256::from(index + 100),
         status: GameStatus::IN_PROGRESS,
@@ -15,6 +15,7 @@ use alloy_sol_types::{SolCall, SolValue};
 use alloy_transport_http::reqwest::Url;
 use anyhow::{Context, Result};
 use op_succinct_bindings::{
+    access_manager::AccessManager,
     anchor_state_registry::AnchorStateRegistry::{self, AnchorStateRegistryInstance},
     dispute_game_factory::DisputeGameFactory::{self, DisputeGameFactoryInstance},
     mock_optimism_portal2::MockOptimismPortal2::{self, MockOptimismPortal2Instance},
@@ -36,8 +37,8 @@ use tokio::task::JoinHandle;
 use tracing::{info, Level};
 
 use fault_proof::{
-    challenger::OPSuccinctChallenger, config::FaultDisputeGameConfig, proposer::OPSuccinctProposer,
-    L2ProviderTrait,
+    challenger::OPSuccinctChallenger, chaos::ChaosScenario, config::FaultDisputeGameConfig,
+    proposer::OPSuccinctProposer, L2ProviderTrait,
 };
 use tracing_subscriber::{filter::Targets, fmt, prelude::*, util::SubscriberInitExt};
 
@@ -279,6 +280,7 @@ impl TestEnvironment {
             self.game_type,
             backup_path,
             sync_l1_confirmations,
+            None,
         )
         .await
     }
@@ -300,6 +302,7 @@ impl TestEnvironment {
             &self.deployed.factory,
             self.game_type,
             None,
+            None,
         )
         .await
     }
@@ -311,16 +314,21 @@ impl TestEnvironment {
         Ok(challenger)
     }
 
-    pub async fn start_proposer(&self) -> Result<JoinHandle<Result<()>>> {
+    /// Start a proposer, injecting the faults of `scenario` if given. See [`fault_proof::chaos`].
+    pub async fn start_proposer(
+        &self,
+        scenario: Option<&ChaosScenario>,
+    ) -> Result<JoinHandle<Result<()>>> {
         let handle = start_proposer(
             &self.rpc_config,
             self.private_keys.proposer,
             &self.deployed.anchor_state_registry,
             &self.deployed.factory,
             self.game_type,
+            scenario,
         )
         .await?;
-        info!("✓ Proposer service started");
+        info!("✓ Proposer service started with chaos scenario: {scenario:?}");
         Ok(handle)
     }
 
@@ -329,9 +337,12 @@ impl TestEnvironment {
         info!("✓ Proposer service stopped");
     }
 
+    /// Start a challenger, injecting the faults of `scenario` if given. See
+    /// [`fault_proof::chaos`].
     pub async fn start_challenger(
         &self,
         malicious_percentage: Option<f64>,
+        scenario: Option<&ChaosScenario>,
     ) -> Result<JoinHandle<Result<()>>> {
         let handle = start_challenger(
             &self.rpc_config,
@@ -340,9 +351,13 @@ impl TestEnvironment {
             &self.deployed.factory,
             self.game_type,
             malicious_percentage,
+            scenario,
         )
         .await?;
-        info!("✓ Challenger service started with malicious percentage: {malicious_percentage:?}");
+        info!(
+            "✓ Challenger service started with malicious percentage: {malicious_percentage:?}, \
+             chaos scenario: {scenario:?}"
+        );
         Ok(handle)
     }
 
@@ -397,6 +412,35 @@ impl TestEnvironment {
         Ok(())
     }
 
+    /// Allow or disallow `proposer` to create games on the access manager.
+    pub async fn set_proposer_allowed(&self, proposer: Address, allowed: bool) -> Result<()> {
+        let call = AccessManager::setProposerCall { _proposer: proposer, _allowed: allowed };
+        self.send_access_manager_tx(call.abi_encode()).await?;
+        info!("✓ Set proposer {proposer} allowed to {allowed}");
+        Ok(())
+    }
+
+    /// Allow or disallow `challenger` to challenge games on the access manager.
+    pub async fn set_challenger_allowed(&self, challenger: Address, allowed: bool) -> Result<()> {
+        let call = AccessManager::setChallengerCall { _challenger: challenger, _allowed: allowed };
+        self.send_access_manager_tx(call.abi_encode()).await?;
+        info!("✓ Set challenger {challenger} allowed to {allowed}");
+        Ok(())
+    }
+
+    pub async fn send_access_manager_tx(&self, call: Vec<u8>) -> Result<()> {
+        let deployer_signer =
+            SignerLock::new(Signer::new_local_signer(self.private_keys.deployer)?);
+        send_contract_transaction(
+            &deployer_signer,
+            &self.rpc_config.l1_rpc,
+            self.deployed.access_manager,
+            Bytes::from(call),
+            None,
+        )
+        .await
+    }
+
     pub async fn send_anchor_state_registry_tx(
         &self,
         call: Vec<u8>,
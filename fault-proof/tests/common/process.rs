@@ -1,22 +1,28 @@
 //! Process management utilities for running proposer and challenger tasks.
 use std::{num::NonZero, path::PathBuf, sync::Arc};
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, U256};
 use alloy_provider::ProviderBuilder;
 use anyhow::Result;
 use fault_proof::{
     challenger::OPSuccinctChallenger,
-    config::{ChallengerConfig, ProofProviderConfig, RangeSplitCount},
+    chaos::ChaosScenario,
+    config::{ChallengerConfig, ProofProviderConfig, ProposerConfig, RangeSplitCount},
     contract::{AnchorStateRegistry, DisputeGameFactory},
     proposer::OPSuccinctProposer,
 };
 use op_succinct_host_utils::{
     fetcher::{OPSuccinctDataFetcher, RPCConfig},
     host::OPSuccinctHost,
+    quorum::QuorumPolicy,
+    witness_generation::{
+        shared_preimage_cache::DEFAULT_SHARED_PREIMAGE_CACHE_BYTES, WitnessGenOptions,
+    },
 };
 use op_succinct_proof_utils::initialize_host;
 use op_succinct_signer_utils::SignerLock;
 use sp1_sdk::{network::FulfillmentStrategy, SP1ProofMode};
+use tempfile::NamedTempFile;
 use tracing::Instrument;
 
 pub async fn new_proposer(
@@ -35,6 +41,7 @@ pub async fn new_proposer(
         game_type,
         backup_path,
         0,
+        None,
     )
     .await
 }
@@ -47,20 +54,46 @@ pub async fn new_proposer_with_confirmations(
     game_type: u32,
     backup_path: Option<PathBuf>,
     sync_l1_confirmations: u64,
+    scenario: Option<&ChaosScenario>,
 ) -> Result<OPSuccinctProposer<fault_proof::L1Provider, impl OPSuccinctHost + Clone>> {
-    // Create signer directly from private key
-    let signer = SignerLock::new(op_succinct_signer_utils::Signer::new_local_signer(private_key)?);
+    // The scenario is read when the proposer is created, so the file can go afterwards.
+    let scenario_file = scenario.map(write_chaos_scenario).transpose()?;
+    let config = ProposerConfig {
+        chaos_scenario_path: scenario_file.as_ref().map(|file| file.path().to_path_buf()),
+        ..proposer_config(
+            rpc_config,
+            anchor_state_registry_address,
+            factory_address,
+            game_type,
+            backup_path,
+            sync_l1_confirmations,
+        )
+    };
+    build_proposer(rpc_config, private_key, config).await
+}
 
-    // Create proposer config with test-specific settings
-    let config = fault_proof::config::ProposerConfig {
+/// Proposer config with test-specific settings.
+fn proposer_config(
+    rpc_config: &RPCConfig,
+    anchor_state_registry_address: &Address,
+    factory_address: &Address,
+    game_type: u32,
+    backup_path: Option<PathBuf>,
+    sync_l1_confirmations: u64,
+) -> ProposerConfig {
+    ProposerConfig {
         l1_rpc: rpc_config.l1_rpc.clone(),
         l2_rpc: rpc_config.l2_rpc.clone(),
+        l2_quorum_rpcs: Vec::new(),
+        output_root_quorum: QuorumPolicy::All,
         anchor_state_registry_address: *anchor_state_registry_address,
         factory_address: *factory_address,
         mock_mode: true,
         fast_finality_mode: false,
         proposal_interval_in_blocks: 10, // Much smaller interval for testing
         fetch_interval: 5,               // Check more frequently in tests
+        proposal_interval_secs: None,
+        proposal_max_lag_blocks: None,
         game_type,
         max_concurrent_defense_tasks: 1,
         safe_db_fallback: false,
@@ -69,7 +102,14 @@ pub async fn new_proposer_with_confirmations(
         use_kms_requester: false,
         range_split_count: RangeSplitCount::one(),
         max_concurrent_range_proofs: NonZero::<usize>::MIN,
+        witness_gen_options: WitnessGenOptions::default(),
+        shared_preimage_cache_bytes: DEFAULT_SHARED_PREIMAGE_CACHE_BYTES,
         backup_path,
+        ledger_path: None,
+        chaos_scenario_path: None,
+        l1_balance_reserve: U256::ZERO,
+        min_prove_balance: U256::ZERO,
+        low_prove_balance_defense_window_secs: None,
         tx_confirmation_timeout: 60,
         proof_provider: ProofProviderConfig {
             timeout: 14400, // 4 hours
@@ -87,12 +127,24 @@ pub async fn new_proposer_with_confirmations(
             whitelist: None,
         },
         sync_l1_confirmations,
-    };
+        alternate_range_elf_path: None,
+        alternate_agg_elf_path: None,
+        guardian: None,
+    }
+}
+
+async fn build_proposer(
+    rpc_config: &RPCConfig,
+    private_key: &str,
+    config: ProposerConfig,
+) -> Result<OPSuccinctProposer<fault_proof::L1Provider, impl OPSuccinctHost + Clone>> {
+    // Create signer directly from private key
+    let signer = SignerLock::new(op_succinct_signer_utils::Signer::new_local_signer(private_key)?);
 
     let l1_provider = ProviderBuilder::default().connect_http(rpc_config.l1_rpc.clone());
     let anchor_state_registry =
-        AnchorStateRegistry::new(*anchor_state_registry_address, l1_provider.clone());
-    let factory = DisputeGameFactory::new(*factory_address, l1_provider.clone());
+        AnchorStateRegistry::new(config.anchor_state_registry_address, l1_provider.clone());
+    let factory = DisputeGameFactory::new(config.factory_address, l1_provider.clone());
 
     let fetcher = Arc::new(OPSuccinctDataFetcher::new_with_rollup_config().await?);
     let host = initialize_host(fetcher.clone());
@@ -100,21 +152,25 @@ pub async fn new_proposer_with_confirmations(
     OPSuccinctProposer::new(config, signer, anchor_state_registry, factory, fetcher, host).await
 }
 
-/// Start a proposer, and return a handle to the proposer task.
+/// Start a proposer, injecting the faults of `scenario` if given, and return a handle to the
+/// proposer task.
 pub async fn start_proposer(
     rpc_config: &RPCConfig,
     private_key: &str,
     anchor_state_registry_address: &Address,
     factory_address: &Address,
     game_type: u32,
+    scenario: Option<&ChaosScenario>,
 ) -> Result<tokio::task::JoinHandle<Result<()>>> {
-    let proposer = new_proposer(
+    let proposer = new_proposer_with_confirmations(
         rpc_config,
         private_key,
         anchor_state_registry_address,
         factory_address,
         game_type,
         None,
+        0,
+        scenario,
     )
     .await?;
     Ok(tokio::spawn(async move {
@@ -122,7 +178,7 @@ pub async fn start_proposer(
     }))
 }
 
-/// Create a new challenger instance.
+/// Create a new challenger instance, injecting the faults of `scenario` if given.
 pub async fn new_challenger(
     rpc_config: &RPCConfig,
     private_key: &str,
@@ -130,12 +186,36 @@ pub async fn new_challenger(
     factory_address: &Address,
     game_type: u32,
     malicious_percentage: Option<f64>,
+    scenario: Option<&ChaosScenario>,
 ) -> Result<OPSuccinctChallenger<fault_proof::L1Provider>> {
-    let signer = SignerLock::new(op_succinct_signer_utils::Signer::new_local_signer(private_key)?);
-
+    // The scenario is read when the challenger is created, so the file can go afterwards.
+    let scenario_file = scenario.map(write_chaos_scenario).transpose()?;
     let config = ChallengerConfig {
+        chaos_scenario_path: scenario_file.as_ref().map(|file| file.path().to_path_buf()),
+        ..challenger_config(
+            rpc_config,
+            anchor_state_registry_address,
+            factory_address,
+            game_type,
+            malicious_percentage,
+        )
+    };
+    build_challenger(rpc_config, private_key, config)
+}
+
+/// Challenger config with test-specific settings.
+fn challenger_config(
+    rpc_config: &RPCConfig,
+    anchor_state_registry_address: &Address,
+    factory_address: &Address,
+    game_type: u32,
+    malicious_percentage: Option<f64>,
+) -> ChallengerConfig {
+    ChallengerConfig {
         l1_rpc: rpc_config.l1_rpc.clone(),
         l2_rpc: rpc_config.l2_rpc.clone(),
+        l2_quorum_rpcs: Vec::new(),
+        output_root_quorum: QuorumPolicy::All,
         anchor_state_registry_address: *anchor_state_registry_address,
         factory_address: *factory_address,
         fetch_interval: 2,
@@ -143,17 +223,36 @@ pub async fn new_challenger(
         metrics_port: 9001,
         malicious_challenge_percentage: malicious_percentage.unwrap_or(0.0),
         tx_confirmation_timeout: 60,
-    };
+        ledger_path: None,
+        chaos_scenario_path: None,
+        l1_balance_reserve: U256::ZERO,
+    }
+}
+
+fn build_challenger(
+    rpc_config: &RPCConfig,
+    private_key: &str,
+    config: ChallengerConfig,
+) -> Result<OPSuccinctChallenger<fault_proof::L1Provider>> {
+    let signer = SignerLock::new(op_succinct_signer_utils::Signer::new_local_signer(private_key)?);
 
     let l1_provider = ProviderBuilder::default().connect_http(rpc_config.l1_rpc.clone());
     let anchor_state_registry =
-        AnchorStateRegistry::new(*anchor_state_registry_address, l1_provider.clone());
-    let factory = DisputeGameFactory::new(*factory_address, l1_provider.clone());
+        AnchorStateRegistry::new(config.anchor_state_registry_address, l1_provider.clone());
+    let factory = DisputeGameFactory::new(config.factory_address, l1_provider.clone());
+
+    OPSuccinctChallenger::new(config, l1_provider, anchor_state_registry, factory, signer)
+}
 
-    Ok(OPSuccinctChallenger::new(config, l1_provider, anchor_state_registry, factory, signer))
+/// Write `scenario` to a temporary file, to pass as the chaos scenario path.
+pub fn write_chaos_scenario(scenario: &ChaosScenario) -> Result<NamedTempFile> {
+    let file = NamedTempFile::new()?;
+    serde_json::to_writer(&file, scenario)?;
+    Ok(file)
 }
 
-/// Start a challenger, and return a handle to the challenger task.
+/// Start a challenger, injecting the faults of `scenario` if given, and return a handle to the
+/// challenger task.
 pub async fn start_challenger(
     rpc_config: &RPCConfig,
     private_key: &str,
@@ -161,6 +260,7 @@ pub async fn start_challenger(
     factory_address: &Address,
     game_type: u32,
     malicious_percentage: Option<f64>,
+    scenario: Option<&ChaosScenario>,
 ) -> Result<tokio::task::JoinHandle<Result<()>>> {
     let challenger = new_challenger(
         rpc_config,
@@ -169,6 +269,7 @@ pub async fn start_challenger(
         factory_address,
         game_type,
         malicious_percentage,
+        scenario,
     )
     .await?;
 
@@ -20,6 +20,7 @@ mod integration {
     };
     use fault_proof::{
         challenger::Game,
+        chaos::{ChaosScenario, Fault, FaultRule, TxAction},
         contract::{GameStatus, ProposalStatus},
     };
     use op_succinct_bindings::dispute_game_factory::DisputeGameFactory;
@@ -44,7 +45,7 @@ mod integration {
 
         let env = TestEnvironment::setup().await?;
 
-        let proposer_handle = env.start_proposer().await?;
+        let proposer_handle = env.start_proposer(None).await?;
 
         // Wait for proposer to create games
         info!("=== Waiting for Game Creation ===");
@@ -150,7 +151,7 @@ mod integration {
 
         env.set_respected_game_type(TEST_GAME_TYPE).await?;
 
-        let proposer_handle = env.start_proposer().await?;
+        let proposer_handle = env.start_proposer(None).await?;
         info!("✓ Proposer started after legacy games seeded");
 
         let tracked_games = env.wait_and_track_games(3, WAIT_TIMEOUT).await?;
@@ -204,7 +205,7 @@ mod integration {
         let mut expected_index = initial_game_count;
         info!("Seeding legacy game (type {MOCK_PERMISSIONED_GAME_TYPE})");
 
-        let proposer_handle = env.start_proposer().await?;
+        let proposer_handle = env.start_proposer(None).await?;
 
         let interval = 10;
         let l2_block = env.anvil.starting_l2_block_number + interval;
@@ -277,7 +278,7 @@ mod integration {
         let env = TestEnvironment::setup().await?;
 
         info!("=== Starting Challenger Service ===");
-        let challenger_handle = env.start_challenger(None).await?;
+        let challenger_handle = env.start_challenger(None, None).await?;
 
         // === PHASE 1: Create Invalid Games ===
         info!("=== Phase 1: Create Invalid Games ===");
@@ -375,6 +376,177 @@ mod integration {
         Ok(())
     }
 
+    // Ensures the proposer creates no games while the access manager doesn't allow it to propose,
+    // and resumes creating games once allowed.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_proposer_paused_without_permission() -> Result<()> {
+        info!("=== Test: Proposer Paused Without Permission ===");
+
+        let env = TestEnvironment::setup().await?;
+        env.set_proposer_allowed(PROPOSER_ADDRESS, false).await?;
+
+        let factory = env.factory()?;
+        let initial_game_count = factory.gameCount().call().await?;
+
+        let proposer_handle = env.start_proposer(None).await?;
+
+        // Several proposer loops pass without a game being created.
+        sleep(Duration::from_secs(20)).await;
+        assert_eq!(
+            factory.gameCount().call().await?,
+            initial_game_count,
+            "proposer should not create games while not allowed to propose"
+        );
+        assert!(!proposer_handle.is_finished(), "Proposer should still be running");
+        info!("✓ No games created while not allowed to propose");
+
+        env.set_proposer_allowed(PROPOSER_ADDRESS, true).await?;
+        let tracked_games = env.wait_and_track_games(1, WAIT_TIMEOUT).await?;
+        info!("✓ Proposer created game {} once allowed", tracked_games[0].address);
+
+        env.stop_proposer(proposer_handle);
+
+        Ok(())
+    }
+
+    // Ensures the challenger leaves an invalid game unchallenged while the access manager doesn't
+    // allow it to challenge, and challenges it once allowed.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_challenger_paused_without_permission() -> Result<()> {
+        info!("=== Test: Challenger Paused Without Permission ===");
+
+        let env = TestEnvironment::setup().await?;
+        env.set_challenger_allowed(CHALLENGER_ADDRESS, false).await?;
+
+        let challenger_handle = env.start_challenger(None, None).await?;
+
+        let factory = env.factory()?;
+        let init_bond = factory.initBonds(TEST_GAME_TYPE).call().await?;
+        let mut invalid_root_bytes = [0u8; 32];
+        rand::rng().fill(&mut invalid_root_bytes);
+        env.create_game(
+            FixedBytes::<32>::from(invalid_root_bytes),
+            env.anvil.starting_l2_block_number + 10,
+            u32::MAX,
+            init_bond,
+        )
+        .await?;
+        let (_, game_address) = env.last_game_info().await?;
+
+        // Several challenger loops pass without the game being challenged.
+        sleep(Duration::from_secs(10)).await;
+        let game = env.fault_dispute_game(game_address).await?;
+        let status = ProposalStatus::try_from(game.claimData().call().await?.status)?;
+        assert_eq!(
+            status,
+            ProposalStatus::Unchallenged,
+            "challenger should not challenge while not allowed to"
+        );
+        assert!(!challenger_handle.is_finished(), "Challenger should still be running");
+        info!("✓ Invalid game left unchallenged while not allowed to challenge");
+
+        env.set_challenger_allowed(CHALLENGER_ADDRESS, true).await?;
+        env.wait_for_challenges(&[game_address], WAIT_TIMEOUT).await?;
+        info!("✓ Invalid game challenged once allowed");
+
+        env.stop_challenger(challenger_handle);
+
+        Ok(())
+    }
+
+    // Makes the proposer create its first game with a wrong output root and drop every proof,
+    // and ensures the honest challenger challenges it and wins.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chaos_invalid_output_root() -> Result<()> {
+        info!("=== Test: Chaos Proposer With Invalid Output Root ===");
+
+        let env = TestEnvironment::setup().await?;
+
+        let scenario = ChaosScenario {
+            seed: Some(0),
+            faults: vec![
+                FaultRule { fault: Fault::InvalidOutputRoot, probability: 1.0, limit: Some(1) },
+                FaultRule { fault: Fault::DropProof, probability: 1.0, limit: None },
+            ],
+        };
+        let proposer_handle = env.start_proposer(Some(&scenario)).await?;
+        let challenger_handle = env.start_challenger(None, None).await?;
+
+        // === PHASE 1: Invalid Game Creation ===
+        info!("=== Phase 1: Invalid Game Creation ===");
+        let tracked_games = env.wait_and_track_games(1, WAIT_TIMEOUT).await?;
+        let invalid_game = tracked_games[0].address;
+        info!("✓ Proposer created invalid game {invalid_game}");
+
+        // === PHASE 2: Challenge ===
+        info!("=== Phase 2: Challenge ===");
+        env.wait_for_challenges(&[invalid_game], WAIT_TIMEOUT).await?;
+        info!("✓ Challenger challenged the invalid game");
+
+        // === PHASE 3: Resolution ===
+        info!("=== Phase 3: Resolution ===");
+        env.warp_time(MAX_CHALLENGE_DURATION + MAX_PROVE_DURATION).await?;
+        env.wait_and_verify_game_resolutions(
+            &[invalid_game],
+            GameStatus::CHALLENGER_WINS,
+            "ChallengerWins",
+            WAIT_TIMEOUT,
+        )
+        .await?;
+
+        // Verify both services survived the injected faults
+        assert!(!proposer_handle.is_finished(), "Proposer should still be running");
+        assert!(!challenger_handle.is_finished(), "Challenger should still be running");
+
+        env.stop_proposer(proposer_handle);
+        env.stop_challenger(challenger_handle);
+
+        info!("✓ Invalid game challenged and won by the challenger");
+        Ok(())
+    }
+
+    // Fails the challenger's first challenge transaction and ensures it retries and still wins.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_chaos_challenge_transaction_failure() -> Result<()> {
+        info!("=== Test: Chaos Challenger With Failed Challenge Transaction ===");
+
+        let env = TestEnvironment::setup().await?;
+
+        let scenario = ChaosScenario {
+            seed: Some(0),
+            faults: vec![FaultRule {
+                fault: Fault::FailTransaction { action: TxAction::Challenge },
+                probability: 1.0,
+                limit: Some(1),
+            }],
+        };
+        let challenger_handle = env.start_challenger(None, Some(&scenario)).await?;
+
+        // Create a game with an invalid output root
+        let factory = env.factory()?;
+        let init_bond = factory.initBonds(TEST_GAME_TYPE).call().await?;
+        let l2_block_number = env.anvil.starting_l2_block_number + 10;
+        env.create_game(B256::repeat_byte(0xaa), l2_block_number, u32::MAX, init_bond).await?;
+        let (_, invalid_game) = env.last_game_info().await?;
+
+        env.wait_for_challenges(&[invalid_game], WAIT_TIMEOUT).await?;
+        info!("✓ Challenger challenged the invalid game after the injected failure");
+
+        env.warp_time(MAX_CHALLENGE_DURATION + MAX_PROVE_DURATION).await?;
+        env.wait_and_verify_game_resolutions(
+            &[invalid_game],
+            GameStatus::CHALLENGER_WINS,
+            "ChallengerWins",
+            WAIT_TIMEOUT,
+        )
+        .await?;
+
+        assert!(!challenger_handle.is_finished(), "Challenger should still be running");
+        env.stop_challenger(challenger_handle);
+
+        Ok(())
+    }
+
     // Builds a chain with an invalid parent root and ensures the proposer rejects it.
     // Demonstrates new games anchor on valid history instead of corrupted branches.
     #[tokio::test(flavor = "multi_thread")]
@@ -438,7 +610,7 @@ mod integration {
         // === PHASE 2: Start Proposer and Verify Chain Rejection ===
         info!("=== Phase 2: Starting Proposer to Validate Chain ===");
 
-        let proposer_handle = env.start_proposer().await?;
+        let proposer_handle = env.start_proposer(None).await?;
 
         // Wait for proposer to create a new game (it should skip the invalid chain)
         let initial_game_count = child_game_index + U256::from(1);
@@ -581,7 +753,7 @@ mod integration {
         info!("=== Phase 3: Challenging Parent Game (after child was created) ===");
 
         // Start challenger to challenge the game (using malicious mode to challenge valid game)
-        let challenger_handle = env.start_challenger(Some(100.0)).await?;
+        let challenger_handle = env.start_challenger(Some(100.0), None).await?;
 
         // Wait for challenge
         env.wait_for_challenges(&[parent_game_address], WAIT_TIMEOUT).await?;
@@ -605,7 +777,7 @@ mod integration {
         info!("=== Phase 4: Starting Proposer to Validate Chain ===");
 
         // Start proposer
-        let proposer_handle = env.start_proposer().await?;
+        let proposer_handle = env.start_proposer(None).await?;
 
         // Wait for proposer to create a new game (it should skip the chain with challenged parent)
         let initial_game_count = child_game_index + U256::from(1);
@@ -739,7 +911,7 @@ mod integration {
         // === PHASE 3: Start proposer and verify it follows new anchor chain ===
         info!("=== Phase 3: Starting Proposer to Observe Anchor Reset ===");
 
-        let proposer_handle = env.start_proposer().await?;
+        let proposer_handle = env.start_proposer(None).await?;
         info!("✓ Proposer service started");
 
         let mut new_game_parent_verified = false;
@@ -795,7 +967,7 @@ mod integration {
         // === PHASE 1: Start Proposer and Create Initial Games ===
         info!("=== Phase 1: Start Proposer and Create Games ===");
 
-        let proposer_handle = env.start_proposer().await?;
+        let proposer_handle = env.start_proposer(None).await?;
 
         // Wait for proposer to create 3 games
         let factory = env.factory()?;
@@ -814,6 +986,7 @@ mod integration {
             &env.deployed.factory,
             env.game_type,
             Some(100.0),
+            None,
         )
         .await?;
         challenger.try_init().await?;
@@ -822,6 +995,7 @@ mod integration {
         let game_to_challenge = Game {
             index: U256::from(2),
             address: tracked_games[2].address,
+            created_at: 0,
             parent_index: 1,
             l2_block_number: tracked_games[2].l2_block_number,
             is_invalid: false,
@@ -12,6 +12,8 @@ mod asr_filtering {
         Game {
             index: U256::from(index),
             address: Address::left_padding_from(&[index as u8]),
+            creator: Address::ZERO,
+            created_at: 0,
             parent_index,
             l2_block: U256::from(l2_block),
             status: GameStatus::IN_PROGRESS,
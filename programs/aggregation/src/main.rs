@@ -8,7 +8,7 @@ use alloy_consensus::Header;
 use alloy_primitives::B256;
 use alloy_sol_types::SolValue;
 use op_succinct_client_utils::{
-    boot::BootInfoStruct,
+    boot::{BootInfoStruct, EXTENDED_RANGE_OUTPUT_VERSION},
     types::{u32_to_u8, AggregationInputs, AggregationOutputs},
 };
 use sha2::{Digest, Sha256};
@@ -22,6 +22,7 @@ pub fn main() {
     let headers_bytes = sp1_zkvm::io::read_vec();
     let headers: Vec<Header> = serde_cbor::from_slice(&headers_bytes).unwrap();
     assert!(!agg_inputs.boot_infos.is_empty());
+    assert_eq!(agg_inputs.boot_infos.len(), agg_inputs.extended_outputs.len());
 
     // Confirm that the boot infos are sequential.
     agg_inputs.boot_infos.windows(2).for_each(|pair| {
@@ -37,14 +38,20 @@ pub fn main() {
     });
 
     // Verify each range program proof.
-    agg_inputs.boot_infos.iter().for_each(|boot_info| {
-        // In the range program, the public values digest is just the hash of the ABI encoded
-        // boot info.
-        let serialized_boot_info = bincode::serialize(&boot_info).unwrap();
-        let pv_digest = Sha256::digest(serialized_boot_info);
-
-        sp1_lib::verify::verify_sp1_proof(&agg_inputs.multi_block_vkey, &pv_digest.into());
-    });
+    agg_inputs.boot_infos.iter().zip(&agg_inputs.extended_outputs).for_each(
+        |(boot_info, extended_outputs)| {
+            // In the range program, the public values are the serialized boot info, followed by
+            // the serialized extended outputs for the extended range ELF.
+            let mut public_values = bincode::serialize(&boot_info).unwrap();
+            if let Some(extended_outputs) = extended_outputs {
+                assert_eq!(extended_outputs.version, EXTENDED_RANGE_OUTPUT_VERSION);
+                public_values.extend(bincode::serialize(extended_outputs).unwrap());
+            }
+            let pv_digest = Sha256::digest(public_values);
+
+            sp1_lib::verify::verify_sp1_proof(&agg_inputs.multi_block_vkey, &pv_digest.into());
+        },
+    );
 
     // Create a map of each l1 head in the [`BootInfoStruct`]'s to booleans
     let mut l1_heads_map: HashMap<B256, bool> =
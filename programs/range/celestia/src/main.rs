@@ -10,25 +10,22 @@
 sp1_zkvm::entrypoint!(main);
 
 use op_succinct_celestia_client_utils::executor::CelestiaDAWitnessExecutor;
-use op_succinct_client_utils::witness::{DefaultWitnessData, WitnessData};
-use op_succinct_range_utils::run_range_program;
+use op_succinct_client_utils::witness::{zero_copy::ZeroCopyWitnessData, DefaultWitnessData};
 #[cfg(feature = "tracing-subscriber")]
 use op_succinct_range_utils::setup_tracing;
-use rkyv::rancor::Error;
+use op_succinct_range_utils::{read_witness, run_range_program};
 
 fn main() {
     #[cfg(feature = "tracing-subscriber")]
     setup_tracing();
 
     kona_proof::block_on(async move {
-        let witness_rkyv_bytes: Vec<u8> = sp1_zkvm::io::read_vec();
-        let witness_data = rkyv::from_bytes::<DefaultWitnessData, Error>(&witness_rkyv_bytes)
-            .expect("Failed to deserialize witness data.");
+        let witness_rkyv_bytes = read_witness();
 
-        let (oracle, beacon) = witness_data
-            .get_oracle_and_blob_provider()
-            .await
-            .expect("Failed to load oracle and blob provider");
+        // Serve preimages from the archived witness instead of deserializing them.
+        let (oracle, beacon, ()) =
+            DefaultWitnessData::get_zero_copy_oracle_and_blob_provider(witness_rkyv_bytes)
+                .expect("Failed to load oracle and blob provider");
 
         run_range_program(CelestiaDAWitnessExecutor::new(), oracle, beacon).await;
     });
@@ -14,32 +14,27 @@ use canoe_verifier_address_fetcher::CanoeVerifierAddressFetcherDeployedByEigenLa
 use hokulea_proof::eigenda_witness::EigenDAWitness;
 use hokulea_zkvm_verification::eigenda_witness_to_preloaded_provider;
 use kona_proof::BootInfo;
-use op_succinct_client_utils::witness::{EigenDAWitnessData, WitnessData};
+use op_succinct_client_utils::witness::{zero_copy::ZeroCopyWitnessData, EigenDAWitnessData};
 use op_succinct_eigenda_client_utils::executor::EigenDAWitnessExecutor;
-use op_succinct_range_utils::run_range_program;
 #[cfg(feature = "tracing-subscriber")]
 use op_succinct_range_utils::setup_tracing;
-use rkyv::rancor::Error;
+use op_succinct_range_utils::{read_witness, run_range_program};
 
 fn main() {
     #[cfg(feature = "tracing-subscriber")]
     setup_tracing();
 
     kona_proof::block_on(async move {
-        let witness_rkyv_bytes: Vec<u8> = sp1_zkvm::io::read_vec();
-        let witness_data = rkyv::from_bytes::<EigenDAWitnessData, Error>(&witness_rkyv_bytes)
-            .expect("Failed to deserialize witness data.");
-
-        let (oracle, beacon) = witness_data
-            .clone()
-            .get_oracle_and_blob_provider()
-            .await
-            .expect("Failed to load oracle and blob provider");
-
-        let eigenda_witness: EigenDAWitness = serde_cbor::from_slice(
-            &witness_data.eigenda_data.clone().expect("eigenda witness data is not present"),
-        )
-        .expect("cannot deserialize eigenda witness");
+        let witness_rkyv_bytes = read_witness();
+
+        // Serve preimages from the archived witness instead of deserializing them.
+        let (oracle, beacon, eigenda_data) =
+            EigenDAWitnessData::get_zero_copy_oracle_and_blob_provider(witness_rkyv_bytes)
+                .expect("Failed to load oracle and blob provider");
+
+        let eigenda_witness: EigenDAWitness =
+            serde_cbor::from_slice(&eigenda_data.expect("eigenda witness data is not present"))
+                .expect("cannot deserialize eigenda witness");
         let boot_info = BootInfo::load(oracle.as_ref()).await.expect("Failed to load boot info");
 
         let preloaded_preimage_provider = eigenda_witness_to_preloaded_provider(
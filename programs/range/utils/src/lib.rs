@@ -2,14 +2,27 @@ use std::sync::Arc;
 
 use kona_proof::{l1::OracleL1ChainProvider, l2::OracleL2ChainProvider};
 use op_succinct_client_utils::{
-    boot::BootInfoStruct,
+    boot::{BootInfoStruct, ExtendedRangeOutputs},
     witness::{
+        compression::WitnessCompression,
         executor::{get_inputs_for_pipeline, WitnessExecutor},
-        preimage_store::PreimageStore,
     },
     BlobStore,
 };
 
+/// Reads the rkyv-serialized witness from stdin, decompressing it with the codec the host wrote
+/// ahead of it.
+pub fn read_witness() -> Vec<u8> {
+    let compression: WitnessCompression = sp1_zkvm::io::read();
+    let witness = sp1_zkvm::io::read_vec();
+
+    println!("cycle-tracker-report-start: witness-decompression");
+    let witness = compression.decompress(witness).expect("Failed to decompress the witness");
+    println!("cycle-tracker-report-end: witness-decompression");
+
+    witness
+}
+
 /// Sets up tracing for the range program
 #[cfg(feature = "tracing-subscriber")]
 pub fn setup_tracing() {
@@ -20,13 +33,19 @@ pub fn setup_tracing() {
     tracing::subscriber::set_global_default(subscriber).map_err(|e| anyhow!(e)).unwrap();
 }
 
-pub async fn run_range_program<E>(executor: E, oracle: Arc<PreimageStore>, beacon: BlobStore)
+/// Runs the range program over the given oracle, which is either an owned
+/// [`op_succinct_client_utils::witness::preimage_store::PreimageStore`] or a
+/// [`op_succinct_client_utils::witness::zero_copy::ZeroCopyPreimageStore`] reading preimages in
+/// place from the archived witness.
+///
+/// Commits the [BootInfoStruct], followed by the [ExtendedRangeOutputs] when built with the
+/// `extended-outputs` feature.
+pub async fn run_range_program<E>(executor: E, oracle: Arc<E::O>, beacon: BlobStore)
 where
     E: WitnessExecutor<
-            O = PreimageStore,
             B = BlobStore,
-            L1 = OracleL1ChainProvider<PreimageStore>,
-            L2 = OracleL2ChainProvider<PreimageStore>,
+            L1 = OracleL1ChainProvider<<E as WitnessExecutor>::O>,
+            L2 = OracleL2ChainProvider<<E as WitnessExecutor>::O>,
         > + Send
         + Sync,
 {
@@ -34,8 +53,9 @@ where
     //                          PROLOGUE                          //
     ////////////////////////////////////////////////////////////////
     let (boot_info, input) = get_inputs_for_pipeline(oracle.clone()).await.unwrap();
-    let boot_info = match input {
+    let (boot_info, extended_outputs) = match input {
         Some((cursor, l1_provider, l2_provider)) => {
+            let start_block = cursor.read().l2_safe_head().block_info.number;
             let rollup_config = Arc::new(boot_info.rollup_config.clone());
             let l1_config = Arc::new(boot_info.l1_config.clone());
 
@@ -52,10 +72,34 @@ where
                 .await
                 .unwrap();
 
-            executor.run(boot_info, pipeline, cursor, l2_provider).await.unwrap()
+            let (boot_info, transaction_count) =
+                executor.run(boot_info, pipeline, cursor.clone(), l2_provider).await.unwrap();
+
+            let extended_outputs = cfg!(feature = "extended-outputs").then(|| {
+                let cursor = cursor.read();
+                let tip = cursor.tip();
+                ExtendedRangeOutputs::new(
+                    &boot_info.rollup_config,
+                    &tip.l2_safe_head_header,
+                    tip.l2_safe_head.block_info.number - start_block,
+                    transaction_count,
+                )
+                .expect("Failed to build extended range outputs")
+            });
+
+            (boot_info, extended_outputs)
+        }
+        None => {
+            assert!(
+                !cfg!(feature = "extended-outputs"),
+                "Extended range outputs require a derived range"
+            );
+            (boot_info, None)
         }
-        None => boot_info,
     };
 
     sp1_zkvm::io::commit(&BootInfoStruct::from(boot_info));
+    if let Some(extended_outputs) = extended_outputs {
+        sp1_zkvm::io::commit(&extended_outputs);
+    }
 }
@@ -1,13 +1,17 @@
 use alloy_primitives::{Address, B256};
 use anyhow::{Context, Result};
 use clap::Parser;
-use op_succinct_client_utils::{boot::BootInfoStruct, types::u32_to_u8};
+use op_succinct_client_utils::{
+    boot::{BootInfoStruct, ExtendedRangeOutputs},
+    types::u32_to_u8,
+};
 use op_succinct_elfs::AGGREGATION_ELF;
 use op_succinct_host_utils::{
     fetcher::OPSuccinctDataFetcher,
     get_agg_proof_stdin,
     network::{build_network_prover_from_env, parse_fulfillment_strategy},
     proof_cache::{get_range_proof_dir, save_agg_proof},
+    read_range_outputs,
 };
 use op_succinct_proof_utils::{
     cluster_agg_proof, cluster_setup_keys, get_range_elf_embedded, is_cluster_mode,
@@ -51,12 +55,13 @@ async fn load_aggregation_proof_data(
     chain_id: u64,
     proof_names: Vec<String>,
     range_vkey: SP1VerifyingKey,
-) -> (Vec<SP1Proof>, Vec<BootInfoStruct>) {
+) -> (Vec<SP1Proof>, Vec<BootInfoStruct>, Vec<Option<ExtendedRangeOutputs>>) {
     tokio::task::spawn_blocking(move || {
         let proof_directory = get_range_proof_dir(chain_id);
 
         let mut proofs = Vec::with_capacity(proof_names.len());
         let mut boot_infos = Vec::with_capacity(proof_names.len());
+        let mut extended_outputs = Vec::with_capacity(proof_names.len());
 
         let prover = blocking::CpuProver::new();
 
@@ -65,18 +70,20 @@ async fn load_aggregation_proof_data(
             if !proof_path.exists() {
                 panic!("Proof file not found: {}", proof_path.display());
             }
-            let mut deserialized_proof =
+            let deserialized_proof =
                 SP1ProofWithPublicValues::load(&proof_path).expect("loading proof failed");
             prover
                 .verify(&deserialized_proof, &range_vkey, None)
                 .expect("proof verification failed");
+            let (boot_info, extended) =
+                read_range_outputs(deserialized_proof.public_values.as_slice())
+                    .expect("reading range outputs failed");
             proofs.push(deserialized_proof.proof);
-
-            let boot_info = deserialized_proof.public_values.read();
             boot_infos.push(boot_info);
+            extended_outputs.push(extended);
         }
 
-        (proofs, boot_infos)
+        (proofs, boot_infos, extended_outputs)
     })
     .await
     .expect("load_aggregation_proof_data task panicked")
@@ -89,7 +96,7 @@ async fn build_agg_stdin(
     range_vkey: &SP1VerifyingKey,
     prover_address: Address,
 ) -> Result<SP1Stdin> {
-    let (proofs, boot_infos) =
+    let (proofs, boot_infos, extended_outputs) =
         load_aggregation_proof_data(chain_id, proof_names, range_vkey.clone()).await;
 
     let header = fetcher.get_latest_l1_head_in_batch(&boot_infos).await?;
@@ -99,8 +106,15 @@ async fn build_agg_stdin(
     let multi_block_vkey_b256 = B256::from(multi_block_vkey_u8);
     println!("Range ELF Verification Key Commitment: {multi_block_vkey_b256}");
 
-    let stdin =
-        get_agg_proof_stdin(proofs, boot_infos, headers, range_vkey, l1_head_hash, prover_address)?;
+    let stdin = get_agg_proof_stdin(
+        proofs,
+        boot_infos,
+        extended_outputs,
+        headers,
+        range_vkey,
+        l1_head_hash,
+        prover_address,
+    )?;
 
     Ok(stdin)
 }
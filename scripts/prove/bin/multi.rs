@@ -2,18 +2,19 @@ use anyhow::{Context, Result};
 use clap::Parser;
 use op_succinct_host_utils::{
     block_range::get_validated_block_range,
-    fetcher::OPSuccinctDataFetcher,
+    fetcher::{BlockInfo, OPSuccinctDataFetcher},
     host::OPSuccinctHost,
     network::{build_network_prover_from_env, parse_fulfillment_strategy},
+    preimage_kv::PreimageStoreManifest,
     proof_cache::save_range_proof,
-    stats::ExecutionStats,
+    stats::{ExecutionStats, ExecutionStatsCsvRow},
     witness_cache::{load_stdin_from_cache, save_stdin_to_cache},
-    witness_generation::WitnessGenerator,
+    witness_generation::{compression::witness_info, WitnessGenerator},
 };
 use op_succinct_proof_utils::{
     cluster_range_proof, get_range_elf_embedded, initialize_host, is_cluster_mode,
 };
-use op_succinct_prove::execute_multi;
+use op_succinct_prove::{execute_multi, execute_range};
 use op_succinct_scripts::HostExecutorArgs;
 use sp1_sdk::{utils, Elf, ProveRequest, Prover};
 use std::{
@@ -32,27 +33,41 @@ async fn main() -> Result<()> {
         .context(format!("Environment file not found: {}", args.env_file.display()))?;
     utils::setup_logger();
 
-    let data_fetcher = OPSuccinctDataFetcher::new_with_rollup_config().await?;
+    // A replay reads the block range and chain configs from the preimage store, so the fetcher
+    // doesn't load the rollup config from the RPCs.
+    let replay_manifest = args.replay.as_deref().map(PreimageStoreManifest::load).transpose()?;
+    let data_fetcher = match replay_manifest {
+        Some(_) => OPSuccinctDataFetcher::new(),
+        None => OPSuccinctDataFetcher::new_with_rollup_config().await?,
+    };
 
     let host = initialize_host(Arc::new(data_fetcher.clone()));
 
-    // If the end block is provided, check that it is less than the latest finalized block. If the
-    // end block is not provided, use the latest finalized block.
-    let (l2_start_block, l2_end_block) = get_validated_block_range(
-        host.as_ref(),
-        &data_fetcher,
-        args.start,
-        args.end,
-        args.default_range,
-    )
-    .await?;
-
-    let l2_chain_id = data_fetcher.get_l2_chain_id().await?;
+    let (l2_chain_id, l2_start_block, l2_end_block) = match &replay_manifest {
+        Some(manifest) => {
+            (manifest.l2_chain_id, manifest.l2_start_block, manifest.claimed_l2_block_number)
+        }
+        None => {
+            // If the end block is provided, check that it is less than the latest finalized block.
+            // If the end block is not provided, use the latest finalized block.
+            let (l2_start_block, l2_end_block) = get_validated_block_range(
+                host.as_ref(),
+                &data_fetcher,
+                args.start,
+                args.end,
+                args.default_range,
+            )
+            .await?;
+            (data_fetcher.get_l2_chain_id().await?, l2_start_block, l2_end_block)
+        }
+    };
 
     // Helper closure to generate stdin (runs witness generation and converts to SP1Stdin)
     let generate_stdin = || async {
-        let host_args =
-            host.fetch(l2_start_block, l2_end_block, None, args.safe_db_fallback).await?;
+        let host_args = match &args.replay {
+            Some(store_dir) => host.replay_args(store_dir)?,
+            None => host.fetch(l2_start_block, l2_end_block, None, args.safe_db_fallback).await?,
+        };
         debug!("Host args: {:?}", host_args);
 
         let start_time = Instant::now();
@@ -110,8 +125,24 @@ async fn main() -> Result<()> {
             info!("Range proof saved to {}", path.display());
         }
     } else {
-        let (block_data, report, execution_duration) =
-            execute_multi(&data_fetcher, sp1_stdin, l2_start_block, l2_end_block).await?;
+        let witness_info = witness_info(&sp1_stdin)?;
+        let (block_data, report, execution_duration) = if args.replay.is_some() {
+            // The block data comes from the L2 RPC, so a replay reports the range's blocks without
+            // their transactions, gas and fees.
+            let (report, execution_duration) = execute_range(sp1_stdin).await?;
+            let block_data = (l2_start_block + 1..=l2_end_block)
+                .map(|block_number| BlockInfo {
+                    block_number,
+                    transaction_count: 0,
+                    gas_used: 0,
+                    total_l1_fees: 0,
+                    total_tx_fees: 0,
+                })
+                .collect();
+            (block_data, report, execution_duration)
+        } else {
+            execute_multi(&data_fetcher, sp1_stdin, l2_start_block, l2_end_block).await?
+        };
 
         let stats = ExecutionStats::new(
             0,
@@ -119,7 +150,8 @@ async fn main() -> Result<()> {
             &report,
             witness_generation_duration.as_secs(),
             execution_duration.as_secs(),
-        );
+        )
+        .with_witness_info(witness_info);
 
         println!("Execution Stats: \n{stats:?}");
 
@@ -134,7 +166,7 @@ async fn main() -> Result<()> {
 
         // Write to CSV.
         let mut csv_writer = csv::Writer::from_path(report_path)?;
-        csv_writer.serialize(&stats)?;
+        csv_writer.serialize(ExecutionStatsCsvRow::from(&stats))?;
         csv_writer.flush()?;
     }
 
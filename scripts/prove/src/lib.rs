@@ -16,6 +16,15 @@ pub async fn execute_multi(
     l2_start_block: u64,
     l2_end_block: u64,
 ) -> Result<(Vec<BlockInfo>, ExecutionReport, Duration)> {
+    let (report, execution_duration) = execute_range(sp1_stdin).await?;
+
+    let block_data = data_fetcher.get_l2_block_data_range(l2_start_block, l2_end_block).await?;
+
+    Ok((block_data, report, execution_duration))
+}
+
+/// Execute the range program on `sp1_stdin`, without fetching the range's block data.
+pub async fn execute_range(sp1_stdin: SP1Stdin) -> Result<(ExecutionReport, Duration)> {
     let start_time = Instant::now();
 
     // CpuProver creates its own tokio runtime, so run it outside the async context.
@@ -29,9 +38,5 @@ pub async fn execute_multi(
     })
     .await??;
 
-    let execution_duration = start_time.elapsed();
-
-    let block_data = data_fetcher.get_l2_block_data_range(l2_start_block, l2_end_block).await?;
-
-    Ok((block_data, report, execution_duration))
+    Ok((report, start_time.elapsed()))
 }
@@ -1,4 +1,4 @@
-use std::{fmt::Write as _, fs::File, sync::Arc};
+use std::{collections::BTreeSet, fmt::Write as _, fs::File, sync::Arc};
 
 use anyhow::Result;
 use common::{post_to_github_pr, DEFAULT_RANGE};
@@ -7,13 +7,16 @@ use op_succinct_host_utils::{
     fetcher::OPSuccinctDataFetcher,
     host::OPSuccinctHost,
     stats::{ExecutionStats, MarkdownExecutionStats},
-    witness_generation::WitnessGenerator,
+    witness_generation::{compression::witness_info, WitnessGenerator},
 };
 use op_succinct_proof_utils::initialize_host;
 use op_succinct_prove::execute_multi;
 
 mod common;
 
+/// Maximum allowed increase of the oracle verify cycles over the base branch, in percent.
+const MAX_ORACLE_VERIFY_REGRESSION_PERCENT: f64 = 1.0;
+
 fn elf_label() -> &'static str {
     cfg_if::cfg_if! {
         if #[cfg(feature = "celestia")] {
@@ -89,30 +92,46 @@ fn create_diff_report(base: &ExecutionStats, current: &ExecutionStats) -> String
         base.blob_verification_instruction_count,
         current.blob_verification_instruction_count,
     );
-    write_metric(&mut report, "Total SP1 Gas", base.total_sp1_gas, current.total_sp1_gas);
-    write_metric(&mut report, "Cycles per Block", base.cycles_per_block, current.cycles_per_block);
     write_metric(
         &mut report,
-        "Cycles per Transaction",
-        base.cycles_per_transaction,
-        current.cycles_per_transaction,
+        "Witness Decompression Cycles",
+        base.witness_decompression_instruction_count,
+        current.witness_decompression_instruction_count,
     );
-    write_metric(&mut report, "BN Pair Cycles", base.bn_pair_cycles, current.bn_pair_cycles);
-    write_metric(&mut report, "BN Add Cycles", base.bn_add_cycles, current.bn_add_cycles);
-    write_metric(&mut report, "BN Mul Cycles", base.bn_mul_cycles, current.bn_mul_cycles);
-    write_metric(&mut report, "KZG Eval Cycles", base.kzg_eval_cycles, current.kzg_eval_cycles);
     write_metric(
         &mut report,
-        "EC Recover Cycles",
-        base.ec_recover_cycles,
-        current.ec_recover_cycles,
+        "Witness Size (bytes)",
+        base.witness_size_bytes,
+        current.witness_size_bytes,
     );
+    write_metric(&mut report, "Total SP1 Gas", base.total_sp1_gas, current.total_sp1_gas);
     write_metric(
         &mut report,
-        "P256 Verify Cycles",
-        base.p256_verify_cycles,
-        current.p256_verify_cycles,
+        "Touched Memory Addresses",
+        base.touched_memory_addresses,
+        current.touched_memory_addresses,
     );
+    write_metric(&mut report, "Cycles per Block", base.cycles_per_block, current.cycles_per_block);
+    write_metric(
+        &mut report,
+        "Cycles per Transaction",
+        base.cycles_per_transaction,
+        current.cycles_per_transaction,
+    );
+    // Compare every precompile either side tracked.
+    let precompiles: BTreeSet<&String> =
+        base.precompiles.keys().chain(current.precompiles.keys()).collect();
+    for name in precompiles {
+        let base_stats = base.precompiles.get(name).copied().unwrap_or_default();
+        let current_stats = current.precompiles.get(name).copied().unwrap_or_default();
+        write_metric(
+            &mut report,
+            &format!("{name} Cycles"),
+            base_stats.cycles,
+            current_stats.cycles,
+        );
+        write_metric(&mut report, &format!("{name} Calls"), base_stats.calls, current_stats.calls);
+    }
 
     report
 }
@@ -146,10 +165,12 @@ async fn test_cycle_count_diff() -> Result<()> {
 
     let witness_data = host.run(&host_args).await?;
     let sp1_stdin = host.witness_generator().get_sp1_stdin(witness_data)?;
+    let witness_info = witness_info(&sp1_stdin)?;
     let (block_data, report, execution_duration) =
         execute_multi(&data_fetcher, sp1_stdin, l2_start_block, l2_end_block).await?;
 
-    let new_stats = ExecutionStats::new(0, &block_data, &report, 0, execution_duration.as_secs());
+    let new_stats = ExecutionStats::new(0, &block_data, &report, 0, execution_duration.as_secs())
+        .with_witness_info(witness_info);
 
     println!("Execution Stats:\n{}", MarkdownExecutionStats::new(new_stats.clone()));
     let mut file = match std::env::var("NEW_BRANCH")
@@ -165,6 +186,27 @@ async fn test_cycle_count_diff() -> Result<()> {
     Ok(())
 }
 
+/// Fail if verifying the witness preimages (the `oracle-verify` cycle tracker) costs more cycles
+/// than on the base branch.
+#[test]
+fn test_oracle_verify_cycles_regression() -> Result<()> {
+    let old_stats =
+        serde_json::from_reader::<_, ExecutionStats>(File::open("old_cycle_stats.json")?)?;
+    let new_stats =
+        serde_json::from_reader::<_, ExecutionStats>(File::open("new_cycle_stats.json")?)?;
+
+    let max_cycles = old_stats.oracle_verify_instruction_count as f64 *
+        (1.0 + MAX_ORACLE_VERIFY_REGRESSION_PERCENT / 100.0);
+    assert!(
+        new_stats.oracle_verify_instruction_count as f64 <= max_cycles,
+        "Oracle verify cycles regressed: {} (base) -> {} (current)",
+        old_stats.oracle_verify_instruction_count,
+        new_stats.oracle_verify_instruction_count,
+    );
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_post_to_github() -> Result<()> {
     let old_stats =
@@ -9,9 +9,11 @@ use op_succinct_host_utils::{
     },
     fetcher::OPSuccinctDataFetcher,
     host::OPSuccinctHost,
-    stats::ExecutionStats,
+    stats::{ExecutionStats, ExecutionStatsCsvRow},
     witness_cache::{load_stdin_from_cache, save_stdin_to_cache},
-    witness_generation::WitnessGenerator,
+    witness_generation::{
+        compression::witness_info, SharedPreimageCache, WitnessGenOptions, WitnessGenerator,
+    },
 };
 use op_succinct_proof_utils::{get_range_elf_embedded, initialize_host};
 use op_succinct_scripts::HostExecutorArgs;
@@ -72,10 +74,19 @@ where
     fs::File::create(&report_path).unwrap();
     let report_path = report_path.canonicalize().unwrap();
 
+    // The split ranges are adjacent, so share the preimages fetched for each range's witness with
+    // the others.
+    let options = WitnessGenOptions::from_env()
+        .expect("Invalid witness generation limits")
+        .with_shared_cache(
+            SharedPreimageCache::from_env().expect("Invalid SHARED_PREIMAGE_CACHE_BYTES"),
+        );
+
     // Run the host tasks in parallel using join_all
     let handles = host_args.iter().zip(ranges.iter()).map(|(host_args, range)| {
         let host_args = host_args.clone();
         let host = host.clone();
+        let options = options.clone();
         let start = range.start;
         let end = range.end;
         tokio::spawn(async move {
@@ -94,7 +105,7 @@ where
             }
 
             // Generate witness and convert to SP1Stdin
-            let witness_data = host.run(&host_args).await.unwrap();
+            let witness_data = host.run_with_options(&host_args, &options).await.unwrap();
             let stdin = host.witness_generator().get_sp1_stdin(witness_data).unwrap();
 
             // Save SP1Stdin to cache
@@ -122,6 +133,7 @@ where
     tokio::task::spawn_blocking(move || {
         let prover = CpuProver::new();
         execution_inputs.into_par_iter().for_each(|(sp1_stdin, (range, block_data))| {
+            let witness_info = witness_info(&sp1_stdin).expect("Failed to read the witness info.");
             let result = prover
                 .execute(Elf::Static(get_range_elf_embedded()), sp1_stdin)
                 .deferred_proof_verification(false)
@@ -139,7 +151,8 @@ where
 
             let (_, report) = result.unwrap();
 
-            let execution_stats = ExecutionStats::new(0, &block_data, &report, 0, 0);
+            let execution_stats = ExecutionStats::new(0, &block_data, &report, 0, 0)
+                .with_witness_info(witness_info);
 
             let mut file = OpenOptions::new()
                 .read(true)
@@ -155,7 +168,7 @@ where
                 .from_writer(file);
 
             csv_writer
-                .serialize(execution_stats.clone())
+                .serialize(ExecutionStatsCsvRow::from(&execution_stats))
                 .expect("Failed to write execution stats to CSV.");
             csv_writer.flush().expect("Failed to flush CSV writer.");
         });
@@ -187,16 +200,24 @@ fn aggregate_execution_stats(
             stats.block_execution_instruction_count;
         aggregate_stats.blob_verification_instruction_count +=
             stats.blob_verification_instruction_count;
+        aggregate_stats.witness_decompression_instruction_count +=
+            stats.witness_decompression_instruction_count;
+        aggregate_stats.witness_compression = stats.witness_compression;
+        aggregate_stats.witness_size_bytes += stats.witness_size_bytes;
         aggregate_stats.total_sp1_gas += stats.total_sp1_gas;
         aggregate_stats.nb_blocks += stats.nb_blocks;
         aggregate_stats.nb_transactions += stats.nb_transactions;
         aggregate_stats.eth_gas_used += stats.eth_gas_used;
-        aggregate_stats.bn_pair_cycles += stats.bn_pair_cycles;
-        aggregate_stats.bn_add_cycles += stats.bn_add_cycles;
-        aggregate_stats.bn_mul_cycles += stats.bn_mul_cycles;
-        aggregate_stats.kzg_eval_cycles += stats.kzg_eval_cycles;
-        aggregate_stats.ec_recover_cycles += stats.ec_recover_cycles;
-        aggregate_stats.p256_verify_cycles += stats.p256_verify_cycles;
+        for (name, precompile) in &stats.precompiles {
+            let aggregate = aggregate_stats.precompiles.entry(name.clone()).or_default();
+            aggregate.cycles += precompile.cycles;
+            aggregate.calls += precompile.calls;
+        }
+        aggregate_stats.blocks.extend_from_slice(&stats.blocks);
+
+        // Each range runs in its own guest, so report the peak rather than the sum.
+        aggregate_stats.touched_memory_addresses =
+            max(aggregate_stats.touched_memory_addresses, stats.touched_memory_addresses);
     }
 
     // For statistics that are per-block or per-transaction, we take the average over the entire
@@ -213,6 +234,9 @@ fn aggregate_execution_stats(
     aggregate_stats.gas_used_per_transaction =
         safe_div(aggregate_stats.eth_gas_used, aggregate_stats.nb_transactions);
 
+    // Ranges finish in any order, so sort the blocks from all of them.
+    aggregate_stats.blocks.sort_by_key(|block| block.block_number);
+
     // Use the earliest start and latest end across all blocks.
     aggregate_stats.batch_start = batch_start;
     aggregate_stats.batch_end = batch_end;
@@ -297,8 +321,8 @@ async fn main() -> Result<()> {
     let mut final_execution_stats = Vec::new();
     let mut csv_reader = csv::Reader::from_path(&report_path)?;
     for result in csv_reader.deserialize() {
-        let stats: ExecutionStats = result?;
-        final_execution_stats.push(stats);
+        let row: ExecutionStatsCsvRow = result?;
+        final_execution_stats.push(ExecutionStats::try_from(row)?);
     }
 
     println!("Wrote execution stats to {}", report_path.display());
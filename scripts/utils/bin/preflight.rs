@@ -13,7 +13,6 @@ use clap::Parser;
 use fault_proof::contract::{
     AnchorStateRegistry, DisputeGameFactory, OPSuccinctFaultDisputeGame, ProposalStatus,
 };
-use op_succinct_client_utils::boot::BootInfoStruct;
 use op_succinct_elfs::AGGREGATION_ELF;
 use op_succinct_host_utils::{
     fetcher::OPSuccinctDataFetcher,
@@ -21,6 +20,7 @@ use op_succinct_host_utils::{
     host::OPSuccinctHost,
     network::{determine_network_mode, get_network_signer, parse_fulfillment_strategy},
     proof_cache::{save_agg_proof, save_range_proof},
+    read_range_outputs,
     witness_generation::WitnessGenerator,
 };
 use op_succinct_proof_utils::{get_range_elf_embedded, initialize_host};
@@ -234,7 +234,7 @@ async fn main() -> Result<()> {
     info!("Initialized network prover successfully");
 
     let range_pk = network_prover.setup(Elf::Static(get_range_elf_embedded())).await?;
-    let mut range_proof = network_prover
+    let range_proof = network_prover
         .prove(&range_pk, range_proof_stdin)
         .compressed()
         .strategy(range_proof_strategy)
@@ -248,7 +248,7 @@ async fn main() -> Result<()> {
     info!("Range proof saved to {}", range_proof_path.display());
 
     // 3. Generate the aggregation proof.
-    let boot_info: BootInfoStruct = range_proof.public_values.read();
+    let (boot_info, extended_outputs) = read_range_outputs(range_proof.public_values.as_slice())?;
     assert_eq!(boot_info.l1Head, l1_head_hash, "L1 head hash mismatch");
 
     // Initialize the network prover.
@@ -262,6 +262,7 @@ async fn main() -> Result<()> {
     let agg_proof_stdin = get_agg_proof_stdin(
         vec![range_proof.proof],
         vec![boot_info.clone()],
+        vec![extended_outputs],
         vec![l1_head.clone()],
         &range_vk,
         boot_info.l1Head,
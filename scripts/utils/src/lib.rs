@@ -39,6 +39,10 @@ pub struct HostExecutorArgs {
     /// Cluster proving timeout in seconds (only used when SP1_PROVER=cluster).
     #[arg(long, default_value = "21600")]
     pub cluster_timeout: u64,
+    /// Replay witness generation from a range's on-disk preimage store directory, without any
+    /// RPC. The block range is read from the store, so `--start` and `--end` are ignored.
+    #[arg(long)]
+    pub replay: Option<PathBuf>,
 }
 
 /// Fallback batch size used when the user provides neither `--batch-size`
@@ -92,6 +96,7 @@ mod tests {
             prove: false,
             safe_db_fallback: false,
             cluster_timeout: 21600,
+            replay: None,
         }
     }
 
@@ -1,11 +1,14 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use alloy_primitives::B256;
 use anyhow::{bail, Result};
 use async_trait::async_trait;
 use hana_host::celestia::{CelestiaCfg, CelestiaChainHost};
 use op_succinct_celestia_client_utils::executor::CelestiaDAWitnessExecutor;
-use op_succinct_host_utils::{fetcher::OPSuccinctDataFetcher, host::OPSuccinctHost};
+use op_succinct_host_utils::{
+    fetcher::OPSuccinctDataFetcher, host::OPSuccinctHost, preimage_kv::PreimageStoreManifest,
+    witness_generation::witness_compression_from_env,
+};
 
 use crate::{
     blobstream_utils::{get_celestia_safe_head_info, get_highest_finalized_l2_block},
@@ -54,6 +57,19 @@ impl OPSuccinctHost for CelestiaOPSuccinctHost {
         Ok(CelestiaChainHost { single_host: host, celestia_args })
     }
 
+    fn replay_args(&self, store_dir: &Path) -> Result<CelestiaChainHost> {
+        let single_host = PreimageStoreManifest::load(store_dir)?.offline_host_args(store_dir)?;
+
+        // Without a Celestia connection, Celestia blobs are served from the store as well.
+        let celestia_args = CelestiaCfg {
+            celestia_connection: None,
+            auth_token: None,
+            namespace: std::env::var("NAMESPACE").ok(),
+        };
+
+        Ok(CelestiaChainHost { single_host, celestia_args })
+    }
+
     fn get_l1_head_hash(&self, args: &Self::Args) -> Option<B256> {
         Some(args.single_host.l1_head)
     }
@@ -90,6 +106,7 @@ impl CelestiaOPSuccinctHost {
             fetcher,
             witness_generator: Arc::new(CelestiaDAWitnessGenerator {
                 executor: CelestiaDAWitnessExecutor::new(),
+                compression: witness_compression_from_env().expect("Invalid WITNESS_COMPRESSION"),
             }),
         }
     }
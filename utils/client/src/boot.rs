@@ -1,13 +1,22 @@
 //! This module contains the prologue phase of the client program, pulling in the boot
 //! information, which is passed to the zkVM a public inputs to be verified on chain.
 
-use alloy_primitives::B256;
+use alloy_consensus::Header;
+use alloy_primitives::{Sealed, B256};
 use alloy_sol_types::sol;
+use anyhow::{anyhow, Result};
 use kona_genesis::RollupConfig;
 use kona_proof::BootInfo;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+/// The version of the [ExtendedRangeOutputs] format.
+///
+/// The default range ELF commits only the [BootInfoStruct]. The extended range ELF (built with the
+/// `extended-outputs` feature) commits the [BootInfoStruct] followed by [ExtendedRangeOutputs], so
+/// readers of the boot info and the verifier contracts are unaffected by the extension.
+pub const EXTENDED_RANGE_OUTPUT_VERSION: u32 = 1;
+
 // ABI encoding of AggregationOutputs is 6 * 32 bytes.
 pub const AGGREGATION_OUTPUTS_SIZE: usize = 6 * 32;
 
@@ -50,3 +59,45 @@ impl From<BootInfo> for BootInfoStruct {
         }
     }
 }
+
+sol! {
+    #[derive(Debug, Serialize, Deserialize)]
+    struct ExtendedRangeOutputs {
+        uint32 version;
+        bytes32 l2BlockHash;
+        bytes32 messagePasserStorageRoot;
+        uint64 numBlocks;
+        uint64 numTransactions;
+    }
+}
+
+impl ExtendedRangeOutputs {
+    /// The extended outputs of a range ending at `header`.
+    ///
+    /// The L2ToL1MessagePasser storage root is read from the header's withdrawals root, so the last
+    /// block of the range must be an Isthmus block.
+    pub fn new(
+        rollup_config: &RollupConfig,
+        header: &Sealed<Header>,
+        num_blocks: u64,
+        num_transactions: u64,
+    ) -> Result<Self> {
+        if !rollup_config.is_isthmus_active(header.timestamp) {
+            return Err(anyhow!(
+                "Extended range outputs require Isthmus, but block {} is pre-Isthmus",
+                header.number
+            ));
+        }
+        let message_passer_storage_root = header
+            .withdrawals_root
+            .ok_or_else(|| anyhow!("Isthmus block {} has no withdrawals root", header.number))?;
+
+        Ok(Self {
+            version: EXTENDED_RANGE_OUTPUT_VERSION,
+            l2BlockHash: header.hash(),
+            messagePasserStorageRoot: message_passer_storage_root,
+            numBlocks: num_blocks,
+            numTransactions: num_transactions,
+        })
+    }
+}
@@ -12,6 +12,22 @@ use op_alloy_consensus::{OpBlock, OpTxEnvelope, OpTxType};
 use std::fmt::Debug;
 use tracing::{error, info, warn};
 
+/// Prefix of the per-block cycle tracker spans emitted by [advance_to_target].
+pub const BLOCK_CYCLE_TRACKER_PREFIX: &str = "l2-block-";
+
+/// The cycle tracker key of the span covering the execution of an L2 block, tagged with the
+/// block number and the number of transactions in its payload attributes.
+pub fn block_cycle_tracker_key(block_number: u64, transaction_count: usize) -> String {
+    format!("{BLOCK_CYCLE_TRACKER_PREFIX}{block_number}-txs-{transaction_count}")
+}
+
+/// Parses the block number and transaction count out of a key made by [block_cycle_tracker_key].
+pub fn parse_block_cycle_tracker_key(key: &str) -> Option<(u64, u64)> {
+    let (block_number, transaction_count) =
+        key.strip_prefix(BLOCK_CYCLE_TRACKER_PREFIX)?.split_once("-txs-")?;
+    Some((block_number.parse().ok()?, transaction_count.parse().ok()?))
+}
+
 /// Fetches the safe head hash of the L2 chain based on the agreed upon L2 output root in the
 /// [BootInfo].
 pub(crate) async fn fetch_safe_head_hash<O>(
@@ -45,19 +61,20 @@ where
 /// - `target`: The target block number.
 ///
 /// ## Returns
-/// - `Ok((l2_safe_head, output_root))` - A tuple containing the [L2BlockInfo] of the produced block
-///   and the output root.
+/// - `Ok((l2_safe_head, output_root, transaction_count))` - A tuple containing the [L2BlockInfo] of
+///   the produced block, the output root, and the number of transactions in the produced blocks.
 /// - `Err(e)` - An error if the block could not be produced.
 pub async fn advance_to_target<E, DP, P>(
     driver: &mut Driver<E, DP, P>,
     cfg: &RollupConfig,
     mut target: Option<u64>,
-) -> DriverResult<(L2BlockInfo, B256), E::Error>
+) -> DriverResult<(L2BlockInfo, B256, u64), E::Error>
 where
     E: Executor + Send + Sync + Debug,
     DP: DriverPipeline<P> + Send + Sync + Debug,
     P: Pipeline + SignalReceiver + Send + Sync + Debug,
 {
+    let mut transaction_count = 0;
     loop {
         // Check if we have reached the target block number.
         let pipeline_cursor = driver.cursor.read();
@@ -65,7 +82,11 @@ where
         if let Some(tb) = target {
             if tip_cursor.l2_safe_head.block_info.number >= tb {
                 info!(target: "client", "Derivation complete, reached L2 safe head.");
-                return Ok((tip_cursor.l2_safe_head, tip_cursor.l2_safe_head_output_root));
+                return Ok((
+                    tip_cursor.l2_safe_head,
+                    tip_cursor.l2_safe_head_output_root,
+                    transaction_count,
+                ));
             }
         }
 
@@ -100,6 +121,16 @@ where
 
         driver.executor.update_safe_head(tip_cursor.l2_safe_head_header.clone());
 
+        // Track the cycles of each block, so a range that exceeds the cycle limit can be traced
+        // back to the blocks responsible.
+        #[cfg(target_os = "zkvm")]
+        let block_span = block_cycle_tracker_key(
+            tip_cursor.l2_safe_head.block_info.number + 1,
+            attributes.transactions.as_ref().map_or(0, Vec::len),
+        );
+        #[cfg(target_os = "zkvm")]
+        println!("cycle-tracker-report-start: {block_span}");
+
         #[cfg(target_os = "zkvm")]
         println!("cycle-tracker-report-start: block-execution");
         let outcome = match driver.executor.execute_payload(attributes.clone()).await {
@@ -138,12 +169,16 @@ where
                     }
                 } else {
                     // Pre-Holocene, discard the block if execution fails.
+                    #[cfg(target_os = "zkvm")]
+                    println!("cycle-tracker-report-end: {block_span}");
                     continue;
                 }
             }
         };
         #[cfg(target_os = "zkvm")]
         println!("cycle-tracker-report-end: block-execution");
+        #[cfg(target_os = "zkvm")]
+        println!("cycle-tracker-report-end: {block_span}");
 
         // Construct the block.
         let block = OpBlock {
@@ -161,6 +196,8 @@ where
             },
         };
 
+        transaction_count += block.body.transactions.len() as u64;
+
         // Get the pipeline origin and update the tip cursor.
         let origin = driver.pipeline.origin().ok_or(PipelineError::MissingOrigin.crit())?;
         let l2_info =
@@ -180,3 +217,22 @@ where
         std::mem::forget(block);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_cycle_tracker_key_round_trip() {
+        let key = block_cycle_tracker_key(1234, 56);
+        assert_eq!(key, "l2-block-1234-txs-56");
+        assert_eq!(parse_block_cycle_tracker_key(&key), Some((1234, 56)));
+    }
+
+    #[test]
+    fn test_parse_block_cycle_tracker_key_ignores_other_keys() {
+        assert_eq!(parse_block_cycle_tracker_key("block-execution"), None);
+        assert_eq!(parse_block_cycle_tracker_key("l2-block-1234"), None);
+        assert_eq!(parse_block_cycle_tracker_key("l2-block-x-txs-1"), None);
+    }
+}
@@ -4,13 +4,16 @@ use alloy_consensus::Blob;
 use alloy_eips::eip4844::kzg_to_versioned_hash;
 use alloy_primitives::B256;
 use async_trait::async_trait;
-use kona_derive::{BlobProvider, BlobProviderError};
+use kona_derive::{BlobProvider, BlobProviderError, PipelineErrorKind};
 use kona_protocol::BlockInfo;
 use kzg_rs::get_kzg_settings;
+use std::{collections::HashMap, fmt};
 
+/// The blobs in the witness, keyed by versioned hash so they can be requested in any order and
+/// more than once.
 #[derive(Clone, Debug, Default)]
 pub struct BlobStore {
-    versioned_blobs: Vec<(B256, Blob)>,
+    blobs: HashMap<B256, Box<Blob>>,
 }
 
 impl From<BlobData> for BlobStore {
@@ -21,8 +24,7 @@ impl From<BlobData> for BlobStore {
             .commitments
             .iter()
             .map(|c| kzg_to_versioned_hash(c.as_slice()))
-            .zip(blobs.iter().map(|b| Blob::from(b.0)))
-            .rev()
+            .zip(blobs.iter().map(|b| Box::new(Blob::from(b.0))))
             .collect();
 
         match kzg_rs::KzgProof::verify_blob_kzg_proof_batch(
@@ -36,25 +38,97 @@ impl From<BlobData> for BlobStore {
             Err(e) => panic!("KZG proof verification error: {}", e),
         }
 
-        Self { versioned_blobs }
+        Self { blobs: versioned_blobs }
+    }
+}
+
+/// An error serving blobs from the witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BlobStoreError {
+    /// No blob in the witness has the requested versioned hash.
+    BlobNotFound(B256),
+}
+
+impl fmt::Display for BlobStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BlobNotFound(hash) => {
+                write!(f, "blob with versioned hash {hash} not found in witness")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BlobStoreError {}
+
+impl From<BlobStoreError> for PipelineErrorKind {
+    fn from(err: BlobStoreError) -> Self {
+        BlobProviderError::Backend(err.to_string()).into()
     }
 }
 
 #[async_trait]
 impl BlobProvider for BlobStore {
-    type Error = BlobProviderError;
+    type Error = BlobStoreError;
 
     async fn get_and_validate_blobs(
         &mut self,
         _: &BlockInfo,
         blob_hashes: &[B256],
     ) -> Result<Vec<Box<Blob>>, Self::Error> {
-        Ok(blob_hashes
+        blob_hashes
             .iter()
-            .filter_map(|hash| {
-                let (blob_hash, blob) = self.versioned_blobs.pop().unwrap();
-                (*hash == blob_hash).then(|| Box::new(blob))
-            })
-            .collect())
+            .map(|hash| self.blobs.get(hash).cloned().ok_or(BlobStoreError::BlobNotFound(*hash)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob_store(blobs: &[(B256, Blob)]) -> BlobStore {
+        BlobStore { blobs: blobs.iter().map(|(hash, blob)| (*hash, Box::new(*blob))).collect() }
+    }
+
+    async fn get_blobs(
+        store: &mut BlobStore,
+        blob_hashes: &[B256],
+    ) -> Result<Vec<Box<Blob>>, BlobStoreError> {
+        store.get_and_validate_blobs(&BlockInfo::default(), blob_hashes).await
+    }
+
+    #[test]
+    fn test_get_blobs_reordered() {
+        let (a, b) = (B256::repeat_byte(1), B256::repeat_byte(2));
+        let mut store = blob_store(&[(a, Blob::repeat_byte(1)), (b, Blob::repeat_byte(2))]);
+
+        let blobs = kona_proof::block_on(get_blobs(&mut store, &[b, a])).unwrap();
+        assert_eq!(*blobs[0], Blob::repeat_byte(2));
+        assert_eq!(*blobs[1], Blob::repeat_byte(1));
+    }
+
+    #[test]
+    fn test_get_blobs_duplicated() {
+        let a = B256::repeat_byte(1);
+        let mut store = blob_store(&[(a, Blob::repeat_byte(1))]);
+
+        let blobs = kona_proof::block_on(get_blobs(&mut store, &[a, a])).unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert!(blobs.iter().all(|blob| **blob == Blob::repeat_byte(1)));
+
+        // Blobs stay available to later requests.
+        let blobs = kona_proof::block_on(get_blobs(&mut store, &[a])).unwrap();
+        assert_eq!(*blobs[0], Blob::repeat_byte(1));
+    }
+
+    #[test]
+    fn test_get_blobs_missing() {
+        let mut store = blob_store(&[(B256::repeat_byte(1), Blob::repeat_byte(1))]);
+
+        assert_eq!(
+            kona_proof::block_on(get_blobs(&mut store, &[B256::repeat_byte(2)])),
+            Err(BlobStoreError::BlobNotFound(B256::repeat_byte(2)))
+        );
     }
 }
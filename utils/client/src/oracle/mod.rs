@@ -1,2 +1,2 @@
 mod blob_provider;
-pub use blob_provider::BlobStore;
+pub use blob_provider::{BlobStore, BlobStoreError};
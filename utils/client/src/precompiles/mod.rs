@@ -19,7 +19,7 @@ pub use custom::CustomCrypto;
 mod factory;
 pub use factory::ZkvmOpEvmFactory;
 
-/// Tracker names for accelerated precompiles.
+/// Tracker names for precompiles.
 /// These names are used in cycle-tracker-report events and must match
 /// the keys expected by stats.rs and validity/src/types.rs.
 pub mod cycle_tracker {
@@ -34,6 +34,40 @@ pub mod cycle_tracker {
         pub const EC_RECOVER: &str = "ec-recover";
         pub const P256_VERIFY: &str = "p256-verify";
         pub const KZG_EVAL: &str = "kzg-eval";
+        pub const SHA256: &str = "sha256";
+        pub const RIPEMD160: &str = "ripemd160";
+        pub const IDENTITY: &str = "identity";
+        pub const MODEXP: &str = "modexp";
+        pub const BLAKE2F: &str = "blake2f";
+        pub const BLS12_G1_ADD: &str = "bls12-g1-add";
+        pub const BLS12_G1_MSM: &str = "bls12-g1-msm";
+        pub const BLS12_G2_ADD: &str = "bls12-g2-add";
+        pub const BLS12_G2_MSM: &str = "bls12-g2-msm";
+        pub const BLS12_PAIRING: &str = "bls12-pairing";
+        pub const BLS12_MAP_FP_TO_G1: &str = "bls12-map-fp-to-g1";
+        pub const BLS12_MAP_FP2_TO_G2: &str = "bls12-map-fp2-to-g2";
+
+        /// Every tracker name, in the same order as [`super::keys::ALL`].
+        pub const ALL: &[&str] = &[
+            BN_ADD,
+            BN_MUL,
+            BN_PAIR,
+            EC_RECOVER,
+            P256_VERIFY,
+            KZG_EVAL,
+            SHA256,
+            RIPEMD160,
+            IDENTITY,
+            MODEXP,
+            BLAKE2F,
+            BLS12_G1_ADD,
+            BLS12_G1_MSM,
+            BLS12_G2_ADD,
+            BLS12_G2_MSM,
+            BLS12_PAIRING,
+            BLS12_MAP_FP_TO_G1,
+            BLS12_MAP_FP2_TO_G2,
+        ];
     }
 
     /// Full cycle tracker keys (with "precompile-" prefix).
@@ -45,11 +79,45 @@ pub mod cycle_tracker {
         pub const EC_RECOVER: &str = "precompile-ec-recover";
         pub const P256_VERIFY: &str = "precompile-p256-verify";
         pub const KZG_EVAL: &str = "precompile-kzg-eval";
+        pub const SHA256: &str = "precompile-sha256";
+        pub const RIPEMD160: &str = "precompile-ripemd160";
+        pub const IDENTITY: &str = "precompile-identity";
+        pub const MODEXP: &str = "precompile-modexp";
+        pub const BLAKE2F: &str = "precompile-blake2f";
+        pub const BLS12_G1_ADD: &str = "precompile-bls12-g1-add";
+        pub const BLS12_G1_MSM: &str = "precompile-bls12-g1-msm";
+        pub const BLS12_G2_ADD: &str = "precompile-bls12-g2-add";
+        pub const BLS12_G2_MSM: &str = "precompile-bls12-g2-msm";
+        pub const BLS12_PAIRING: &str = "precompile-bls12-pairing";
+        pub const BLS12_MAP_FP_TO_G1: &str = "precompile-bls12-map-fp-to-g1";
+        pub const BLS12_MAP_FP2_TO_G2: &str = "precompile-bls12-map-fp2-to-g2";
+
+        /// Every tracker key, in the same order as [`super::names::ALL`].
+        pub const ALL: &[&str] = &[
+            BN_ADD,
+            BN_MUL,
+            BN_PAIR,
+            EC_RECOVER,
+            P256_VERIFY,
+            KZG_EVAL,
+            SHA256,
+            RIPEMD160,
+            IDENTITY,
+            MODEXP,
+            BLAKE2F,
+            BLS12_G1_ADD,
+            BLS12_G1_MSM,
+            BLS12_G2_ADD,
+            BLS12_G2_MSM,
+            BLS12_PAIRING,
+            BLS12_MAP_FP_TO_G1,
+            BLS12_MAP_FP2_TO_G2,
+        ];
     }
 }
 
 /// Get the cycle tracker name for a precompile by its ID.
-/// Returns None if the precompile is not accelerated/tracked.
+/// Returns None only for custom precompiles, which the OP Stack doesn't define.
 #[cfg(any(test, target_os = "zkvm"))]
 #[inline]
 fn get_precompile_tracker_name(id: &PrecompileId) -> Option<&'static str> {
@@ -60,6 +128,18 @@ fn get_precompile_tracker_name(id: &PrecompileId) -> Option<&'static str> {
         PrecompileId::EcRec => Some(cycle_tracker::names::EC_RECOVER),
         PrecompileId::P256Verify => Some(cycle_tracker::names::P256_VERIFY),
         PrecompileId::KzgPointEvaluation => Some(cycle_tracker::names::KZG_EVAL),
+        PrecompileId::Sha256 => Some(cycle_tracker::names::SHA256),
+        PrecompileId::Ripemd160 => Some(cycle_tracker::names::RIPEMD160),
+        PrecompileId::Identity => Some(cycle_tracker::names::IDENTITY),
+        PrecompileId::ModExp => Some(cycle_tracker::names::MODEXP),
+        PrecompileId::Blake2F => Some(cycle_tracker::names::BLAKE2F),
+        PrecompileId::Bls12G1Add => Some(cycle_tracker::names::BLS12_G1_ADD),
+        PrecompileId::Bls12G1Msm => Some(cycle_tracker::names::BLS12_G1_MSM),
+        PrecompileId::Bls12G2Add => Some(cycle_tracker::names::BLS12_G2_ADD),
+        PrecompileId::Bls12G2Msm => Some(cycle_tracker::names::BLS12_G2_MSM),
+        PrecompileId::Bls12Pairing => Some(cycle_tracker::names::BLS12_PAIRING),
+        PrecompileId::Bls12MapFpToGp1 => Some(cycle_tracker::names::BLS12_MAP_FP_TO_G1),
+        PrecompileId::Bls12MapFp2ToGp2 => Some(cycle_tracker::names::BLS12_MAP_FP2_TO_G2),
         _ => None,
     }
 }
@@ -122,7 +202,7 @@ where
             output: Bytes::new(),
         };
 
-        // Track cycles for accelerated precompiles. zkVM acceleration comes
+        // Track cycles and calls for every precompile. zkVM acceleration comes
         // from patched crypto crates ([patch.crates-io] in workspace
         // Cargo.toml); the wrapper only adds cycle-tracker prints.
         #[cfg(target_os = "zkvm")]
@@ -457,10 +537,44 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_precompile_returns_none() {
-        // SHA256 is a precompile but not accelerated/tracked
-        assert_eq!(get_precompile_tracker_name(&PrecompileId::Sha256), None);
-        assert_eq!(get_precompile_tracker_name(&PrecompileId::Identity), None);
+    fn test_precompile_tracker_name_bls12() {
+        assert_eq!(
+            get_precompile_tracker_name(&PrecompileId::Bls12G1Msm),
+            Some(cycle_tracker::names::BLS12_G1_MSM)
+        );
+        assert_eq!(
+            get_precompile_tracker_name(&PrecompileId::Bls12Pairing),
+            Some(cycle_tracker::names::BLS12_PAIRING)
+        );
+        assert_eq!(
+            get_precompile_tracker_name(&PrecompileId::Bls12MapFp2ToGp2),
+            Some(cycle_tracker::names::BLS12_MAP_FP2_TO_G2)
+        );
+    }
+
+    #[test]
+    fn test_custom_precompile_returns_none() {
+        assert_eq!(get_precompile_tracker_name(&PrecompileId::Custom("custom".into())), None);
+    }
+
+    #[test]
+    fn test_every_canonical_precompile_has_tracker_name() {
+        for spec in ALL_OP_SPECS {
+            let op_precompiles = OpPrecompiles::new_with_spec(spec);
+            let addresses: Vec<_> =
+                <OpPrecompiles as PrecompileProvider<TestContext>>::warm_addresses(&op_precompiles)
+                    .collect();
+
+            for address in addresses {
+                let precompile = op_precompiles.precompiles().get(&address).unwrap();
+                let name = get_precompile_tracker_name(precompile.id());
+                assert!(
+                    name.is_some_and(|name| cycle_tracker::names::ALL.contains(&name)),
+                    "Canonical OP precompile {:?} at {address:?} for {spec:?} is not tracked",
+                    precompile.id(),
+                );
+            }
+        }
     }
 
     // ===== Consistency Tests =====
@@ -663,16 +777,7 @@ mod tests {
 
     #[test]
     fn test_tracker_keys_match_expected_format() {
-        let expected_keys = [
-            cycle_tracker::keys::BN_ADD,
-            cycle_tracker::keys::BN_MUL,
-            cycle_tracker::keys::BN_PAIR,
-            cycle_tracker::keys::EC_RECOVER,
-            cycle_tracker::keys::P256_VERIFY,
-            cycle_tracker::keys::KZG_EVAL,
-        ];
-
-        for key in &expected_keys {
+        for key in cycle_tracker::keys::ALL {
             assert!(
                 key.starts_with(cycle_tracker::PREFIX),
                 "Key '{}' should start with '{}'",
@@ -714,5 +819,10 @@ mod tests {
             cycle_tracker::keys::KZG_EVAL,
             format!("{}{}", cycle_tracker::PREFIX, cycle_tracker::names::KZG_EVAL)
         );
+
+        assert_eq!(cycle_tracker::keys::ALL.len(), cycle_tracker::names::ALL.len());
+        for (key, name) in cycle_tracker::keys::ALL.iter().zip(cycle_tracker::names::ALL) {
+            assert_eq!(*key, format!("{}{}", cycle_tracker::PREFIX, name));
+        }
     }
 }
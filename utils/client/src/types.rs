@@ -2,11 +2,14 @@ use alloy_primitives::{Address, B256};
 use alloy_sol_types::sol;
 use serde::{Deserialize, Serialize};
 
-use crate::boot::BootInfoStruct;
+use crate::boot::{BootInfoStruct, ExtendedRangeOutputs};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AggregationInputs {
     pub boot_infos: Vec<BootInfoStruct>,
+    /// The extended outputs committed after each boot info, for range proofs from the extended
+    /// range ELF.
+    pub extended_outputs: Vec<Option<ExtendedRangeOutputs>>,
     pub latest_l1_checkpoint_head: B256,
     pub multi_block_vkey: [u32; 8],
     pub prover_address: Address,
@@ -0,0 +1,100 @@
+//! Optional compression of the rkyv-serialized witness.
+//!
+//! The host writes the [WitnessCompression] to stdin ahead of the (possibly compressed) witness,
+//! and the range program decompresses the witness before reading the archive. The compressed
+//! witness is what gets uploaded to the prover network or cluster artifact store and written to
+//! the witness cache.
+
+use std::{fmt, io::Read, str::FromStr};
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The codec the witness is compressed with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WitnessCompression {
+    /// The witness is sent uncompressed and read in place.
+    #[default]
+    None,
+    /// The smallest witness, at the cost of more decompression cycles than lz4.
+    Zstd,
+    /// A larger witness than zstd, but cheaper to decompress.
+    Lz4,
+}
+
+impl WitnessCompression {
+    /// Decompress a witness compressed with this codec. An uncompressed witness is returned as is.
+    pub fn decompress(self, bytes: Vec<u8>) -> Result<Vec<u8>> {
+        match self {
+            Self::None => Ok(bytes),
+            Self::Zstd => {
+                let mut decoder = ruzstd::decoding::StreamingDecoder::new(bytes.as_slice())
+                    .map_err(|e| anyhow!("Failed to read zstd witness frame header: {e}"))?;
+                let mut witness = Vec::with_capacity(decoder.inner().content_size() as usize);
+                decoder.read_to_end(&mut witness)?;
+                Ok(witness)
+            }
+            Self::Lz4 => lz4_flex::decompress_size_prepended(&bytes)
+                .map_err(|e| anyhow!("Failed to decompress lz4 witness: {e}")),
+        }
+    }
+}
+
+impl fmt::Display for WitnessCompression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Self::None => "none",
+            Self::Zstd => "zstd",
+            Self::Lz4 => "lz4",
+        })
+    }
+}
+
+impl FromStr for WitnessCompression {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "zstd" => Ok(Self::Zstd),
+            "lz4" => Ok(Self::Lz4),
+            _ => bail!("Unknown witness compression {s:?}, expected one of none, zstd, lz4"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_witness_compression_from_str() {
+        for compression in
+            [WitnessCompression::None, WitnessCompression::Zstd, WitnessCompression::Lz4]
+        {
+            assert_eq!(compression.to_string().parse::<WitnessCompression>().unwrap(), compression);
+        }
+        assert_eq!("ZSTD".parse::<WitnessCompression>().unwrap(), WitnessCompression::Zstd);
+        assert!("gzip".parse::<WitnessCompression>().is_err());
+    }
+
+    #[test]
+    fn test_zstd_decompress() {
+        // Compressed on the host with the zstd library, decompressed as in the range program.
+        let witness: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+        let compressed = zstd::bulk::compress(&witness, 3).unwrap();
+        assert!(compressed.len() < witness.len());
+        assert_eq!(WitnessCompression::Zstd.decompress(compressed).unwrap(), witness);
+        assert!(WitnessCompression::Zstd.decompress(vec![0x28, 0xb5, 0x2f]).is_err());
+    }
+
+    #[test]
+    fn test_lz4_decompress() {
+        let witness = vec![7u8; 4096];
+        let compressed = lz4_flex::compress_prepend_size(&witness);
+        assert_eq!(WitnessCompression::Lz4.decompress(compressed).unwrap(), witness);
+        // A 16 byte witness with a truncated block.
+        assert!(WitnessCompression::Lz4.decompress(vec![16, 0, 0, 0, 0xf0]).is_err());
+    }
+}
@@ -111,14 +111,15 @@ pub trait WitnessExecutor {
     ) -> Result<OraclePipeline<Self::O, Self::L1, Self::L2, Self::DA>>;
 
     // Sourced from https://github.com/op-rs/kona/tree/main/bin/client/src/single.rs
-    // Runs the OP Succinct witness executor using the given derivation pipeline,
+    // Runs the OP Succinct witness executor using the given derivation pipeline. Returns the boot
+    // info and the number of transactions in the executed blocks.
     async fn run<O, DP, P>(
         &self,
         boot: BootInfo,
         pipeline: DP,
         cursor: Arc<RwLock<PipelineCursor>>,
         l2_provider: OracleL2ChainProvider<O>,
-    ) -> Result<BootInfo>
+    ) -> Result<(BootInfo, u64)>
     where
         O: CommsClient + FlushableCache + Send + Sync + Debug,
         DP: DriverPipeline<P> + Send + Sync + Debug,
@@ -145,7 +146,7 @@ pub trait WitnessExecutor {
         // Use custom advance to target with cycle tracking.
         #[cfg(target_os = "zkvm")]
         println!("cycle-tracker-report-start: block-execution-and-derivation");
-        let (safe_head, output_root) = advance_to_target(
+        let (safe_head, output_root, transaction_count) = advance_to_target(
             &mut driver,
             rollup_config.as_ref(),
             Some(boot.claimed_l2_block_number),
@@ -180,6 +181,6 @@ pub trait WitnessExecutor {
             std::mem::forget(rollup_config);
         }
 
-        Ok(boot_clone)
+        Ok((boot_clone, transaction_count))
     }
 }
@@ -1,5 +1,7 @@
+pub mod compression;
 pub mod executor;
 pub mod preimage_store;
+pub mod zero_copy;
 
 use std::{fmt::Debug, sync::Arc};
 
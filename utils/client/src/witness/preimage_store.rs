@@ -83,3 +83,22 @@ impl PreimageOracleClient for PreimageStore {
 impl FlushableCache for PreimageStore {
     fn flush(&self) {}
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keccak_key(value: &[u8]) -> PreimageKey {
+        PreimageKey::new(keccak256(value).0, PreimageKeyType::Keccak256)
+    }
+
+    #[test]
+    fn test_check_preimages_rejects_invalid_preimage() {
+        let mut store = PreimageStore::default();
+        store.save_preimage(keccak_key(b"valid"), b"valid".to_vec()).unwrap();
+        assert!(store.check_preimages().is_ok());
+
+        store.preimage_map.insert(keccak_key(b"value"), b"tampered".to_vec());
+        assert!(matches!(store.check_preimages(), Err(PreimageOracleError::InvalidPreimageKey)));
+    }
+}
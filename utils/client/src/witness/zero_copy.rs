@@ -0,0 +1,278 @@
+//! Reading witness preimages in place from the rkyv archive.
+//!
+//! Deserializing the witness with `rkyv::from_bytes` allocates an owned copy of every preimage,
+//! doubling the guest memory spent on the witness before the program has read a single preimage.
+//! [`ZeroCopyPreimageStore`] instead validates the archive once and serves each preimage straight
+//! from the archived bytes, copying a preimage only when the oracle hands it out.
+
+use std::{collections::HashMap, fmt::Debug, ops::Range, sync::Arc};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use kona_preimage::{
+    errors::{PreimageOracleError, PreimageOracleResult},
+    HintWriterClient, PreimageKey, PreimageOracleClient,
+};
+use kona_proof::FlushableCache;
+use rkyv::{
+    api::high::HighValidator, bytecheck::CheckBytes, rancor::Error, util::AlignedVec, Portable,
+};
+
+use super::{
+    preimage_store::{check_preimage, ArchivedPreimageStore},
+    ArchivedBlobData, ArchivedDefaultWitnessData, ArchivedEigenDAWitnessData, BlobData,
+    DefaultWitnessData, EigenDAWitnessData,
+};
+use crate::BlobStore;
+
+/// Witness data that can be read in place from its rkyv archive.
+pub trait ZeroCopyWitnessData: rkyv::Archive {
+    /// Witness data besides the preimages and blobs, copied out of the archive.
+    type Extra;
+
+    /// The archived preimage store and blob data of the archived witness.
+    fn archived_parts(archived: &Self::Archived) -> (&ArchivedPreimageStore, &ArchivedBlobData);
+
+    /// Copies the rest of the witness data out of the archived witness.
+    fn archived_extra(archived: &Self::Archived) -> Result<Self::Extra>;
+
+    /// Gets the oracle and blob provider from the rkyv-serialized witness without deserializing
+    /// the preimages. The archive and the preimages are validated up front, as in
+    /// [`super::WitnessData::get_oracle_and_blob_provider`].
+    fn get_zero_copy_oracle_and_blob_provider(
+        witness_bytes: Vec<u8>,
+    ) -> Result<(Arc<ZeroCopyPreimageStore>, BlobStore, Self::Extra)>
+    where
+        Self::Archived: Portable + for<'a> CheckBytes<HighValidator<'a, Error>>,
+    {
+        let witness = WitnessBuffer::new(witness_bytes);
+
+        let (index, blob_data, extra) = {
+            let archived = rkyv::access::<Self::Archived, Error>(&witness)?;
+            let (preimage_store, blob_data) = Self::archived_parts(archived);
+            (
+                index_preimages(&witness, preimage_store)?,
+                rkyv::deserialize::<BlobData, Error>(blob_data)?,
+                Self::archived_extra(archived)?,
+            )
+        };
+
+        // Moving the buffer doesn't move its heap allocation, so the indexed offsets stay valid.
+        let oracle = Arc::new(ZeroCopyPreimageStore { witness, index });
+
+        println!("cycle-tracker-report-start: oracle-verify");
+        // Check the preimages in the witness are valid.
+        oracle.check_preimages().expect("Failed to validate preimages");
+        println!("cycle-tracker-report-end: oracle-verify");
+
+        // Create a BlobStore from the blobs in the witness and verifies them for correctness.
+        println!("cycle-tracker-report-start: blob-verification");
+        let beacon = BlobStore::from(blob_data);
+        println!("cycle-tracker-report-end: blob-verification");
+
+        Ok((oracle, beacon, extra))
+    }
+}
+
+impl ZeroCopyWitnessData for DefaultWitnessData {
+    type Extra = ();
+
+    fn archived_parts(
+        archived: &ArchivedDefaultWitnessData,
+    ) -> (&ArchivedPreimageStore, &ArchivedBlobData) {
+        (&archived.preimage_store, &archived.blob_data)
+    }
+
+    fn archived_extra(_: &ArchivedDefaultWitnessData) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl ZeroCopyWitnessData for EigenDAWitnessData {
+    /// The serialized EigenDA witness, if present.
+    type Extra = Option<Vec<u8>>;
+
+    fn archived_parts(
+        archived: &ArchivedEigenDAWitnessData,
+    ) -> (&ArchivedPreimageStore, &ArchivedBlobData) {
+        (&archived.preimage_store, &archived.blob_data)
+    }
+
+    fn archived_extra(archived: &ArchivedEigenDAWitnessData) -> Result<Option<Vec<u8>>> {
+        Ok(archived.eigenda_data.as_ref().map(|data| data.to_vec()))
+    }
+}
+
+/// The alignment rkyv requires of the archived witness.
+const WITNESS_ALIGNMENT: usize = 16;
+
+/// The witness bytes, copied into an aligned buffer only if the input isn't already aligned for
+/// rkyv access.
+enum WitnessBuffer {
+    Input(Vec<u8>),
+    Copied(AlignedVec<WITNESS_ALIGNMENT>),
+}
+
+impl WitnessBuffer {
+    fn new(bytes: Vec<u8>) -> Self {
+        if (bytes.as_ptr() as usize) % WITNESS_ALIGNMENT == 0 {
+            Self::Input(bytes)
+        } else {
+            let mut aligned = AlignedVec::<WITNESS_ALIGNMENT>::with_capacity(bytes.len());
+            aligned.extend_from_slice(&bytes);
+            Self::Copied(aligned)
+        }
+    }
+}
+
+impl std::ops::Deref for WitnessBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Input(bytes) => bytes,
+            Self::Copied(bytes) => bytes,
+        }
+    }
+}
+
+/// Maps each preimage key to the byte range of its value in `witness`. Only the keys are
+/// deserialized.
+fn index_preimages(
+    witness: &[u8],
+    preimage_store: &ArchivedPreimageStore,
+) -> Result<HashMap<PreimageKey, Range<usize>>> {
+    let base = witness.as_ptr() as usize;
+    preimage_store
+        .preimage_map
+        .iter()
+        .map(|(key, value)| {
+            let key = rkyv::deserialize::<PreimageKey, Error>(key)?;
+            let start = value.as_ptr() as usize - base;
+            Ok((key, start..start + value.len()))
+        })
+        .collect()
+}
+
+/// A preimage oracle serving preimages from the archived witness.
+pub struct ZeroCopyPreimageStore {
+    witness: WitnessBuffer,
+    index: HashMap<PreimageKey, Range<usize>>,
+}
+
+impl ZeroCopyPreimageStore {
+    /// The number of preimages in the witness.
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Whether the witness has no preimages.
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Checks that every preimage matches its key.
+    pub fn check_preimages(&self) -> PreimageOracleResult<()> {
+        for (key, range) in &self.index {
+            check_preimage(key, &self.witness[range.clone()])?;
+        }
+        Ok(())
+    }
+
+    /// Returns the archived preimage.
+    fn preimage(&self, key: &PreimageKey) -> PreimageOracleResult<&[u8]> {
+        let Some(range) = self.index.get(key) else {
+            return Err(PreimageOracleError::InvalidPreimageKey);
+        };
+        Ok(&self.witness[range.clone()])
+    }
+}
+
+impl Debug for ZeroCopyPreimageStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZeroCopyPreimageStore")
+            .field("witness_len", &self.witness.len())
+            .field("preimages", &self.index.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl HintWriterClient for ZeroCopyPreimageStore {
+    async fn write(&self, _hint: &str) -> PreimageOracleResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PreimageOracleClient for ZeroCopyPreimageStore {
+    async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
+        Ok(self.preimage(&key)?.to_vec())
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
+        buf.copy_from_slice(self.preimage(&key)?);
+        Ok(())
+    }
+}
+
+impl FlushableCache for ZeroCopyPreimageStore {
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::witness::preimage_store::PreimageStore;
+    use alloy_primitives::keccak256;
+    use kona_preimage::PreimageKeyType;
+
+    fn keccak_key(value: &[u8]) -> PreimageKey {
+        PreimageKey::new(keccak256(value).0, PreimageKeyType::Keccak256)
+    }
+
+    fn zero_copy_store(store: &PreimageStore) -> ZeroCopyPreimageStore {
+        let witness = WitnessBuffer::new(rkyv::to_bytes::<Error>(store).unwrap().to_vec());
+        let archived = rkyv::access::<ArchivedPreimageStore, Error>(&witness).unwrap();
+        let index = index_preimages(&witness, archived).unwrap();
+        ZeroCopyPreimageStore { witness, index }
+    }
+
+    #[test]
+    fn test_zero_copy_matches_owned_store() {
+        let mut store = PreimageStore::default();
+        let values = [b"a".to_vec(), b"bb".to_vec(), vec![7u8; 1024]];
+        for value in &values {
+            store.save_preimage(keccak_key(value), value.clone()).unwrap();
+        }
+
+        let zero_copy = zero_copy_store(&store);
+        assert_eq!(zero_copy.len(), values.len());
+        kona_proof::block_on(async {
+            for value in &values {
+                assert_eq!(&zero_copy.get(keccak_key(value)).await.unwrap(), value);
+
+                let mut buf = vec![0u8; value.len()];
+                zero_copy.get_exact(keccak_key(value), &mut buf).await.unwrap();
+                assert_eq!(&buf, value);
+            }
+            assert!(matches!(
+                zero_copy.get(keccak_key(b"missing")).await,
+                Err(PreimageOracleError::InvalidPreimageKey)
+            ));
+        });
+    }
+
+    #[test]
+    fn test_zero_copy_rejects_invalid_preimage() {
+        let mut store = PreimageStore::default();
+        let key = keccak_key(b"value");
+        store.preimage_map.insert(key, b"tampered".to_vec());
+
+        let zero_copy = zero_copy_store(&store);
+        assert!(matches!(
+            zero_copy.check_preimages(),
+            Err(PreimageOracleError::InvalidPreimageKey)
+        ));
+    }
+}
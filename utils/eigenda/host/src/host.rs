@@ -1,11 +1,14 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use alloy_eips::BlockId;
 use alloy_primitives::B256;
 use anyhow::Result;
 use async_trait::async_trait;
 use hokulea_host_bin::cfg::SingleChainHostWithEigenDA;
-use op_succinct_host_utils::{fetcher::OPSuccinctDataFetcher, host::OPSuccinctHost};
+use op_succinct_host_utils::{
+    fetcher::OPSuccinctDataFetcher, host::OPSuccinctHost, preimage_kv::PreimageStoreManifest,
+    witness_generation::witness_compression_from_env,
+};
 
 use crate::witness_generator::EigenDAWitnessGenerator;
 
@@ -45,6 +48,13 @@ impl OPSuccinctHost for EigenDAOPSuccinctHost {
         Ok(SingleChainHostWithEigenDA { kona_cfg: host, eigenda_proxy_address, verbose: 1 })
     }
 
+    fn replay_args(&self, store_dir: &Path) -> Result<SingleChainHostWithEigenDA> {
+        let kona_cfg = PreimageStoreManifest::load(store_dir)?.offline_host_args(store_dir)?;
+
+        // Without an EigenDA proxy, EigenDA blobs are served from the store as well.
+        Ok(SingleChainHostWithEigenDA { kona_cfg, eigenda_proxy_address: None, verbose: 1 })
+    }
+
     fn get_l1_head_hash(&self, args: &Self::Args) -> Option<B256> {
         Some(args.kona_cfg.l1_head)
     }
@@ -80,6 +90,11 @@ impl OPSuccinctHost for EigenDAOPSuccinctHost {
 
 impl EigenDAOPSuccinctHost {
     pub fn new(fetcher: Arc<OPSuccinctDataFetcher>) -> Self {
-        Self { fetcher, witness_generator: Arc::new(EigenDAWitnessGenerator {}) }
+        Self {
+            fetcher,
+            witness_generator: Arc::new(EigenDAWitnessGenerator {
+                compression: witness_compression_from_env().expect("Invalid WITNESS_COMPRESSION"),
+            }),
+        }
     }
 }
@@ -15,6 +15,7 @@ use hokulea_witgen::witness_provider::OracleEigenDAPreimageProviderWithPreimage;
 use kona_preimage::{HintWriter, NativeChannel, OracleReader};
 use kona_proof::l1::OracleBlobProvider;
 use op_succinct_client_utils::witness::{
+    compression::WitnessCompression,
     executor::{get_inputs_for_pipeline, WitnessExecutor as WitnessExecutorTrait},
     preimage_store::PreimageStore,
     BlobData, EigenDAWitnessData,
@@ -22,7 +23,7 @@ use op_succinct_client_utils::witness::{
 use op_succinct_eigenda_client_utils::executor::EigenDAWitnessExecutor;
 use op_succinct_host_utils::witness_generation::{
     online_blob_store::OnlineBlobStore, preimage_witness_collector::PreimageWitnessCollector,
-    DefaultOracleBase, WitnessGenerator,
+    write_witness, DefaultOracleBase, WitnessGenOptions, WitnessGenerator,
 };
 use rkyv::to_bytes;
 use sp1_core_executor::SP1RecursionProof;
@@ -54,7 +55,11 @@ static CANOE_VK: LazyLock<SP1VerifyingKey> = LazyLock::new(|| {
     .expect("Canoe VK setup thread panicked")
 });
 
-pub struct EigenDAWitnessGenerator {}
+#[derive(Default)]
+pub struct EigenDAWitnessGenerator {
+    /// The compression applied to the witness written to stdin.
+    pub compression: WitnessCompression,
+}
 
 #[async_trait]
 impl WitnessGenerator for EigenDAWitnessGenerator {
@@ -91,7 +96,7 @@ impl WitnessGenerator for EigenDAWitnessGenerator {
 
         // Write the witness data after the proofs
         let buffer = to_bytes::<rkyv::rancor::Error>(&witness)?;
-        stdin.write_slice(&buffer);
+        write_witness(&mut stdin, &buffer, self.compression)?;
         Ok(stdin)
     }
 
@@ -99,6 +104,7 @@ impl WitnessGenerator for EigenDAWitnessGenerator {
         &self,
         preimage_chan: NativeChannel,
         hint_chan: NativeChannel,
+        options: &WitnessGenOptions,
     ) -> Result<Self::WitnessData> {
         let preimage_witness_store = Arc::new(std::sync::Mutex::new(PreimageStore::default()));
         let blob_data = Arc::new(std::sync::Mutex::new(BlobData::default()));
@@ -110,10 +116,12 @@ impl WitnessGenerator for EigenDAWitnessGenerator {
         ));
         let blob_provider = OracleBlobProvider::new(preimage_oracle.clone());
 
-        let oracle = Arc::new(PreimageWitnessCollector {
-            preimage_oracle: preimage_oracle.clone(),
-            preimage_witness_store: preimage_witness_store.clone(),
-        });
+        let oracle = Arc::new(PreimageWitnessCollector::new(
+            preimage_oracle.clone(),
+            preimage_witness_store.clone(),
+            options.shared_cache.clone(),
+            options.max_preimage_bytes,
+        ));
         let beacon = OnlineBlobStore { provider: blob_provider.clone(), store: blob_data.clone() };
 
         // Create EigenDA blob provider that collects witness data
@@ -127,7 +135,9 @@ impl WitnessGenerator for EigenDAWitnessGenerator {
 
         let executor = EigenDAWitnessExecutor::new(eigenda_preimage_provider);
 
-        let (boot_info, input) = get_inputs_for_pipeline(oracle.clone()).await?;
+        let (boot_info, input) = get_inputs_for_pipeline(oracle.clone())
+            .await
+            .map_err(|e| oracle.map_limit_error(e.into()))?;
         if let Some((cursor, l1_provider, l2_provider)) = input {
             let rollup_config = Arc::new(boot_info.rollup_config.clone());
             let l1_config = Arc::new(boot_info.l1_config.clone());
@@ -141,9 +151,11 @@ impl WitnessGenerator for EigenDAWitnessGenerator {
                 l1_provider.clone(),
                 l2_provider.clone(),
             )
-            .await?;
+            .await
+            .map_err(|e| oracle.map_limit_error(e.into()))?;
             WitnessExecutorTrait::run(&executor, boot_info.clone(), pipeline, cursor, l2_provider)
-                .await?;
+                .await
+                .map_err(|e| oracle.map_limit_error(e.into()))?;
         }
 
         // Extract the EigenDA preimage data
@@ -15,13 +15,13 @@ fn default_witness() -> EigenDAWitnessData {
 
 #[test]
 fn test_get_sp1_stdin_with_no_eigenda_data() {
-    let generator = EigenDAWitnessGenerator {};
+    let generator = EigenDAWitnessGenerator::default();
     assert!(generator.get_sp1_stdin(default_witness()).is_ok());
 }
 
 #[test]
 fn test_get_sp1_stdin_rejects_malformed_eigenda_data() {
-    let generator = EigenDAWitnessGenerator {};
+    let generator = EigenDAWitnessGenerator::default();
     let witness = EigenDAWitnessData {
         preimage_store: PreimageStore::default(),
         blob_data: BlobData::default(),
@@ -36,7 +36,7 @@ fn test_get_sp1_stdin_rejects_malformed_eigenda_data() {
 /// This is a realistic scenario for blocks without EigenDA certs requiring validity proofs.
 #[test]
 fn test_get_sp1_stdin_with_eigenda_data_but_no_canoe_proof() {
-    let generator = EigenDAWitnessGenerator {};
+    let generator = EigenDAWitnessGenerator::default();
 
     let eigenda_witness =
         EigenDAWitness { validities: vec![], encoded_payloads: vec![], canoe_proof_bytes: None };
@@ -56,7 +56,7 @@ fn test_get_sp1_stdin_with_eigenda_data_but_no_canoe_proof() {
 /// This bypasses the first deserialization but should fail at nested proof deserialization.
 #[test]
 fn test_get_sp1_stdin_rejects_invalid_canoe_proof_bytes() {
-    let generator = EigenDAWitnessGenerator {};
+    let generator = EigenDAWitnessGenerator::default();
 
     // Create a valid EigenDAWitness with garbage in canoe_proof_bytes
     let eigenda_witness = EigenDAWitness {
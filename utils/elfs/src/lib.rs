@@ -7,3 +7,10 @@ pub const CELESTIA_RANGE_ELF_EMBEDDED: &[u8] =
     include_bytes!("../../../elf/celestia-range-elf-embedded");
 pub const EIGENDA_RANGE_ELF_EMBEDDED: &[u8] =
     include_bytes!("../../../elf/eigenda-range-elf-embedded");
+
+/// Reads an ELF from disk. ELFs loaded at runtime live for the lifetime of the process, so they
+/// are leaked to get the `'static` lifetime of the embedded ELFs.
+pub fn load_elf(path: &std::path::Path) -> std::io::Result<&'static [u8]> {
+    let elf = std::fs::read(path)?;
+    Ok(Box::leak(elf.into_boxed_slice()))
+}
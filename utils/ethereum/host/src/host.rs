@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use crate::witness_generator::ETHDAWitnessGenerator;
 use alloy_eips::BlockId;
@@ -7,7 +7,10 @@ use anyhow::Result;
 use async_trait::async_trait;
 use kona_host::single::SingleChainHost;
 use op_succinct_ethereum_client_utils::executor::ETHDAWitnessExecutor;
-use op_succinct_host_utils::{fetcher::OPSuccinctDataFetcher, host::OPSuccinctHost};
+use op_succinct_host_utils::{
+    fetcher::OPSuccinctDataFetcher, host::OPSuccinctHost, preimage_kv::PreimageStoreManifest,
+    witness_generation::witness_compression_from_env,
+};
 
 #[derive(Clone)]
 pub struct SingleChainOPSuccinctHost {
@@ -43,6 +46,10 @@ impl OPSuccinctHost for SingleChainOPSuccinctHost {
         Ok(host)
     }
 
+    fn replay_args(&self, store_dir: &Path) -> Result<SingleChainHost> {
+        PreimageStoreManifest::load(store_dir)?.offline_host_args(store_dir)
+    }
+
     fn get_l1_head_hash(&self, args: &Self::Args) -> Option<B256> {
         Some(args.l1_head)
     }
@@ -84,6 +91,7 @@ impl SingleChainOPSuccinctHost {
             fetcher,
             witness_generator: Arc::new(ETHDAWitnessGenerator {
                 executor: ETHDAWitnessExecutor::new(),
+                compression: witness_compression_from_env().expect("Invalid WITNESS_COMPRESSION"),
             }),
         }
     }
@@ -1,11 +1,11 @@
 use anyhow::Result;
 use async_trait::async_trait;
 use kona_proof::l1::OracleBlobProvider;
-use op_succinct_client_utils::witness::DefaultWitnessData;
+use op_succinct_client_utils::witness::{compression::WitnessCompression, DefaultWitnessData};
 use op_succinct_ethereum_client_utils::executor::ETHDAWitnessExecutor;
 use op_succinct_host_utils::witness_generation::{
     online_blob_store::OnlineBlobStore, preimage_witness_collector::PreimageWitnessCollector,
-    DefaultOracleBase, WitnessGenerator,
+    write_witness, DefaultOracleBase, WitnessGenerator,
 };
 use rkyv::to_bytes;
 use sp1_sdk::SP1Stdin;
@@ -17,6 +17,8 @@ type WitnessExecutor = ETHDAWitnessExecutor<
 
 pub struct ETHDAWitnessGenerator {
     pub executor: WitnessExecutor,
+    /// The compression applied to the witness written to stdin.
+    pub compression: WitnessCompression,
 }
 
 #[async_trait]
@@ -31,7 +33,7 @@ impl WitnessGenerator for ETHDAWitnessGenerator {
     fn get_sp1_stdin(&self, witness: Self::WitnessData) -> Result<SP1Stdin> {
         let mut stdin = SP1Stdin::default();
         let buffer = to_bytes::<rkyv::rancor::Error>(&witness)?;
-        stdin.write_slice(&buffer);
+        write_witness(&mut stdin, &buffer, self.compression)?;
         Ok(stdin)
     }
 }
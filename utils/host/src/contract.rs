@@ -12,10 +12,14 @@ sol! {
             bytes32 rollupConfigHash;
         }
 
+        event OutputProposed(bytes32 indexed outputRoot, uint256 indexed l2OutputIndex, uint256 indexed l2BlockNumber, uint256 l1Timestamp);
+
         mapping(bytes32 => OpSuccinctConfig) public opSuccinctConfigs;
 
         uint256 public submissionInterval;
 
+        uint256 public startingTimestamp;
+
         function latestBlockNumber() public view returns (uint256);
 
         function historicBlockHashes(uint256 _blockNumber) external view returns (bytes32);
@@ -6,7 +6,6 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use crate::rpc_types::{OutputResponse, SafeHeadResponse};
 use alloy_consensus::{BlockHeader, Header};
 use alloy_eips::{BlockId, BlockNumberOrTag};
 use alloy_primitives::{address, keccak256, Address, Bytes, B256, U256, U64};
@@ -26,7 +25,12 @@ use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use crate::L2Output;
+use crate::{
+    preimage_kv::{get_preimage_store_dir, preimage_store_base_dir, PreimageStoreManifest},
+    quorum::{parse_endpoints, QuorumPolicy},
+    rpc_types::{OutputResponse, SafeHeadResponse},
+    L2Output,
+};
 
 /// L2ToL1MessagePasser predeploy address (OP Stack).
 const L2_TO_L1_MESSAGE_PASSER: Address = address!("0x4200000000000000000000000000000000000016");
@@ -117,6 +121,10 @@ pub struct RPCConfig {
     pub l2_rpc: Url,
     // TODO(fakedev9999): Make optional if possible.
     pub l2_node_rpc: Url,
+    /// L2 nodes that have to agree with `l2_node_rpc` on output roots.
+    pub l2_node_quorum_rpcs: Vec<Url>,
+    /// How many L2 nodes have to agree on an output root.
+    pub output_root_quorum: QuorumPolicy,
 }
 
 /// The mode corresponding to the chain we are fetching data for.
@@ -134,6 +142,9 @@ pub enum RPCMode {
 /// L1_BEACON_RPC: The L1 beacon RPC URL.
 /// L2_RPC: The L2 RPC URL.
 /// L2_NODE_RPC: The L2 node RPC URL.
+/// L2_QUORUM_RPCS: Comma-separated L2 node RPC URLs that have to agree with L2_NODE_RPC on output
+/// roots.
+/// OUTPUT_ROOT_QUORUM: `all` (default) or the number of L2 nodes that have to agree.
 pub fn get_rpcs_from_env() -> RPCConfig {
     let l1_rpc = env::var("L1_RPC").expect("L1_RPC must be set");
     let maybe_l1_beacon_rpc = env::var("L1_BEACON_RPC").ok();
@@ -147,12 +158,23 @@ pub fn get_rpcs_from_env() -> RPCConfig {
 
     let l2_rpc = env::var("L2_RPC").expect("L2_RPC must be set");
     let l2_node_rpc = env::var("L2_NODE_RPC").expect("L2_NODE_RPC must be set");
+    let l2_node_quorum_rpcs = parse_endpoints(&env::var("L2_QUORUM_RPCS").unwrap_or_default())
+        .expect("L2_QUORUM_RPCS must be comma-separated URLs");
+    let output_root_quorum: QuorumPolicy = env::var("OUTPUT_ROOT_QUORUM")
+        .map(|quorum| quorum.parse().expect("OUTPUT_ROOT_QUORUM must be `all` or a number"))
+        .unwrap_or_default();
+    // The quorum includes the primary L2_NODE_RPC.
+    output_root_quorum
+        .validate(l2_node_quorum_rpcs.len() + 1)
+        .expect("OUTPUT_ROOT_QUORUM must not exceed the number of L2 nodes");
 
     RPCConfig {
         l1_rpc: Url::parse(&l1_rpc).expect("L1_RPC must be a valid URL"),
         l1_beacon_rpc,
         l2_rpc: Url::parse(&l2_rpc).expect("L2_RPC must be a valid URL"),
         l2_node_rpc: Url::parse(&l2_node_rpc).expect("L2_NODE_RPC must be a valid URL"),
+        l2_node_quorum_rpcs,
+        output_root_quorum,
     }
 }
 
@@ -715,15 +737,36 @@ impl OPSuccinctDataFetcher {
         Ok(headers)
     }
 
+    /// Get the output at `block_number` from the L2 node. If quorum nodes are configured, enough
+    /// of the L2 nodes have to agree on the output root.
     pub async fn get_l2_output_at_block(&self, block_number: u64) -> Result<OutputResponse> {
         let block_number_hex = format!("0x{block_number:x}");
-        let l2_output_data: OutputResponse = self
-            .fetch_rpc_data_with_mode(
-                RPCMode::L2Node,
-                "optimism_outputAtBlock",
-                vec![block_number_hex.into()],
-            )
-            .await?;
+        let params: Vec<Value> = vec![block_number_hex.into()];
+        if self.rpc_config.l2_node_quorum_rpcs.is_empty() {
+            let l2_output_data: OutputResponse = self
+                .fetch_rpc_data_with_mode(RPCMode::L2Node, "optimism_outputAtBlock", params)
+                .await?;
+            return Ok(l2_output_data);
+        }
+
+        let urls = std::iter::once(&self.rpc_config.l2_node_rpc)
+            .chain(&self.rpc_config.l2_node_quorum_rpcs);
+        let responses = futures::future::join_all(urls.map(|url| {
+            let params = params.clone();
+            async move {
+                let output =
+                    Self::fetch_rpc_data::<OutputResponse>(url, "optimism_outputAtBlock", params)
+                        .await;
+                (url.to_string(), output)
+            }
+        }))
+        .await;
+
+        let l2_output_data = self
+            .rpc_config
+            .output_root_quorum
+            .reach(responses, |output| output.output_root)
+            .with_context(|| format!("L2 nodes disagree on the output at block {block_number}"))?;
         Ok(l2_output_data)
     }
 
@@ -945,7 +988,18 @@ impl OPSuccinctDataFetcher {
             .as_ref()
             .map(|addr| addr.as_str().trim_end_matches('/').to_string());
 
-        Ok(SingleChainHost {
+        // Back the host with an on-disk key-value store if configured, so the range can be
+        // replayed.
+        let data_dir = preimage_store_base_dir().map(|base_dir| {
+            get_preimage_store_dir(
+                &base_dir,
+                rollup_config.l2_chain_id.id(),
+                l2_start_block,
+                l2_end_block,
+            )
+        });
+
+        let host = SingleChainHost {
             l1_head: l1_head_hash,
             agreed_l2_output_root,
             agreed_l2_head_hash,
@@ -960,13 +1014,24 @@ impl OPSuccinctDataFetcher {
                 self.rpc_config.l1_rpc.as_str().trim_end_matches('/').to_string(),
             ),
             l1_beacon_address,
-            data_dir: None, // Use in-memory key-value store.
+            // Use an in-memory key-value store unless `PREIMAGE_STORE_DIR` is set.
+            data_dir,
             native: false,
             server: true,
             rollup_config_path: self.rollup_config_path.clone(),
             l1_config_path: self.l1_config_path.clone(),
             enable_experimental_witness_endpoint: false,
-        })
+        };
+
+        if let Some(data_dir) = &host.data_dir {
+            let l1_config_path =
+                self.l1_config_path.as_ref().ok_or_else(|| anyhow!("L1 config not loaded."))?;
+            let l1_config = serde_json::from_slice(&fs::read(l1_config_path)?)?;
+            PreimageStoreManifest::from_host_args(&host, l2_start_block, rollup_config, l1_config)
+                .save(data_dir)?;
+        }
+
+        Ok(host)
     }
 }
 
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use alloy_primitives::B256;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -5,9 +7,21 @@ use hana_host::celestia::CelestiaChainHost;
 use hokulea_host_bin::cfg::SingleChainHostWithEigenDA;
 use kona_host::single::{SingleChainHost, SingleChainHostError};
 use kona_preimage::{BidirectionalChannel, Channel};
-use tokio::task::JoinHandle;
+use tokio::task::{AbortHandle, JoinHandle};
+
+use crate::{
+    fetcher::OPSuccinctDataFetcher,
+    witness_generation::{WitnessGenOptions, WitnessGenerationError, WitnessGenerator},
+};
+
+/// Aborts the task when dropped.
+struct AbortOnDrop(AbortHandle);
 
-use crate::{fetcher::OPSuccinctDataFetcher, witness_generation::WitnessGenerator};
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
 
 #[async_trait]
 pub trait PreimageServerStarter {
@@ -91,18 +105,60 @@ pub trait OPSuccinctHost: Send + Sync + 'static {
     async fn run(
         &self,
         args: &Self::Args,
+    ) -> Result<<Self::WitnessGenerator as WitnessGenerator>::WitnessData> {
+        self.run_with_options(args, &WitnessGenOptions::default()).await
+    }
+
+    /// Run the host and client program with the given [WitnessGenOptions].
+    ///
+    /// Preimages in the shared cache are served without fetching them, and the preimages fetched
+    /// are added to it. The witness holds every preimage the program reads, whether or not it
+    /// came from the shared cache. The run fails with a [WitnessGenerationError] if it exceeds its
+    /// timeout or preimage limit. Dropping the returned future cancels the run.
+    ///
+    /// Returns the witness which can be supplied to the zkVM.
+    async fn run_with_options(
+        &self,
+        args: &Self::Args,
+        options: &WitnessGenOptions,
     ) -> Result<<Self::WitnessGenerator as WitnessGenerator>::WitnessData> {
         let preimage = BidirectionalChannel::new()?;
         let hint = BidirectionalChannel::new()?;
 
+        // Unlike the upstream, abort the server task instead of waiting for it, as it will hang
+        // if you wait for both tasks to complete. The guard also aborts it if the run fails, times
+        // out or is dropped.
         let server_task = args.start_server(hint.host, preimage.host).await?;
+        let _server_guard = AbortOnDrop(server_task.abort_handle());
 
-        let witness = self.witness_generator().run(preimage.client, hint.client).await?;
-        // Unlike the upstream, manually abort the server task, as it will hang if you wait for both
-        // tasks to complete.
-        server_task.abort();
+        let timed_out = async {
+            match options.timeout {
+                Some(timeout) => {
+                    tokio::time::sleep(timeout).await;
+                    timeout
+                }
+                None => std::future::pending().await,
+            }
+        };
 
-        Ok(witness)
+        tokio::select! {
+            witness = self.witness_generator().run(preimage.client, hint.client, options) => witness,
+            timeout = timed_out => Err(WitnessGenerationError::Timeout(timeout).into()),
+        }
+    }
+
+    /// The host arguments replaying a range from its on-disk preimage store (see
+    /// [`crate::preimage_kv`]). Running the host with them serves preimages only from the store,
+    /// without any RPC.
+    fn replay_args(&self, store_dir: &Path) -> Result<Self::Args>;
+
+    /// Replay the witness generation of a range from its on-disk preimage store, without any RPC.
+    async fn replay(
+        &self,
+        store_dir: &Path,
+    ) -> Result<<Self::WitnessGenerator as WitnessGenerator>::WitnessData> {
+        let args = self.replay_args(store_dir)?;
+        self.run(&args).await
     }
 
     /// Get the L1 head hash from the host args.
@@ -0,0 +1,392 @@
+//! A per-game ledger of the funds a proposer or challenger puts in and gets out.
+//!
+//! Each entry records the bond posted for a game, the L1 gas spent on the transactions sent for
+//! it, the estimated cost of proving it, and the credit finally claimed, so that the profit or
+//! loss of each game can be read off without reconstructing it from a block explorer. The ledger
+//! is persisted as JSON, with a CSV export written alongside, by a background writer so that
+//! recording stays off the hot path.
+
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use alloy_primitives::{utils::format_ether, B256, I256, U256};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tempfile::NamedTempFile;
+use tokio::sync::Notify;
+
+/// The purpose of a transaction sent for a ledger entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedgerTxKind {
+    Create,
+    Challenge,
+    Prove,
+    Resolve,
+    Claim,
+    /// A validity proposer relaying an aggregation proof.
+    Relay,
+}
+
+/// A transaction sent for a ledger entry. Reverted transactions are recorded too, since they
+/// still cost gas.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerTx {
+    pub kind: LedgerTxKind,
+    pub tx_hash: B256,
+    pub gas_used: u64,
+    /// The gas used times the effective gas price.
+    pub gas_cost_wei: U256,
+}
+
+/// The funds put into and taken out of one game, or one proposal of a validity proposer.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// The game address, or another identifier of what the funds were spent on.
+    pub id: String,
+    /// The bond posted when creating or challenging the game.
+    pub bond_posted_wei: U256,
+    /// The credit claimed from the game once it was finalized.
+    pub credit_claimed_wei: U256,
+    pub txs: Vec<LedgerTx>,
+    /// The SP1 gas of the proofs generated for the game, where the prover reports it.
+    pub sp1_gas: u64,
+    /// The SP1 gas times the price per PGU the proofs were fulfilled at, in PROVE wei.
+    pub proof_cost_prove_wei: U256,
+    /// The part of `sp1_gas` of proofs the prover didn't report a price for, so whose cost is
+    /// missing from `proof_cost_prove_wei`.
+    #[serde(default)]
+    pub unpriced_sp1_gas: u64,
+}
+
+impl LedgerEntry {
+    /// The L1 gas spent on the game's transactions.
+    pub fn gas_spent_wei(&self) -> U256 {
+        self.txs.iter().map(|tx| tx.gas_cost_wei).sum()
+    }
+
+    /// The ETH profit (or loss, if negative) of the game: the credit claimed less the bond
+    /// posted and gas spent. Proving is paid in PROVE and not included.
+    pub fn net_wei(&self) -> I256 {
+        I256::from_raw(self.credit_claimed_wei) -
+            I256::from_raw(self.bond_posted_wei) -
+            I256::from_raw(self.gas_spent_wei())
+    }
+}
+
+/// The sums over all ledger entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LedgerTotals {
+    pub bond_posted_wei: U256,
+    pub credit_claimed_wei: U256,
+    pub gas_spent_wei: U256,
+    pub proof_cost_prove_wei: U256,
+    pub net_wei: I256,
+}
+
+impl LedgerTotals {
+    /// The net profit or loss in ETH, for metrics.
+    pub fn net_eth(&self) -> f64 {
+        let abs = wei_to_eth(self.net_wei.unsigned_abs());
+        if self.net_wei.is_negative() {
+            -abs
+        } else {
+            abs
+        }
+    }
+}
+
+/// `wei` in ETH (or PROVE wei in PROVE), for metrics.
+pub fn wei_to_eth(wei: U256) -> f64 {
+    u128::try_from(wei).map_or(f64::MAX, |wei| wei as f64 / 1e18)
+}
+
+/// The ledger entries, shared between clones. If `path` is set, changes are saved there by the
+/// writer started with [`Ledger::spawn_writer`].
+#[derive(Clone, Debug, Default)]
+pub struct Ledger {
+    path: Option<PathBuf>,
+    entries: Arc<Mutex<BTreeMap<String, LedgerEntry>>>,
+    /// Set while there are changes that haven't been saved yet.
+    dirty: Arc<AtomicBool>,
+    /// Notified on every change.
+    changed: Arc<Notify>,
+}
+
+impl Ledger {
+    /// Open the ledger saved at `path`, or an empty ledger if there is none yet. Without a path,
+    /// the ledger is kept in memory only.
+    pub fn open(path: Option<PathBuf>) -> Result<Self> {
+        let entries = match &path {
+            Some(path) if path.exists() => {
+                let json = std::fs::read_to_string(path)
+                    .with_context(|| format!("failed to read ledger {}", path.display()))?;
+                let entries: Vec<LedgerEntry> = serde_json::from_str(&json)
+                    .with_context(|| format!("failed to parse ledger {}", path.display()))?;
+                entries.into_iter().map(|entry| (entry.id.clone(), entry)).collect()
+            }
+            _ => BTreeMap::new(),
+        };
+        Ok(Self { path, entries: Arc::new(Mutex::new(entries)), ..Default::default() })
+    }
+
+    /// Start saving changes in the background, batching changes made while a save is running.
+    /// Does nothing for a ledger kept in memory only.
+    pub fn spawn_writer(&self) {
+        if self.path.is_none() {
+            return;
+        }
+        let ledger = self.clone();
+        tokio::spawn(async move {
+            loop {
+                ledger.changed.notified().await;
+                let writer = ledger.clone();
+                match tokio::task::spawn_blocking(move || writer.flush()).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => tracing::warn!(?e, "Failed to save ledger"),
+                    Err(e) => tracing::warn!(?e, "Ledger writer panicked"),
+                }
+            }
+        });
+    }
+
+    /// Save any unsaved changes to `path`.
+    pub fn flush(&self) -> Result<()> {
+        match &self.path {
+            Some(path) if self.dirty.swap(false, Ordering::AcqRel) => {
+                self.save(path).inspect_err(|_| self.dirty.store(true, Ordering::Release))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Record the bond posted for `id`.
+    pub fn record_bond(&self, id: &str, amount_wei: U256) {
+        self.update(id, |entry| entry.bond_posted_wei += amount_wei);
+    }
+
+    /// Record a transaction sent for `id`.
+    pub fn record_tx(
+        &self,
+        id: &str,
+        kind: LedgerTxKind,
+        tx_hash: B256,
+        gas_used: u64,
+        effective_gas_price: u128,
+    ) {
+        let gas_cost_wei = U256::from(gas_used) * U256::from(effective_gas_price);
+        self.update(id, |entry| entry.txs.push(LedgerTx { kind, tx_hash, gas_used, gas_cost_wei }));
+    }
+
+    /// Record the SP1 gas of a proof generated for `id`, fulfilled at `price_per_pgu` PROVE wei,
+    /// or at an unknown price if `None`.
+    pub fn record_proof(&self, id: &str, sp1_gas: u64, price_per_pgu: Option<u64>) {
+        self.update(id, |entry| {
+            entry.sp1_gas += sp1_gas;
+            match price_per_pgu {
+                Some(price) => {
+                    entry.proof_cost_prove_wei += U256::from(sp1_gas) * U256::from(price)
+                }
+                None => entry.unpriced_sp1_gas += sp1_gas,
+            }
+        });
+    }
+
+    /// Move the entry for `from` into the entry for `into`, e.g. the proofs an aggregation proof
+    /// consumed into the entry of the aggregation proof once it is relayed.
+    pub fn roll_up(&self, from: &str, into: &str) {
+        if from == into {
+            return;
+        }
+        let Some(from) = self.entries.lock().expect("ledger lock poisoned").remove(from) else {
+            return;
+        };
+        self.update(into, |entry| {
+            entry.bond_posted_wei += from.bond_posted_wei;
+            entry.credit_claimed_wei += from.credit_claimed_wei;
+            entry.txs.extend(from.txs);
+            entry.sp1_gas += from.sp1_gas;
+            entry.proof_cost_prove_wei += from.proof_cost_prove_wei;
+            entry.unpriced_sp1_gas += from.unpriced_sp1_gas;
+        });
+    }
+
+    /// Record the credit claimed from `id`.
+    pub fn record_claim(&self, id: &str, amount_wei: U256) {
+        self.update(id, |entry| entry.credit_claimed_wei += amount_wei);
+    }
+
+    /// A copy of the entry for `id`, if any.
+    pub fn entry(&self, id: &str) -> Option<LedgerEntry> {
+        self.entries.lock().expect("ledger lock poisoned").get(id).cloned()
+    }
+
+    /// The sums over all entries.
+    pub fn totals(&self) -> LedgerTotals {
+        let entries = self.entries.lock().expect("ledger lock poisoned");
+        entries.values().fold(LedgerTotals::default(), |totals, entry| LedgerTotals {
+            bond_posted_wei: totals.bond_posted_wei + entry.bond_posted_wei,
+            credit_claimed_wei: totals.credit_claimed_wei + entry.credit_claimed_wei,
+            gas_spent_wei: totals.gas_spent_wei + entry.gas_spent_wei(),
+            proof_cost_prove_wei: totals.proof_cost_prove_wei + entry.proof_cost_prove_wei,
+            net_wei: totals.net_wei + entry.net_wei(),
+        })
+    }
+
+    /// The entries as CSV, one row per entry with amounts in ETH (and PROVE for the proof cost).
+    pub fn to_csv(&self) -> String {
+        let entries = self.entries.lock().expect("ledger lock poisoned");
+        let mut csv = String::from(
+            "id,bond_posted_eth,credit_claimed_eth,gas_spent_eth,net_eth,txs,sp1_gas,proof_cost_prove,unpriced_sp1_gas\n",
+        );
+        for entry in entries.values() {
+            let net = entry.net_wei();
+            let net_eth = match net.is_negative() {
+                true => format!("-{}", format_ether(net.unsigned_abs())),
+                false => format_ether(net.into_raw()),
+            };
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                entry.id,
+                format_ether(entry.bond_posted_wei),
+                format_ether(entry.credit_claimed_wei),
+                format_ether(entry.gas_spent_wei()),
+                net_eth,
+                entry.txs.len(),
+                entry.sp1_gas,
+                format_ether(entry.proof_cost_prove_wei),
+                entry.unpriced_sp1_gas,
+            ));
+        }
+        csv
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut LedgerEntry)) {
+        {
+            let mut entries = self.entries.lock().expect("ledger lock poisoned");
+            let entry = entries
+                .entry(id.to_string())
+                .or_insert_with(|| LedgerEntry { id: id.to_string(), ..Default::default() });
+            f(entry);
+        }
+
+        self.dirty.store(true, Ordering::Release);
+        self.changed.notify_one();
+    }
+
+    /// Save the entries to `path` as JSON, and as CSV to `path` with a `csv` extension (both
+    /// atomic via temp file + rename).
+    fn save(&self, path: &Path) -> Result<()> {
+        let json = {
+            let entries = self.entries.lock().expect("ledger lock poisoned");
+            serde_json::to_string_pretty(&entries.values().collect::<Vec<_>>())
+                .context("failed to serialize ledger")?
+        };
+        write_atomic(path, &json)?;
+        write_atomic(&path.with_extension("csv"), &self.to_csv())
+    }
+}
+
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let dir = path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let mut temp = NamedTempFile::new_in(dir).context("failed to create ledger temp file")?;
+    temp.write_all(contents.as_bytes()).context("failed to write ledger temp file")?;
+    temp.as_file().sync_all().context("failed to sync ledger temp file")?;
+    temp.persist(path).context("failed to persist ledger file")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETH: u64 = 1_000_000_000_000_000_000;
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ledger.json");
+        let ledger = Ledger::open(Some(path.clone())).unwrap();
+
+        // A game created with a 1 ETH bond, challenged, proven, and claimed with the
+        // challenger's 2 ETH bond.
+        ledger.record_bond("game", U256::from(ETH));
+        ledger.record_tx(
+            "game",
+            LedgerTxKind::Create,
+            B256::repeat_byte(1),
+            100_000,
+            1_000_000_000,
+        );
+        ledger.record_tx("game", LedgerTxKind::Prove, B256::repeat_byte(2), 300_000, 1_000_000_000);
+        ledger.record_proof("game", 1_000, Some(2));
+        ledger.record_claim("game", U256::from(3 * ETH));
+        assert!(!path.exists());
+        ledger.flush().unwrap();
+
+        let entry = ledger.entry("game").unwrap();
+        assert_eq!(entry.gas_spent_wei(), U256::from(400_000_000_000_000u64));
+        assert_eq!(entry.net_wei(), I256::from_raw(U256::from(2 * ETH - 400_000_000_000_000)));
+        assert_eq!(entry.proof_cost_prove_wei, U256::from(2_000));
+
+        let reopened = Ledger::open(Some(path.clone())).unwrap();
+        assert_eq!(reopened.entry("game"), Some(entry));
+        assert_eq!(reopened.totals(), ledger.totals());
+
+        let csv = std::fs::read_to_string(path.with_extension("csv")).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("game,1.000000000000000000,3.000000000000000000,0.000400000000000000,1.999600000000000000,2,1000,0.000000000000002000,0"));
+    }
+
+    #[tokio::test]
+    async fn test_ledger_writer_saves_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ledger.json");
+        let ledger = Ledger::open(Some(path.clone())).unwrap();
+        ledger.spawn_writer();
+
+        ledger.record_bond("game", U256::from(ETH));
+        for _ in 0..100 {
+            if Ledger::open(Some(path.clone())).unwrap().entry("game").is_some() {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("ledger was not saved");
+    }
+
+    #[test]
+    fn test_ledger_net_loss() {
+        let ledger = Ledger::default();
+        ledger.record_bond("game", U256::from(ETH));
+
+        assert_eq!(ledger.totals().net_eth(), -1.0);
+        assert!(ledger.to_csv().contains("game,1.000000000000000000,0.000000000000000000,0.000000000000000000,-1.000000000000000000,"));
+    }
+
+    #[test]
+    fn test_ledger_roll_up() {
+        let ledger = Ledger::default();
+        ledger.record_proof("100-150", 1_000, Some(2));
+        ledger.record_proof("150-200", 500, None);
+        ledger.record_proof("100-200", 100, Some(3));
+        ledger.record_tx("100-200", LedgerTxKind::Relay, B256::repeat_byte(1), 100, 1);
+
+        ledger.roll_up("100-150", "100-200");
+        ledger.roll_up("150-200", "100-200");
+        ledger.roll_up("100-200", "100-200");
+
+        assert_eq!(ledger.entry("100-150"), None);
+        assert_eq!(ledger.entry("150-200"), None);
+        let entry = ledger.entry("100-200").unwrap();
+        assert_eq!(entry.sp1_gas, 1_600);
+        assert_eq!(entry.unpriced_sp1_gas, 500);
+        assert_eq!(entry.proof_cost_prove_wei, U256::from(2_300));
+        assert_eq!(entry.txs.len(), 1);
+    }
+}
@@ -3,6 +3,7 @@ mod constants;
 mod contract;
 pub mod fetcher;
 pub mod host;
+pub mod ledger;
 mod proof;
 pub mod rpc_types;
 pub mod stats;
@@ -12,7 +13,9 @@ pub use proof::*;
 pub mod logger;
 pub mod metrics;
 pub mod network;
+pub mod preimage_kv;
 pub mod proof_cache;
+pub mod quorum;
 pub mod witness_cache;
 pub mod witness_generation;
 pub use logger::setup_logger;
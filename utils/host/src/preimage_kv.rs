@@ -0,0 +1,182 @@
+//! On-disk preimage stores for the kona host, keyed by (chain_id, start_block, end_block).
+//!
+//! When `PREIMAGE_STORE_DIR` is set, the host writes every preimage it fetches for a range to an
+//! on-disk key-value store instead of keeping them in memory, and saves the range's boot inputs
+//! and chain configs next to it. The range's witness generation can then be replayed without any
+//! RPC, e.g. to debug
+//! a derivation failure after the RPC node has pruned the state, or to build test fixtures. See
+//! [`crate::host::OPSuccinctHost::replay_args`].
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use alloy_primitives::B256;
+use anyhow::{Context, Result};
+use kona_genesis::RollupConfig;
+use kona_host::single::SingleChainHost;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The name of the file holding the [PreimageStoreManifest] in a range's preimage store.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The names of the chain config files written for the host when a range is replayed.
+const ROLLUP_CONFIG_FILE: &str = "rollup_config.json";
+const L1_CONFIG_FILE: &str = "l1_config.json";
+
+/// Returns the base directory of the on-disk preimage stores, if `PREIMAGE_STORE_DIR` is set.
+pub fn preimage_store_base_dir() -> Option<PathBuf> {
+    env::var("PREIMAGE_STORE_DIR").ok().map(PathBuf::from)
+}
+
+/// Returns the preimage store directory for a given block range.
+pub fn get_preimage_store_dir(
+    base_dir: &Path,
+    chain_id: u64,
+    start_block: u64,
+    end_block: u64,
+) -> PathBuf {
+    base_dir.join(chain_id.to_string()).join(format!("{start_block}-{end_block}"))
+}
+
+/// The boot inputs, block range and chain configs of a range, saved with its preimage store so the
+/// range can be replayed without fetching them from the RPCs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreimageStoreManifest {
+    pub l1_head: B256,
+    pub agreed_l2_output_root: B256,
+    pub agreed_l2_head_hash: B256,
+    pub claimed_l2_output_root: B256,
+    pub l2_start_block: u64,
+    pub claimed_l2_block_number: u64,
+    pub l2_chain_id: u64,
+    pub rollup_config: RollupConfig,
+    pub l1_config: Value,
+}
+
+impl PreimageStoreManifest {
+    /// The boot inputs of the host arguments for the range starting at `l2_start_block`, derived
+    /// with the given chain configs.
+    pub fn from_host_args(
+        args: &SingleChainHost,
+        l2_start_block: u64,
+        rollup_config: &RollupConfig,
+        l1_config: Value,
+    ) -> Self {
+        Self {
+            l1_head: args.l1_head,
+            agreed_l2_output_root: args.agreed_l2_output_root,
+            agreed_l2_head_hash: args.agreed_l2_head_hash,
+            claimed_l2_output_root: args.claimed_l2_output_root,
+            l2_start_block,
+            claimed_l2_block_number: args.claimed_l2_block_number,
+            l2_chain_id: rollup_config.l2_chain_id.id(),
+            rollup_config: rollup_config.clone(),
+            l1_config,
+        }
+    }
+
+    /// Save the manifest to the preimage store directory, creating it if it doesn't exist.
+    pub fn save(&self, store_dir: &Path) -> Result<()> {
+        fs::create_dir_all(store_dir)?;
+        fs::write(store_dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Load the manifest from the preimage store directory.
+    pub fn load(store_dir: &Path) -> Result<Self> {
+        let path = store_dir.join(MANIFEST_FILE);
+        let bytes = fs::read(&path)
+            .with_context(|| format!("No preimage store manifest at {}", path.display()))?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    /// The host arguments serving the range's preimages only from the preimage store, without
+    /// RPCs. The chain configs are written to the store for the host to read.
+    pub fn offline_host_args(&self, store_dir: &Path) -> Result<SingleChainHost> {
+        let rollup_config_path = store_dir.join(ROLLUP_CONFIG_FILE);
+        fs::write(&rollup_config_path, serde_json::to_vec_pretty(&self.rollup_config)?)?;
+        let l1_config_path = store_dir.join(L1_CONFIG_FILE);
+        fs::write(&l1_config_path, serde_json::to_vec_pretty(&self.l1_config)?)?;
+
+        Ok(SingleChainHost {
+            l1_head: self.l1_head,
+            agreed_l2_output_root: self.agreed_l2_output_root,
+            agreed_l2_head_hash: self.agreed_l2_head_hash,
+            claimed_l2_output_root: self.claimed_l2_output_root,
+            claimed_l2_block_number: self.claimed_l2_block_number,
+            l2_chain_id: Some(self.l2_chain_id),
+            l2_node_address: None,
+            l1_node_address: None,
+            l1_beacon_address: None,
+            data_dir: Some(store_dir.to_path_buf()),
+            native: false,
+            server: true,
+            rollup_config_path: Some(rollup_config_path),
+            l1_config_path: Some(l1_config_path),
+            enable_experimental_witness_endpoint: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn manifest() -> PreimageStoreManifest {
+        PreimageStoreManifest {
+            l1_head: B256::repeat_byte(1),
+            agreed_l2_output_root: B256::repeat_byte(2),
+            agreed_l2_head_hash: B256::repeat_byte(3),
+            claimed_l2_output_root: B256::repeat_byte(4),
+            l2_start_block: 100,
+            claimed_l2_block_number: 110,
+            l2_chain_id: 10,
+            rollup_config: RollupConfig { l2_chain_id: 10.into(), ..Default::default() },
+            l1_config: json!({ "chainId": 1 }),
+        }
+    }
+
+    #[test]
+    fn test_manifest_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let store_dir = get_preimage_store_dir(dir.path(), 10, 100, 110);
+
+        manifest().save(&store_dir).unwrap();
+        assert_eq!(PreimageStoreManifest::load(&store_dir).unwrap(), manifest());
+        assert!(PreimageStoreManifest::load(dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_offline_host_args_have_no_rpcs() {
+        let dir = TempDir::new().unwrap();
+        let manifest = manifest();
+
+        let args = manifest.offline_host_args(dir.path()).unwrap();
+        assert_eq!(args.l1_node_address, None);
+        assert_eq!(args.l2_node_address, None);
+        assert_eq!(args.l1_beacon_address, None);
+        assert_eq!(args.data_dir.as_deref(), Some(dir.path()));
+        assert_eq!(
+            PreimageStoreManifest::from_host_args(
+                &args,
+                manifest.l2_start_block,
+                &manifest.rollup_config,
+                manifest.l1_config.clone(),
+            ),
+            manifest
+        );
+
+        // The host reads the chain configs from the store.
+        let rollup_config: RollupConfig =
+            serde_json::from_slice(&fs::read(args.rollup_config_path.unwrap()).unwrap()).unwrap();
+        assert_eq!(rollup_config, manifest.rollup_config);
+        let l1_config: Value =
+            serde_json::from_slice(&fs::read(args.l1_config_path.unwrap()).unwrap()).unwrap();
+        assert_eq!(l1_config, manifest.l1_config);
+    }
+}
@@ -1,18 +1,53 @@
 use alloy_consensus::Header;
 use alloy_primitives::{Address, B256};
-use anyhow::Result;
-use op_succinct_client_utils::{boot::BootInfoStruct, types::AggregationInputs};
+use anyhow::{bail, Result};
+use op_succinct_client_utils::{
+    boot::{BootInfoStruct, ExtendedRangeOutputs, EXTENDED_RANGE_OUTPUT_VERSION},
+    types::AggregationInputs,
+};
 use sp1_sdk::{HashableKey, SP1Proof, SP1Stdin};
 
+/// Read the outputs of a range proof from its public values. Proofs from the extended range ELF
+/// commit [ExtendedRangeOutputs] after the [BootInfoStruct].
+pub fn read_range_outputs(
+    public_values: &[u8],
+) -> Result<(BootInfoStruct, Option<ExtendedRangeOutputs>)> {
+    let boot_info: BootInfoStruct = bincode::deserialize(public_values)?;
+    let boot_info_len = bincode::serialized_size(&boot_info)? as usize;
+    if public_values.len() == boot_info_len {
+        return Ok((boot_info, None));
+    }
+
+    let extended_outputs: ExtendedRangeOutputs =
+        bincode::deserialize(&public_values[boot_info_len..])?;
+    if extended_outputs.version != EXTENDED_RANGE_OUTPUT_VERSION {
+        bail!(
+            "Unsupported extended range output version {}, expected {}",
+            extended_outputs.version,
+            EXTENDED_RANGE_OUTPUT_VERSION
+        );
+    }
+    Ok((boot_info, Some(extended_outputs)))
+}
+
 /// Get the stdin for the aggregation proof.
 pub fn get_agg_proof_stdin(
     proofs: Vec<SP1Proof>,
     boot_infos: Vec<BootInfoStruct>,
+    extended_outputs: Vec<Option<ExtendedRangeOutputs>>,
     headers: Vec<Header>,
     multi_block_vkey: &sp1_sdk::SP1VerifyingKey,
     latest_checkpoint_head: B256,
     prover_address: Address,
 ) -> Result<SP1Stdin> {
+    if extended_outputs.len() != boot_infos.len() {
+        bail!(
+            "Got {} extended outputs for {} boot infos",
+            extended_outputs.len(),
+            boot_infos.len()
+        );
+    }
+
     let mut stdin = SP1Stdin::default();
     for proof in proofs {
         let SP1Proof::Compressed(compressed_proof) = proof else {
@@ -24,6 +59,7 @@ pub fn get_agg_proof_stdin(
     // Write the aggregation inputs to the stdin.
     stdin.write(&AggregationInputs {
         boot_infos,
+        extended_outputs,
         latest_l1_checkpoint_head: latest_checkpoint_head,
         multi_block_vkey: multi_block_vkey.hash_u32(),
         prover_address,
@@ -34,3 +70,58 @@ pub fn get_agg_proof_stdin(
 
     Ok(stdin)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn boot_info() -> BootInfoStruct {
+        BootInfoStruct {
+            l1Head: B256::repeat_byte(1),
+            l2PreRoot: B256::repeat_byte(2),
+            l2PostRoot: B256::repeat_byte(3),
+            l2BlockNumber: 100,
+            rollupConfigHash: B256::repeat_byte(4),
+        }
+    }
+
+    fn extended_outputs(version: u32) -> ExtendedRangeOutputs {
+        ExtendedRangeOutputs {
+            version,
+            l2BlockHash: B256::repeat_byte(5),
+            messagePasserStorageRoot: B256::repeat_byte(6),
+            numBlocks: 10,
+            numTransactions: 42,
+        }
+    }
+
+    #[test]
+    fn test_read_legacy_range_outputs() {
+        let public_values = bincode::serialize(&boot_info()).unwrap();
+
+        let (decoded, extended) = read_range_outputs(&public_values).unwrap();
+        assert_eq!(decoded.l2PostRoot, boot_info().l2PostRoot);
+        assert!(extended.is_none());
+    }
+
+    #[test]
+    fn test_read_extended_range_outputs() {
+        let mut public_values = bincode::serialize(&boot_info()).unwrap();
+        public_values
+            .extend(bincode::serialize(&extended_outputs(EXTENDED_RANGE_OUTPUT_VERSION)).unwrap());
+
+        let (decoded, extended) = read_range_outputs(&public_values).unwrap();
+        assert_eq!(decoded.l2BlockNumber, 100);
+        let extended = extended.unwrap();
+        assert_eq!(extended.l2BlockHash, B256::repeat_byte(5));
+        assert_eq!(extended.numTransactions, 42);
+    }
+
+    #[test]
+    fn test_read_unknown_range_output_version() {
+        let mut public_values = bincode::serialize(&boot_info()).unwrap();
+        public_values.extend(bincode::serialize(&extended_outputs(99)).unwrap());
+
+        assert!(read_range_outputs(&public_values).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Agreement between independent RPC endpoints on a value.
+//!
+//! A single buggy or lagging L2 node can make the proposer commit to a wrong output root or the
+//! challenger dispute a valid one, forfeiting a bond either way. Querying several independent
+//! endpoints and acting only when enough of them agree turns a single faulty node into a halt
+//! instead of a loss.
+
+use std::{fmt::Debug, num::NonZeroUsize, str::FromStr};
+
+use anyhow::{ensure, Context, Result};
+use thiserror::Error;
+
+/// How many endpoints have to agree on a value.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QuorumPolicy {
+    /// Every endpoint responds with the same value.
+    #[default]
+    All,
+    /// At least this many endpoints respond with the same value, and no other value is backed by
+    /// as many.
+    AtLeast(NonZeroUsize),
+}
+
+impl FromStr for QuorumPolicy {
+    type Err = anyhow::Error;
+
+    /// Parses `all` or the number of endpoints that have to agree.
+    fn from_str(s: &str) -> Result<Self> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+        let quorum = s.parse().with_context(|| format!("Invalid quorum policy: {s}"))?;
+        Ok(Self::AtLeast(quorum))
+    }
+}
+
+/// Why the endpoints failed to agree.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum QuorumError {
+    /// Endpoints responded with different values.
+    #[error("Endpoints disagree: {values:?}")]
+    Disagreement { values: Vec<(String, String)> },
+    /// Too few endpoints responded for the quorum to be reached.
+    #[error("Only {responded} of {required} required endpoints responded")]
+    Unavailable { responded: usize, required: usize },
+}
+
+impl QuorumError {
+    /// Whether `err` was caused by endpoints disagreeing.
+    pub fn is_disagreement(err: &anyhow::Error) -> bool {
+        err.chain()
+            .any(|cause| matches!(cause.downcast_ref::<Self>(), Some(Self::Disagreement { .. })))
+    }
+}
+
+/// Parses a comma-separated list of endpoints, skipping empty entries.
+pub fn parse_endpoints<U>(list: &str) -> Result<Vec<U>>
+where
+    U: FromStr,
+    U::Err: std::error::Error + Send + Sync + 'static,
+{
+    list.split(',')
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+        .map(|endpoint| endpoint.parse().with_context(|| format!("Invalid endpoint: {endpoint}")))
+        .collect()
+}
+
+impl QuorumPolicy {
+    /// Checks that the policy can be met by `endpoints` endpoints, the primary one included.
+    pub fn validate(&self, endpoints: usize) -> Result<()> {
+        if let Self::AtLeast(quorum) = self {
+            ensure!(
+                quorum.get() <= endpoints,
+                "Quorum of {quorum} endpoints can't be reached with {endpoints} endpoints"
+            );
+        }
+        Ok(())
+    }
+
+    /// The number of endpoints that have to agree, out of `endpoints`.
+    fn required(&self, endpoints: usize) -> usize {
+        match self {
+            Self::All => endpoints,
+            Self::AtLeast(quorum) => quorum.get(),
+        }
+    }
+
+    /// The value the `responses` agree on under this policy, comparing values by `key`.
+    ///
+    /// Failed responses are logged and count as missing. Responses dissenting from a reached
+    /// quorum are logged.
+    pub fn reach<T, K>(
+        &self,
+        responses: Vec<(String, Result<T>)>,
+        key: impl Fn(&T) -> K,
+    ) -> Result<T, QuorumError>
+    where
+        K: PartialEq + Debug,
+    {
+        let required = self.required(responses.len());
+
+        // Group the successful responses by value, in order of first appearance.
+        let mut groups: Vec<(K, Vec<(String, T)>)> = Vec::new();
+        for (url, response) in responses {
+            let value = match response {
+                Ok(value) => value,
+                Err(err) => {
+                    tracing::warn!(endpoint = %url, ?err, "Endpoint failed to respond");
+                    continue;
+                }
+            };
+            let value_key = key(&value);
+            match groups.iter_mut().find(|(k, _)| *k == value_key) {
+                Some((_, members)) => members.push((url, value)),
+                None => groups.push((value_key, vec![(url, value)])),
+            }
+        }
+
+        let disagreement = |groups: &[(K, Vec<(String, T)>)]| QuorumError::Disagreement {
+            values: groups
+                .iter()
+                .flat_map(|(k, members)| {
+                    members.iter().map(move |(url, _)| (url.clone(), format!("{k:?}")))
+                })
+                .collect(),
+        };
+
+        let responded = groups.iter().map(|(_, members)| members.len()).sum::<usize>();
+        if matches!(self, Self::All) && groups.len() > 1 {
+            return Err(disagreement(&groups));
+        }
+
+        groups.sort_by_key(|(_, members)| std::cmp::Reverse(members.len()));
+        let backing = |i: usize| groups.get(i).map_or(0, |(_, members)| members.len());
+        if backing(0) < required {
+            return Err(if groups.len() > 1 {
+                disagreement(&groups)
+            } else {
+                QuorumError::Unavailable { responded, required }
+            });
+        }
+        // Two values backed by the same number of endpoints can't be told apart.
+        if backing(1) == backing(0) {
+            return Err(disagreement(&groups));
+        }
+
+        let mut groups = groups.into_iter();
+        let (_, mut members) = groups.next().expect("quorum group exists");
+        for (k, dissenting) in groups {
+            for (url, _) in dissenting {
+                tracing::warn!(endpoint = %url, value = ?k, "Endpoint dissents from the quorum");
+            }
+        }
+        Ok(members.swap_remove(0).1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn responses(values: &[Option<u64>]) -> Vec<(String, Result<u64>)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                (format!("http://node-{i}"), value.ok_or_else(|| anyhow!("unreachable")))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reach_quorum() {
+        let cases: &[(&str, &[Option<u64>], Option<u64>)] = &[
+            ("all", &[Some(1), Some(1), Some(1)], Some(1)),
+            ("all", &[Some(1), Some(2), Some(1)], None),
+            ("all", &[Some(1), None, Some(1)], None),
+            ("2", &[Some(1), Some(2), Some(1)], Some(1)),
+            ("2", &[Some(1), None, Some(1)], Some(1)),
+            ("2", &[Some(1), Some(2), Some(3)], None),
+            // Tied values can't be told apart.
+            ("1", &[Some(1), Some(2)], None),
+        ];
+
+        for (policy, values, expected) in cases {
+            let policy: QuorumPolicy = policy.parse().unwrap();
+            let reached = policy.reach(responses(values), |value| *value).ok();
+            assert_eq!(reached, *expected, "{policy:?} over {values:?}");
+        }
+    }
+
+    #[test]
+    fn test_validate_quorum() {
+        let cases = [("all", 1, true), ("1", 1, true), ("3", 3, true), ("3", 2, false)];
+
+        for (policy, endpoints, valid) in cases {
+            let policy: QuorumPolicy = policy.parse().unwrap();
+            assert_eq!(policy.validate(endpoints).is_ok(), valid, "{policy:?} of {endpoints}");
+        }
+    }
+
+    #[test]
+    fn test_quorum_errors() {
+        let policy = QuorumPolicy::All;
+        let err = anyhow::Error::from(
+            policy.reach(responses(&[Some(1), Some(2)]), |value| *value).unwrap_err(),
+        );
+        assert!(QuorumError::is_disagreement(&err.context("Failed to compute output root")));
+
+        let err = policy.reach(responses(&[Some(1), None]), |value| *value).unwrap_err();
+        assert_eq!(err, QuorumError::Unavailable { responded: 1, required: 2 });
+    }
+}
@@ -1,11 +1,59 @@
-use std::fmt;
+use std::{collections::BTreeMap, fmt};
 
 use crate::fetcher::BlockInfo;
 use num_format::{Locale, ToFormattedString};
-use op_succinct_client_utils::precompiles::cycle_tracker::keys;
-use serde::{Deserialize, Serialize};
+use op_succinct_client_utils::{
+    client::parse_block_cycle_tracker_key,
+    precompiles::cycle_tracker::{keys, names},
+    witness::compression::WitnessCompression,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sp1_sdk::ExecutionReport;
 
+/// The cycles spent in and calls made to a precompile.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecompileStats {
+    pub cycles: u64,
+    pub calls: u64,
+}
+
+/// The stats of every tracked precompile in the report, keyed by tracker name.
+pub fn precompile_stats(report: &ExecutionReport) -> BTreeMap<String, PrecompileStats> {
+    names::ALL
+        .iter()
+        .zip(keys::ALL)
+        .map(|(name, key)| {
+            let stats = PrecompileStats {
+                cycles: *report.cycle_tracker.get(*key).unwrap_or(&0),
+                calls: *report.invocation_tracker.get(*key).unwrap_or(&0),
+            };
+            (name.to_string(), stats)
+        })
+        .collect()
+}
+
+/// The cycles spent executing an L2 block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockCycleStats {
+    pub block_number: u64,
+    pub transaction_count: u64,
+    pub cycles: u64,
+}
+
+/// The execution cycles of each block in the report, sorted by block number.
+pub fn block_cycle_stats(report: &ExecutionReport) -> Vec<BlockCycleStats> {
+    let mut blocks: Vec<_> = report
+        .cycle_tracker
+        .iter()
+        .filter_map(|(key, cycles)| {
+            let (block_number, transaction_count) = parse_block_cycle_tracker_key(key)?;
+            Some(BlockCycleStats { block_number, transaction_count, cycles: *cycles })
+        })
+        .collect();
+    blocks.sort_by_key(|block| block.block_number);
+    blocks
+}
+
 /// Statistics for the range execution.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ExecutionStats {
@@ -21,7 +69,60 @@ pub struct ExecutionStats {
     pub derivation_instruction_count: u64,
     pub block_execution_instruction_count: u64,
     pub blob_verification_instruction_count: u64,
+    /// The cycles spent decompressing the witness.
+    #[serde(default)]
+    pub witness_decompression_instruction_count: u64,
+    /// The codec the witness was compressed with.
+    #[serde(default)]
+    pub witness_compression: WitnessCompression,
+    /// The size of the witness in stdin, after compression.
+    #[serde(default)]
+    pub witness_size_bytes: u64,
+    pub total_sp1_gas: u64,
+    /// The number of distinct memory addresses the program touched, a proxy for peak guest
+    /// memory.
+    #[serde(default)]
+    pub touched_memory_addresses: u64,
+    pub nb_blocks: u64,
+    pub nb_transactions: u64,
+    pub eth_gas_used: u64,
+    pub l1_fees: u128,
+    pub total_tx_fees: u128,
+    pub cycles_per_block: u64,
+    pub cycles_per_transaction: u64,
+    pub transactions_per_block: u64,
+    pub gas_used_per_block: u64,
+    pub gas_used_per_transaction: u64,
+    /// The cycles and calls of each precompile, keyed by tracker name.
+    #[serde(default)]
+    pub precompiles: BTreeMap<String, PrecompileStats>,
+    /// The execution cycles of each block, sorted by block number.
+    #[serde(default)]
+    pub blocks: Vec<BlockCycleStats>,
+}
+
+/// An [`ExecutionStats`] as a CSV row. CSV rows are flat, so the nested stats are JSON strings.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExecutionStatsCsvRow {
+    pub l1_head: u64,
+    pub batch_start: u64,
+    pub batch_end: u64,
+    pub witness_generation_time_sec: u64,
+    pub total_execution_time_sec: u64,
+    pub total_instruction_count: u64,
+    pub oracle_verify_instruction_count: u64,
+    pub derivation_instruction_count: u64,
+    pub block_execution_instruction_count: u64,
+    pub blob_verification_instruction_count: u64,
+    #[serde(default)]
+    pub witness_decompression_instruction_count: u64,
+    #[serde(default)]
+    pub witness_compression: WitnessCompression,
+    #[serde(default)]
+    pub witness_size_bytes: u64,
     pub total_sp1_gas: u64,
+    #[serde(default)]
+    pub touched_memory_addresses: u64,
     pub nb_blocks: u64,
     pub nb_transactions: u64,
     pub eth_gas_used: u64,
@@ -32,12 +133,124 @@ pub struct ExecutionStats {
     pub transactions_per_block: u64,
     pub gas_used_per_block: u64,
     pub gas_used_per_transaction: u64,
-    pub bn_pair_cycles: u64,
-    pub bn_add_cycles: u64,
-    pub bn_mul_cycles: u64,
-    pub kzg_eval_cycles: u64,
-    pub ec_recover_cycles: u64,
-    pub p256_verify_cycles: u64,
+    #[serde(default)]
+    pub precompiles: String,
+    #[serde(default)]
+    pub blocks: String,
+}
+
+impl From<&ExecutionStats> for ExecutionStatsCsvRow {
+    fn from(stats: &ExecutionStats) -> Self {
+        Self {
+            l1_head: stats.l1_head,
+            batch_start: stats.batch_start,
+            batch_end: stats.batch_end,
+            witness_generation_time_sec: stats.witness_generation_time_sec,
+            total_execution_time_sec: stats.total_execution_time_sec,
+            total_instruction_count: stats.total_instruction_count,
+            oracle_verify_instruction_count: stats.oracle_verify_instruction_count,
+            derivation_instruction_count: stats.derivation_instruction_count,
+            block_execution_instruction_count: stats.block_execution_instruction_count,
+            blob_verification_instruction_count: stats.blob_verification_instruction_count,
+            witness_decompression_instruction_count: stats.witness_decompression_instruction_count,
+            witness_compression: stats.witness_compression,
+            witness_size_bytes: stats.witness_size_bytes,
+            total_sp1_gas: stats.total_sp1_gas,
+            touched_memory_addresses: stats.touched_memory_addresses,
+            nb_blocks: stats.nb_blocks,
+            nb_transactions: stats.nb_transactions,
+            eth_gas_used: stats.eth_gas_used,
+            l1_fees: stats.l1_fees,
+            total_tx_fees: stats.total_tx_fees,
+            cycles_per_block: stats.cycles_per_block,
+            cycles_per_transaction: stats.cycles_per_transaction,
+            transactions_per_block: stats.transactions_per_block,
+            gas_used_per_block: stats.gas_used_per_block,
+            gas_used_per_transaction: stats.gas_used_per_transaction,
+            precompiles: serde_json::to_string(&stats.precompiles)
+                .expect("precompile stats serialize to JSON"),
+            blocks: serde_json::to_string(&stats.blocks).expect("block stats serialize to JSON"),
+        }
+    }
+}
+
+/// Parse a nested stats column, which is empty in rows written before the stats were tracked.
+fn from_json_or_default<T: DeserializeOwned + Default>(json: &str) -> serde_json::Result<T> {
+    if json.is_empty() {
+        return Ok(T::default());
+    }
+    serde_json::from_str(json)
+}
+
+impl TryFrom<ExecutionStatsCsvRow> for ExecutionStats {
+    type Error = serde_json::Error;
+
+    fn try_from(row: ExecutionStatsCsvRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            l1_head: row.l1_head,
+            batch_start: row.batch_start,
+            batch_end: row.batch_end,
+            witness_generation_time_sec: row.witness_generation_time_sec,
+            total_execution_time_sec: row.total_execution_time_sec,
+            total_instruction_count: row.total_instruction_count,
+            oracle_verify_instruction_count: row.oracle_verify_instruction_count,
+            derivation_instruction_count: row.derivation_instruction_count,
+            block_execution_instruction_count: row.block_execution_instruction_count,
+            blob_verification_instruction_count: row.blob_verification_instruction_count,
+            witness_decompression_instruction_count: row.witness_decompression_instruction_count,
+            witness_compression: row.witness_compression,
+            witness_size_bytes: row.witness_size_bytes,
+            total_sp1_gas: row.total_sp1_gas,
+            touched_memory_addresses: row.touched_memory_addresses,
+            nb_blocks: row.nb_blocks,
+            nb_transactions: row.nb_transactions,
+            eth_gas_used: row.eth_gas_used,
+            l1_fees: row.l1_fees,
+            total_tx_fees: row.total_tx_fees,
+            cycles_per_block: row.cycles_per_block,
+            cycles_per_transaction: row.cycles_per_transaction,
+            transactions_per_block: row.transactions_per_block,
+            gas_used_per_block: row.gas_used_per_block,
+            gas_used_per_transaction: row.gas_used_per_transaction,
+            precompiles: from_json_or_default(&row.precompiles)?,
+            blocks: from_json_or_default(&row.blocks)?,
+        })
+    }
+}
+
+/// Write the block with the most execution cycles to the formatter, if any.
+fn write_max_cycle_block(
+    f: &mut fmt::Formatter<'_>,
+    block: Option<&BlockCycleStats>,
+) -> fmt::Result {
+    if let Some(block) = block {
+        write_stat(f, "Max Block Cycles", block.cycles)?;
+        write_stat(f, "Max Cycles Block Number", block.block_number)?;
+        write_stat(f, "Max Cycles Block Transactions", block.transaction_count)?;
+    }
+    Ok(())
+}
+
+/// Write the cycles and calls of each precompile to the formatter.
+fn write_precompile_stats(
+    f: &mut fmt::Formatter<'_>,
+    precompiles: &BTreeMap<String, PrecompileStats>,
+) -> fmt::Result {
+    for (name, stats) in precompiles {
+        write_stat(f, &format!("{name} Cycles"), stats.cycles)?;
+        write_stat(f, &format!("{name} Calls"), stats.calls)?;
+    }
+    Ok(())
+}
+
+/// Write the codec and size of the witness to the formatter.
+fn write_witness_stats(
+    f: &mut fmt::Formatter<'_>,
+    compression: WitnessCompression,
+    size_bytes: u64,
+) -> fmt::Result {
+    writeln!(f, "| {:<30} | {:>25} |", "Witness Compression", compression)?;
+    write_stat(f, "Witness Size (bytes)", size_bytes)
 }
 
 /// Write a statistic to the formatter.
@@ -59,7 +272,14 @@ impl fmt::Display for ExecutionStats {
         write_stat(f, "Derivation Cycles", self.derivation_instruction_count)?;
         write_stat(f, "Block Execution Cycles", self.block_execution_instruction_count)?;
         write_stat(f, "Blob Verification Cycles", self.blob_verification_instruction_count)?;
+        write_witness_stats(f, self.witness_compression, self.witness_size_bytes)?;
+        write_stat(
+            f,
+            "Witness Decompression Cycles",
+            self.witness_decompression_instruction_count,
+        )?;
         write_stat(f, "Total SP1 Gas", self.total_sp1_gas)?;
+        write_stat(f, "Touched Memory Addresses", self.touched_memory_addresses)?;
         write_stat(f, "Number of Blocks", self.nb_blocks)?;
         write_stat(f, "Number of Transactions", self.nb_transactions)?;
         write_stat(f, "Ethereum Gas Used", self.eth_gas_used)?;
@@ -68,17 +288,18 @@ impl fmt::Display for ExecutionStats {
         write_stat(f, "Transactions per Block", self.transactions_per_block)?;
         write_stat(f, "Gas Used per Block", self.gas_used_per_block)?;
         write_stat(f, "Gas Used per Transaction", self.gas_used_per_transaction)?;
-        write_stat(f, "BN Pair Cycles", self.bn_pair_cycles)?;
-        write_stat(f, "BN Add Cycles", self.bn_add_cycles)?;
-        write_stat(f, "BN Mul Cycles", self.bn_mul_cycles)?;
-        write_stat(f, "KZG Eval Cycles", self.kzg_eval_cycles)?;
-        write_stat(f, "EC Recover Cycles", self.ec_recover_cycles)?;
-        write_stat(f, "P256 Verify Cycles", self.p256_verify_cycles)?;
+        write_max_cycle_block(f, self.max_cycle_block())?;
+        write_precompile_stats(f, &self.precompiles)?;
         writeln!(f, "+--------------------------------+---------------------------+")
     }
 }
 
 impl ExecutionStats {
+    /// The block with the most execution cycles, if blocks were tracked.
+    pub fn max_cycle_block(&self) -> Option<&BlockCycleStats> {
+        self.blocks.iter().max_by_key(|block| block.cycles)
+    }
+
     /// Create a new execution stats.
     pub fn new(
         l1_head: u64,
@@ -113,16 +334,14 @@ impl ExecutionStats {
             batch_end: block_data[block_data.len() - 1].block_number,
             total_instruction_count: total_instructions,
             total_sp1_gas: report.gas().unwrap_or(0),
+            touched_memory_addresses: report.touched_memory_addresses,
             block_execution_instruction_count: get_cycles("block-execution"),
             oracle_verify_instruction_count: get_cycles("oracle-verify"),
             derivation_instruction_count: get_cycles("payload-derivation"),
             blob_verification_instruction_count: get_cycles("blob-verification"),
-            bn_add_cycles: get_cycles(keys::BN_ADD),
-            bn_mul_cycles: get_cycles(keys::BN_MUL),
-            bn_pair_cycles: get_cycles(keys::BN_PAIR),
-            kzg_eval_cycles: get_cycles(keys::KZG_EVAL),
-            ec_recover_cycles: get_cycles(keys::EC_RECOVER),
-            p256_verify_cycles: get_cycles(keys::P256_VERIFY),
+            witness_decompression_instruction_count: get_cycles("witness-decompression"),
+            precompiles: precompile_stats(report),
+            blocks: block_cycle_stats(report),
             nb_transactions,
             eth_gas_used: block_data.iter().map(|b| b.gas_used).sum(),
             l1_fees: block_data.iter().map(|b| b.total_l1_fees).sum(),
@@ -135,8 +354,20 @@ impl ExecutionStats {
             gas_used_per_transaction: safe_div(total_gas_used, nb_transactions),
             witness_generation_time_sec,
             total_execution_time_sec,
+            ..Default::default()
         }
     }
+
+    /// Record the codec and size of the witness the range was executed with, as returned by
+    /// [crate::witness_generation::compression::witness_info].
+    pub fn with_witness_info(
+        mut self,
+        (compression, size_bytes): (WitnessCompression, u64),
+    ) -> Self {
+        self.witness_compression = compression;
+        self.witness_size_bytes = size_bytes;
+        self
+    }
 }
 
 /// A [ExecutionStats] that can be displayed as Markdown.
@@ -162,7 +393,14 @@ impl fmt::Display for MarkdownExecutionStats {
         write_stat(f, "Derivation Cycles", self.0.derivation_instruction_count)?;
         write_stat(f, "Block Execution Cycles", self.0.block_execution_instruction_count)?;
         write_stat(f, "Blob Verification Cycles", self.0.blob_verification_instruction_count)?;
+        write_witness_stats(f, self.0.witness_compression, self.0.witness_size_bytes)?;
+        write_stat(
+            f,
+            "Witness Decompression Cycles",
+            self.0.witness_decompression_instruction_count,
+        )?;
         write_stat(f, "Total SP1 Gas", self.0.total_sp1_gas)?;
+        write_stat(f, "Touched Memory Addresses", self.0.touched_memory_addresses)?;
         write_stat(f, "Number of Blocks", self.0.nb_blocks)?;
         write_stat(f, "Number of Transactions", self.0.nb_transactions)?;
         write_stat(f, "Ethereum Gas Used", self.0.eth_gas_used)?;
@@ -171,11 +409,93 @@ impl fmt::Display for MarkdownExecutionStats {
         write_stat(f, "Transactions per Block", self.0.transactions_per_block)?;
         write_stat(f, "Gas Used per Block", self.0.gas_used_per_block)?;
         write_stat(f, "Gas Used per Transaction", self.0.gas_used_per_transaction)?;
-        write_stat(f, "BN Pair Cycles", self.0.bn_pair_cycles)?;
-        write_stat(f, "BN Add Cycles", self.0.bn_add_cycles)?;
-        write_stat(f, "BN Mul Cycles", self.0.bn_mul_cycles)?;
-        write_stat(f, "KZG Eval Cycles", self.0.kzg_eval_cycles)?;
-        write_stat(f, "EC Recover Cycles", self.0.ec_recover_cycles)?;
-        write_stat(f, "P256 Verify Cycles", self.0.p256_verify_cycles)
+        write_max_cycle_block(f, self.0.max_cycle_block())?;
+        write_precompile_stats(f, &self.0.precompiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precompile_stats_round_trip() {
+        let stats = ExecutionStats {
+            precompiles: BTreeMap::from([
+                (names::BLS12_PAIRING.to_string(), PrecompileStats { cycles: 100, calls: 2 }),
+                (names::MODEXP.to_string(), PrecompileStats { cycles: 5, calls: 1 }),
+            ]),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let decoded: ExecutionStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.precompiles, stats.precompiles);
+    }
+
+    #[test]
+    fn test_block_stats_round_trip() {
+        let stats = ExecutionStats {
+            blocks: vec![
+                BlockCycleStats { block_number: 10, transaction_count: 3, cycles: 1_000 },
+                BlockCycleStats { block_number: 11, transaction_count: 40, cycles: 9_000 },
+            ],
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&stats).unwrap();
+        let decoded: ExecutionStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.blocks, stats.blocks);
+        assert_eq!(decoded.max_cycle_block().map(|block| block.block_number), Some(11));
+    }
+
+    #[test]
+    fn test_nested_stats_serialize_natively() {
+        let stats = ExecutionStats {
+            precompiles: BTreeMap::from([(
+                names::MODEXP.to_string(),
+                PrecompileStats { cycles: 5, calls: 1 },
+            )]),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["precompiles"][names::MODEXP]["cycles"], 5);
+        assert!(json["blocks"].is_array());
+    }
+
+    #[test]
+    fn test_csv_row_round_trip() {
+        let stats = ExecutionStats {
+            precompiles: BTreeMap::from([(
+                names::MODEXP.to_string(),
+                PrecompileStats { cycles: 5, calls: 1 },
+            )]),
+            blocks: vec![BlockCycleStats { block_number: 10, transaction_count: 3, cycles: 1_000 }],
+            ..Default::default()
+        };
+
+        let mut writer = csv::Writer::from_writer(vec![]);
+        writer.serialize(ExecutionStatsCsvRow::from(&stats)).unwrap();
+        let csv = writer.into_inner().unwrap();
+
+        let mut reader = csv::Reader::from_reader(csv.as_slice());
+        let row: ExecutionStatsCsvRow = reader.deserialize().next().unwrap().unwrap();
+        let decoded = ExecutionStats::try_from(row).unwrap();
+        assert_eq!(decoded.precompiles, stats.precompiles);
+        assert_eq!(decoded.blocks, stats.blocks);
+    }
+
+    #[test]
+    fn test_stats_without_precompiles_deserialize() {
+        // Stats written before precompiles were tracked as a map.
+        let mut json = serde_json::to_value(ExecutionStats::default()).unwrap();
+        json.as_object_mut().unwrap().remove("precompiles");
+        json.as_object_mut().unwrap().remove("blocks");
+        json["bn_pair_cycles"] = 10.into();
+
+        let decoded: ExecutionStats = serde_json::from_value(json).unwrap();
+        assert!(decoded.precompiles.is_empty());
+        assert!(decoded.blocks.is_empty());
     }
 }
@@ -7,12 +7,26 @@
 //! Note: While SP1Stdin is the same type across all DA implementations, the serialized contents
 //! (WitnessData) are DA-specific. Cache files are compatible between Ethereum DA and Celestia DA
 //! (both use DefaultWitnessData), but NOT compatible with EigenDA (uses EigenDAWitnessData).
+//!
+//! The cached stdin holds the witness as written by `get_sp1_stdin`, compressed with the
+//! `WITNESS_COMPRESSION` in effect when it was generated. Cache files start with a format
+//! version, and files written with another stdin layout are discarded and regenerated.
 
 use std::{fs, path::PathBuf};
 
 use anyhow::Result;
 use sp1_sdk::SP1Stdin;
 
+/// Prefix of cache files, followed by [`CACHE_FORMAT_VERSION`].
+const CACHE_MAGIC: &[u8; 8] = b"OPSWITNS";
+
+/// Version of the cached stdin layout. Bump it whenever the layout written by `get_sp1_stdin`
+/// changes, so stale cache files are regenerated instead of fed to the range program.
+///
+/// - 1: The witness compression codec is written ahead of the witness. Files from before had no
+///   header.
+pub const CACHE_FORMAT_VERSION: u32 = 1;
+
 /// Returns the cache directory path for a given chain ID.
 pub fn get_cache_dir(chain_id: u64) -> PathBuf {
     PathBuf::from(format!("data/{}/witness-cache", chain_id))
@@ -39,8 +53,7 @@ pub fn save_stdin_to_cache(
     }
 
     let cache_path = get_stdin_cache_path(chain_id, start_block, end_block);
-    let bytes = bincode::serialize(stdin)?;
-    fs::write(&cache_path, &bytes)?;
+    fs::write(&cache_path, encode_cache_file(stdin)?)?;
 
     Ok(cache_path)
 }
@@ -48,7 +61,8 @@ pub fn save_stdin_to_cache(
 /// Load SP1Stdin from cache if it exists.
 ///
 /// Returns `Ok(Some(stdin))` if the cache file exists and was successfully deserialized,
-/// `Ok(None)` if the cache file doesn't exist, or an error if deserialization failed.
+/// `Ok(None)` if the cache file doesn't exist or has another format version, in which case it is
+/// removed, or an error if deserialization failed.
 pub fn load_stdin_from_cache(
     chain_id: u64,
     start_block: u64,
@@ -61,7 +75,65 @@ pub fn load_stdin_from_cache(
     }
 
     let bytes = fs::read(&cache_path)?;
-    let stdin = bincode::deserialize(&bytes)?;
+    let stdin = decode_cache_file(&bytes)?;
+    if stdin.is_none() {
+        tracing::warn!(
+            path = %cache_path.display(),
+            version = CACHE_FORMAT_VERSION,
+            "Removing witness cache file with a stale format"
+        );
+        fs::remove_file(&cache_path)?;
+    }
+
+    Ok(stdin)
+}
+
+/// Serialize `stdin` behind the cache header.
+fn encode_cache_file(stdin: &SP1Stdin) -> Result<Vec<u8>> {
+    let mut bytes = CACHE_MAGIC.to_vec();
+    bytes.extend(CACHE_FORMAT_VERSION.to_le_bytes());
+    bytes.extend(bincode::serialize(stdin)?);
+    Ok(bytes)
+}
+
+/// Deserialize a cache file, or `None` if it doesn't have the current format version.
+fn decode_cache_file(bytes: &[u8]) -> Result<Option<SP1Stdin>> {
+    let Some(rest) = bytes.strip_prefix(CACHE_MAGIC.as_slice()) else {
+        return Ok(None);
+    };
+    let Some(stdin) = rest.strip_prefix(CACHE_FORMAT_VERSION.to_le_bytes().as_slice()) else {
+        return Ok(None);
+    };
+    Ok(Some(bincode::deserialize(stdin)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stdin() -> SP1Stdin {
+        let mut stdin = SP1Stdin::default();
+        stdin.write_slice(&[1, 2, 3]);
+        stdin
+    }
+
+    #[test]
+    fn test_cache_file_round_trip() {
+        let bytes = encode_cache_file(&stdin()).unwrap();
+        let decoded = decode_cache_file(&bytes).unwrap().unwrap();
+        assert_eq!(decoded.buffer, stdin().buffer);
+    }
 
-    Ok(Some(stdin))
+    #[test]
+    fn test_stale_cache_file_rejected() {
+        // A file from before the format version was written.
+        let unversioned = bincode::serialize(&stdin()).unwrap();
+        assert!(decode_cache_file(&unversioned).unwrap().is_none());
+
+        // A file from another format version.
+        let mut other_version = CACHE_MAGIC.to_vec();
+        other_version.extend((CACHE_FORMAT_VERSION + 1).to_le_bytes());
+        other_version.extend(bincode::serialize(&stdin()).unwrap());
+        assert!(decode_cache_file(&other_version).unwrap().is_none());
+    }
 }
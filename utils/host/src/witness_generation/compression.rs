@@ -0,0 +1,72 @@
+//! Writing the witness to [SP1Stdin] with the configured [WitnessCompression].
+
+use std::env;
+
+use anyhow::{anyhow, Result};
+use op_succinct_client_utils::witness::compression::WitnessCompression;
+use sp1_sdk::SP1Stdin;
+
+/// The zstd level the witness is compressed with.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Read the witness compression from `WITNESS_COMPRESSION` (`none`, `zstd` or `lz4`). Defaults to
+/// no compression.
+pub fn witness_compression_from_env() -> Result<WitnessCompression> {
+    match env::var("WITNESS_COMPRESSION") {
+        Ok(compression) => compression.parse(),
+        Err(_) => Ok(WitnessCompression::None),
+    }
+}
+
+/// Compress the rkyv-serialized witness and write it to `stdin`, after the codec the range program
+/// decompresses it with.
+pub fn write_witness(
+    stdin: &mut SP1Stdin,
+    witness: &[u8],
+    compression: WitnessCompression,
+) -> Result<()> {
+    stdin.write(&compression);
+    match compression {
+        WitnessCompression::None => stdin.write_slice(witness),
+        WitnessCompression::Zstd => stdin.write_slice(&zstd::bulk::compress(witness, ZSTD_LEVEL)?),
+        WitnessCompression::Lz4 => stdin.write_slice(&lz4_flex::compress_prepend_size(witness)),
+    }
+    Ok(())
+}
+
+/// The codec and size in bytes of the witness written to `stdin` by [write_witness].
+pub fn witness_info(stdin: &SP1Stdin) -> Result<(WitnessCompression, u64)> {
+    let [compression, witness, ..] = stdin.buffer.as_slice() else {
+        return Err(anyhow!("Stdin has no witness"));
+    };
+    Ok((bincode::deserialize(compression)?, witness.len() as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_witness_round_trip() {
+        let witness: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+
+        for compression in
+            [WitnessCompression::None, WitnessCompression::Zstd, WitnessCompression::Lz4]
+        {
+            let mut stdin = SP1Stdin::default();
+            write_witness(&mut stdin, &witness, compression).unwrap();
+
+            let (written_compression, size) = witness_info(&stdin).unwrap();
+            assert_eq!(written_compression, compression);
+            if compression != WitnessCompression::None {
+                assert!(size < witness.len() as u64);
+            }
+            assert_eq!(compression.decompress(stdin.buffer[1].clone()).unwrap(), witness);
+        }
+    }
+
+    #[test]
+    fn test_witness_info_requires_witness() {
+        assert!(witness_info(&SP1Stdin::default()).is_err());
+    }
+}
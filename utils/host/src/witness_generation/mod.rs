@@ -1,8 +1,17 @@
+pub mod compression;
+pub use compression::{witness_compression_from_env, write_witness};
+
 pub mod traits;
 pub use traits::{DefaultOracleBase, WitnessGenerator};
 
+pub mod options;
+pub use options::{WitnessGenOptions, WitnessGenerationError};
+
 pub mod online_blob_store;
 pub use online_blob_store::OnlineBlobStore;
 
 pub mod preimage_witness_collector;
 pub use preimage_witness_collector::PreimageWitnessCollector;
+
+pub mod shared_preimage_cache;
+pub use shared_preimage_cache::{SharedPreimageCache, SharedPreimageCacheScope};
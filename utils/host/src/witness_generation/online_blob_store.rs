@@ -33,6 +33,12 @@ impl<T: BlobProvider + Send> BlobProvider for OnlineBlobStore<T> {
         for blob in &blobs {
             let (c_kzg_blob, commitment, proof) = get_blob_data(blob, &settings);
 
+            // The same blob may be requested more than once. The zkVM looks blobs up by versioned
+            // hash, so each blob only needs to be in the witness once.
+            if store.commitments.iter().any(|c| c.0 == commitment.0) {
+                continue;
+            }
+
             store.blobs.push(c_kzg_blob);
             store.commitments.push(commitment);
             store.proofs.push(proof);
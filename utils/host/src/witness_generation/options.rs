@@ -0,0 +1,78 @@
+//! Limits for a witness generation run.
+
+use std::{env, time::Duration};
+
+use anyhow::{Context, Result};
+use thiserror::Error;
+
+use crate::witness_generation::SharedPreimageCache;
+
+/// The options of a witness generation run. See
+/// [`crate::host::OPSuccinctHost::run_with_options`].
+#[derive(Clone, Debug, Default)]
+pub struct WitnessGenOptions {
+    /// Preimages shared with the witness generation of other ranges.
+    pub shared_cache: Option<SharedPreimageCache>,
+    /// The wall-clock time the run may take before it is aborted.
+    pub timeout: Option<Duration>,
+    /// The total size of the preimages the run may collect into the witness before it is aborted.
+    pub max_preimage_bytes: Option<u64>,
+}
+
+impl WitnessGenOptions {
+    /// The limits set by `WITNESSGEN_TIMEOUT` (in seconds) and `MAX_WITNESS_PREIMAGE_BYTES`. Runs
+    /// are unbounded if unset.
+    pub fn from_env() -> Result<Self> {
+        let timeout = match env::var("WITNESSGEN_TIMEOUT") {
+            Ok(secs) => {
+                Some(Duration::from_secs(secs.parse().context("Invalid WITNESSGEN_TIMEOUT")?))
+            }
+            Err(_) => None,
+        };
+        let max_preimage_bytes = match env::var("MAX_WITNESS_PREIMAGE_BYTES") {
+            Ok(bytes) => Some(bytes.parse().context("Invalid MAX_WITNESS_PREIMAGE_BYTES")?),
+            Err(_) => None,
+        };
+        Ok(Self { timeout, max_preimage_bytes, ..Default::default() })
+    }
+
+    /// These options, sharing preimages through `shared_cache`.
+    pub fn with_shared_cache(self, shared_cache: SharedPreimageCache) -> Self {
+        Self { shared_cache: Some(shared_cache), ..self }
+    }
+}
+
+/// Why a witness generation run was aborted. Either way the range is too large, so a smaller range
+/// may succeed.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WitnessGenerationError {
+    #[error("Witness generation timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("Witness generation collected more than {limit} bytes of preimages")]
+    PreimageLimitExceeded { limit: u64 },
+}
+
+impl WitnessGenerationError {
+    /// The [WitnessGenerationError] in the chain of `err`, if any.
+    pub fn find(err: &anyhow::Error) -> Option<&Self> {
+        err.chain().find_map(|cause| cause.downcast_ref::<Self>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_find_witness_generation_error() {
+        let err = anyhow::Error::from(WitnessGenerationError::PreimageLimitExceeded { limit: 1 })
+            .context("Failed to generate witness");
+        assert_eq!(
+            WitnessGenerationError::find(&err),
+            Some(&WitnessGenerationError::PreimageLimitExceeded { limit: 1 })
+        );
+
+        assert!(WitnessGenerationError::find(&anyhow!("RPC error")).is_none());
+    }
+}
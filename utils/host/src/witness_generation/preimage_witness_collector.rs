@@ -1,4 +1,7 @@
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
 
 use async_trait::async_trait;
 use kona_preimage::{
@@ -8,10 +11,19 @@ use kona_preimage::{
 use kona_proof::FlushableCache;
 use op_succinct_client_utils::witness::preimage_store::PreimageStore;
 
+use crate::witness_generation::{SharedPreimageCache, WitnessGenerationError};
+
 #[derive(Clone, Debug)]
 pub struct PreimageWitnessCollector<P: CommsClient + FlushableCache + Send + Sync + Clone> {
     pub preimage_oracle: Arc<P>,
     pub preimage_witness_store: Arc<Mutex<PreimageStore>>,
+    /// Preimages shared with the witness generation of other ranges. Preimages found here are
+    /// not requested from the host.
+    pub shared_cache: Option<SharedPreimageCache>,
+    /// The total size of the preimages saved to the witness store. Saving a preimage past
+    /// `max_preimage_bytes` fails, aborting the run.
+    pub preimage_bytes: Arc<AtomicU64>,
+    pub max_preimage_bytes: Option<u64>,
 }
 
 #[async_trait]
@@ -20,13 +32,30 @@ where
     P: CommsClient + FlushableCache + Send + Sync + Clone,
 {
     async fn get(&self, key: PreimageKey) -> PreimageOracleResult<Vec<u8>> {
-        let value = self.preimage_oracle.get(key).await?;
+        let value = match self.shared_cache.as_ref().and_then(|cache| cache.get(&key)) {
+            Some(value) => value,
+            None => {
+                let value = self.preimage_oracle.get(key).await?;
+                if let Some(cache) = &self.shared_cache {
+                    cache.insert(key, &value);
+                }
+                value
+            }
+        };
         self.save(key, &value)?;
         Ok(value)
     }
 
     async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> PreimageOracleResult<()> {
-        self.preimage_oracle.get_exact(key, buf).await?;
+        match self.shared_cache.as_ref().and_then(|cache| cache.get(&key)) {
+            Some(value) if value.len() == buf.len() => buf.copy_from_slice(&value),
+            _ => {
+                self.preimage_oracle.get_exact(key, buf).await?;
+                if let Some(cache) = &self.shared_cache {
+                    cache.insert(key, buf);
+                }
+            }
+        }
         self.save(key, buf)?;
         Ok(())
     }
@@ -55,10 +84,46 @@ impl<P> PreimageWitnessCollector<P>
 where
     P: CommsClient + FlushableCache + Send + Sync + Clone,
 {
+    pub fn new(
+        preimage_oracle: Arc<P>,
+        preimage_witness_store: Arc<Mutex<PreimageStore>>,
+        shared_cache: Option<SharedPreimageCache>,
+        max_preimage_bytes: Option<u64>,
+    ) -> Self {
+        Self {
+            preimage_oracle,
+            preimage_witness_store,
+            shared_cache,
+            preimage_bytes: Default::default(),
+            max_preimage_bytes,
+        }
+    }
+
     pub fn save(&self, key: PreimageKey, value: &[u8]) -> PreimageOracleResult<()> {
         let mut witness_store_lock = self.preimage_witness_store.lock().map_err(|_| {
             PreimageOracleError::Other("Failed to acquire preimage_witness_store lock".to_string())
         })?;
+        if !witness_store_lock.preimage_map.contains_key(&key) {
+            self.preimage_bytes.fetch_add(value.len() as u64, Ordering::Relaxed);
+            if let Some(err) = self.limit_exceeded() {
+                return Err(PreimageOracleError::Other(err.to_string()));
+            }
+        }
         witness_store_lock.save_preimage(key, value.to_vec())
     }
+
+    /// The error aborting the run if the collected preimages exceed `max_preimage_bytes`.
+    pub fn limit_exceeded(&self) -> Option<WitnessGenerationError> {
+        let limit = self.max_preimage_bytes?;
+        (self.preimage_bytes.load(Ordering::Relaxed) > limit)
+            .then_some(WitnessGenerationError::PreimageLimitExceeded { limit })
+    }
+
+    /// Replaces `err` with [`WitnessGenerationError::PreimageLimitExceeded`] if the collected
+    /// preimages exceed `max_preimage_bytes`. The oracle error returned by
+    /// [`PreimageWitnessCollector::save`] loses its type on its way through the derivation
+    /// pipeline and executor.
+    pub fn map_limit_error(&self, err: anyhow::Error) -> anyhow::Error {
+        self.limit_exceeded().map_or(err, Into::into)
+    }
 }
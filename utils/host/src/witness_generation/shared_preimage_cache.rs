@@ -0,0 +1,234 @@
+use std::{
+    env,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use kona_preimage::{PreimageKey, PreimageKeyType};
+use lru::LruCache;
+
+/// The default capacity of a [SharedPreimageCache], in bytes of preimage data.
+pub const DEFAULT_SHARED_PREIMAGE_CACHE_BYTES: usize = 1 << 30;
+
+/// A preimage cache shared by the witness generation of several ranges.
+///
+/// Adjacent ranges read many of the same preimages (L1 headers, receipts and state trie nodes
+/// around the range boundary). With a shared cache, a preimage fetched for one range is served
+/// from memory to the others instead of being fetched from the RPCs again. Each range still
+/// collects every preimage it reads into its own witness.
+///
+/// Only preimages keyed by the hash of their value are shared. Local preimages, such as the boot
+/// info, differ between ranges. The least recently used preimages are evicted once the cache
+/// holds more than its capacity.
+#[derive(Clone, Debug)]
+pub struct SharedPreimageCache {
+    inner: Arc<Mutex<SharedPreimageCacheInner>>,
+}
+
+#[derive(Debug)]
+struct SharedPreimageCacheInner {
+    preimages: LruCache<PreimageKey, Vec<u8>>,
+    size_bytes: usize,
+    capacity_bytes: usize,
+}
+
+impl Default for SharedPreimageCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHARED_PREIMAGE_CACHE_BYTES)
+    }
+}
+
+impl SharedPreimageCache {
+    /// Create a cache holding up to `capacity_bytes` of preimage data.
+    pub fn new(capacity_bytes: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(SharedPreimageCacheInner {
+                preimages: LruCache::unbounded(),
+                size_bytes: 0,
+                capacity_bytes,
+            })),
+        }
+    }
+
+    /// Create a cache holding up to `SHARED_PREIMAGE_CACHE_BYTES` of preimage data, or
+    /// [DEFAULT_SHARED_PREIMAGE_CACHE_BYTES] if unset.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(capacity_from_env()?))
+    }
+
+    /// Whether preimages of this key type can be shared between ranges.
+    pub fn is_shared(key_type: PreimageKeyType) -> bool {
+        matches!(key_type, PreimageKeyType::Keccak256 | PreimageKeyType::Sha256)
+    }
+
+    /// The cached preimage for `key`, if any.
+    pub fn get(&self, key: &PreimageKey) -> Option<Vec<u8>> {
+        self.inner.lock().unwrap().preimages.get(key).cloned()
+    }
+
+    /// Cache the preimage for `key` if its key type is shared, evicting the least recently used
+    /// preimages to stay within capacity.
+    pub fn insert(&self, key: PreimageKey, value: &[u8]) {
+        if !Self::is_shared(key.key_type()) {
+            return;
+        }
+
+        let mut inner = self.inner.lock().unwrap();
+        if value.len() > inner.capacity_bytes || inner.preimages.contains(&key) {
+            return;
+        }
+        while inner.size_bytes + value.len() > inner.capacity_bytes {
+            let Some((_, evicted)) = inner.preimages.pop_lru() else {
+                break;
+            };
+            inner.size_bytes -= evicted.len();
+        }
+        inner.size_bytes += value.len();
+        inner.preimages.put(key, value.to_vec());
+    }
+
+    /// The number of cached preimages.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().preimages.len()
+    }
+
+    /// Whether the cache holds no preimages.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The total size of the cached preimages, in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.inner.lock().unwrap().size_bytes
+    }
+}
+
+/// The capacity set by `SHARED_PREIMAGE_CACHE_BYTES`, or [DEFAULT_SHARED_PREIMAGE_CACHE_BYTES] if
+/// unset.
+fn capacity_from_env() -> Result<usize> {
+    match env::var("SHARED_PREIMAGE_CACHE_BYTES") {
+        Ok(bytes) => bytes.parse().context("Invalid SHARED_PREIMAGE_CACHE_BYTES"),
+        Err(_) => Ok(DEFAULT_SHARED_PREIMAGE_CACHE_BYTES),
+    }
+}
+
+/// Hands out a [SharedPreimageCache] per batch of adjacent ranges.
+///
+/// A range starting within or at the end of the current batch joins it and shares its cache,
+/// until the batch holds `max_batch_ranges` ranges. Any other range starts a new batch with an
+/// empty cache, so the preimages of earlier batches are dropped once their ranges are done.
+#[derive(Debug)]
+pub struct SharedPreimageCacheScope {
+    capacity_bytes: usize,
+    max_batch_ranges: usize,
+    batch: Mutex<Option<RangeBatch>>,
+}
+
+#[derive(Debug)]
+struct RangeBatch {
+    start_block: u64,
+    end_block: u64,
+    num_ranges: usize,
+    cache: SharedPreimageCache,
+}
+
+impl SharedPreimageCacheScope {
+    /// Create a scope whose batches hold up to `max_batch_ranges` ranges sharing a cache of
+    /// `capacity_bytes`.
+    pub fn new(capacity_bytes: usize, max_batch_ranges: usize) -> Self {
+        Self { capacity_bytes, max_batch_ranges, batch: Mutex::new(None) }
+    }
+
+    /// Create a scope with the cache capacity set by `SHARED_PREIMAGE_CACHE_BYTES`. See
+    /// [SharedPreimageCache::from_env].
+    pub fn from_env(max_batch_ranges: usize) -> Result<Self> {
+        Ok(Self::new(capacity_from_env()?, max_batch_ranges))
+    }
+
+    /// The cache of the batch the range `start_block..end_block` belongs to.
+    pub fn cache_for_range(&self, start_block: u64, end_block: u64) -> SharedPreimageCache {
+        let mut batch = self.batch.lock().unwrap();
+        match batch.as_mut() {
+            Some(current)
+                if (current.start_block..=current.end_block).contains(&start_block) &&
+                    current.num_ranges < self.max_batch_ranges =>
+            {
+                current.end_block = current.end_block.max(end_block);
+                current.num_ranges += 1;
+                current.cache.clone()
+            }
+            _ => {
+                let cache = SharedPreimageCache::new(self.capacity_bytes);
+                *batch = Some(RangeBatch {
+                    start_block,
+                    end_block,
+                    num_ranges: 1,
+                    cache: cache.clone(),
+                });
+                cache
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+
+    fn keccak_key(value: &[u8]) -> PreimageKey {
+        PreimageKey::new(keccak256(value).0, PreimageKeyType::Keccak256)
+    }
+
+    #[test]
+    fn test_shared_preimage_cache_skips_local_keys() {
+        let cache = SharedPreimageCache::new(1024);
+        let local_key = PreimageKey::new_local(1);
+        cache.insert(local_key, b"l1 head");
+        cache.insert(keccak_key(b"node"), b"node");
+
+        assert_eq!(cache.get(&local_key), None);
+        assert_eq!(cache.get(&keccak_key(b"node")), Some(b"node".to_vec()));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_shared_preimage_cache_evicts_least_recently_used() {
+        let cache = SharedPreimageCache::new(8);
+        let (a, b, c) = (b"aaaa", b"bbbb", b"cccc");
+        cache.insert(keccak_key(a), a);
+        cache.insert(keccak_key(b), b);
+
+        // Reading `a` makes `b` the least recently used preimage.
+        assert!(cache.get(&keccak_key(a)).is_some());
+        cache.insert(keccak_key(c), c);
+
+        assert!(cache.get(&keccak_key(a)).is_some());
+        assert_eq!(cache.get(&keccak_key(b)), None);
+        assert!(cache.get(&keccak_key(c)).is_some());
+        assert_eq!(cache.size_bytes(), 8);
+
+        // Preimages larger than the capacity are not cached.
+        cache.insert(keccak_key(&[0u8; 9]), &[0u8; 9]);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_shared_preimage_cache_scope_batches_adjacent_ranges() {
+        let scope = SharedPreimageCacheScope::new(1024, 3);
+        let first = scope.cache_for_range(100, 110);
+        first.insert(keccak_key(b"node"), b"node");
+
+        // Adjacent ranges, and retries of ranges in the batch, share its cache.
+        assert_eq!(scope.cache_for_range(110, 120).len(), 1);
+        assert_eq!(scope.cache_for_range(100, 110).len(), 1);
+
+        // The batch is full, so the next adjacent range starts a new one.
+        let second = scope.cache_for_range(120, 130);
+        assert!(second.is_empty());
+        second.insert(keccak_key(b"node"), b"node");
+
+        // A range that isn't adjacent to the batch starts a new one.
+        assert!(scope.cache_for_range(200, 210).is_empty());
+    }
+}
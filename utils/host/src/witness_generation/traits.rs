@@ -15,7 +15,7 @@ use op_succinct_client_utils::witness::{
 };
 use sp1_sdk::SP1Stdin;
 
-use crate::witness_generation::{OnlineBlobStore, PreimageWitnessCollector};
+use crate::witness_generation::{OnlineBlobStore, PreimageWitnessCollector, WitnessGenOptions};
 
 pub type DefaultOracleBase = CachingOracle<OracleReader<NativeChannel>, HintWriter<NativeChannel>>;
 
@@ -32,10 +32,14 @@ pub trait WitnessGenerator {
 
     fn get_executor(&self) -> &Self::WitnessExecutor;
 
+    /// Run the client program against the host, collecting the preimages it reads into the
+    /// witness. Preimages in the options' shared cache are served without a request to the host,
+    /// and the run fails once the collected preimages exceed `max_preimage_bytes`.
     async fn run(
         &self,
         preimage_chan: NativeChannel,
         hint_chan: NativeChannel,
+        options: &WitnessGenOptions,
     ) -> Result<Self::WitnessData> {
         let preimage_witness_store = Arc::new(Mutex::new(PreimageStore::default()));
         let blob_data = Arc::new(Mutex::new(BlobData::default()));
@@ -47,13 +51,17 @@ pub trait WitnessGenerator {
         ));
         let blob_provider = OracleBlobProvider::new(preimage_oracle.clone());
 
-        let oracle = Arc::new(PreimageWitnessCollector {
-            preimage_oracle: preimage_oracle.clone(),
-            preimage_witness_store: preimage_witness_store.clone(),
-        });
+        let oracle = Arc::new(PreimageWitnessCollector::new(
+            preimage_oracle.clone(),
+            preimage_witness_store.clone(),
+            options.shared_cache.clone(),
+            options.max_preimage_bytes,
+        ));
         let beacon = OnlineBlobStore { provider: blob_provider.clone(), store: blob_data.clone() };
 
-        let (boot_info, input) = get_inputs_for_pipeline(oracle.clone()).await?;
+        let (boot_info, input) = get_inputs_for_pipeline(oracle.clone())
+            .await
+            .map_err(|e| oracle.map_limit_error(e.into()))?;
         if let Some((cursor, l1_provider, l2_provider)) = input {
             let rollup_config = Arc::new(boot_info.rollup_config.clone());
             let l1_config = Arc::new(boot_info.l1_config.clone());
@@ -69,8 +77,11 @@ pub trait WitnessGenerator {
                     l2_provider.clone(),
                 )
                 .await
-                .unwrap();
-            self.get_executor().run(boot_info, pipeline, cursor, l2_provider).await?;
+                .map_err(|e| oracle.map_limit_error(e.into()))?;
+            self.get_executor()
+                .run(boot_info, pipeline, cursor, l2_provider)
+                .await
+                .map_err(|e| oracle.map_limit_error(e.into()))?;
         }
 
         let witness = Self::WitnessData::from_parts(
@@ -88,14 +88,21 @@ pub fn is_cluster_mode() -> bool {
 /// internally, which would panic if called directly from an async context.
 pub async fn cluster_setup_keys(
 ) -> Result<(SP1ProvingKey, SP1VerifyingKey, SP1ProvingKey, SP1VerifyingKey)> {
-    tokio::task::spawn_blocking(|| {
+    cluster_setup_program_keys(get_range_elf_embedded(), AGGREGATION_ELF).await
+}
+
+/// Set up proving/verifying keys for an arbitrary range and aggregation ELF pair via blocking
+/// CpuProver. See [`cluster_setup_keys`].
+pub async fn cluster_setup_program_keys(
+    range_elf: &'static [u8],
+    agg_elf: &'static [u8],
+) -> Result<(SP1ProvingKey, SP1VerifyingKey, SP1ProvingKey, SP1VerifyingKey)> {
+    tokio::task::spawn_blocking(move || {
         let cpu_prover = CpuProver::new();
-        let range_pk = cpu_prover
-            .setup(Elf::Static(get_range_elf_embedded()))
-            .context("range ELF setup failed")?;
+        let range_pk =
+            cpu_prover.setup(Elf::Static(range_elf)).context("range ELF setup failed")?;
         let range_vk = range_pk.verifying_key().clone();
-        let agg_pk =
-            cpu_prover.setup(Elf::Static(AGGREGATION_ELF)).context("agg ELF setup failed")?;
+        let agg_pk = cpu_prover.setup(Elf::Static(agg_elf)).context("agg ELF setup failed")?;
         let agg_vk = agg_pk.verifying_key().clone();
         anyhow::Ok((range_pk, range_vk, agg_pk, agg_vk))
     })
@@ -137,14 +144,16 @@ pub async fn cluster_range_proof(
     timeout_secs: u64,
     stdin: SP1Stdin,
 ) -> Result<SP1ProofWithPublicValues> {
-    cluster_proof_blocking(
-        timeout_secs,
-        ProofMode::Compressed,
-        get_range_elf_embedded(),
-        stdin,
-        "range",
-    )
-    .await
+    cluster_program_range_proof(timeout_secs, get_range_elf_embedded(), stdin).await
+}
+
+/// Generate a compressed range proof for `range_elf` via a self-hosted SP1 cluster.
+pub async fn cluster_program_range_proof(
+    timeout_secs: u64,
+    range_elf: &[u8],
+    stdin: SP1Stdin,
+) -> Result<SP1ProofWithPublicValues> {
+    cluster_proof_blocking(timeout_secs, ProofMode::Compressed, range_elf, stdin, "range").await
 }
 
 /// Generate an aggregation proof via a self-hosted SP1 cluster.
@@ -152,11 +161,21 @@ pub async fn cluster_agg_proof(
     timeout_secs: u64,
     agg_mode: SP1ProofMode,
     stdin: SP1Stdin,
+) -> Result<SP1ProofWithPublicValues> {
+    cluster_program_agg_proof(timeout_secs, AGGREGATION_ELF, agg_mode, stdin).await
+}
+
+/// Generate an aggregation proof for `agg_elf` via a self-hosted SP1 cluster.
+pub async fn cluster_program_agg_proof(
+    timeout_secs: u64,
+    agg_elf: &[u8],
+    agg_mode: SP1ProofMode,
+    stdin: SP1Stdin,
 ) -> Result<SP1ProofWithPublicValues> {
     cluster_proof_blocking(
         timeout_secs,
         to_proto_proof_mode(agg_mode),
-        AGGREGATION_ELF,
+        agg_elf,
         stdin,
         "aggregation",
     )
@@ -223,7 +242,7 @@ pub struct ClusterProofHandle {
 }
 
 /// JSON representation stored in the `cluster_proof_handle` JSONB column.
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ClusterProofHandleJson {
     pub proof_id: String,
     pub proof_output_id: String,
@@ -289,21 +308,25 @@ impl ClusterProofConfig {
     }
 }
 
-/// Submit a range proof to the cluster. Returns immediately with a `ProofRequest` handle.
+/// Submit a range proof for `range_elf` to the cluster. Returns immediately with a
+/// `ProofRequest` handle.
 pub async fn cluster_submit_range_proof(
     config: &ClusterProofConfig,
+    range_elf: &[u8],
     timeout_secs: u64,
     stdin: SP1Stdin,
 ) -> Result<ProofRequest> {
     tracing::info!("Submitting range proof to cluster");
     let req_config = config.build_request_config(ProofMode::Compressed, timeout_secs);
-    let cluster_elf = ClusterElf::NewElf(get_range_elf_embedded().to_vec());
+    let cluster_elf = ClusterElf::NewElf(range_elf.to_vec());
     config.artifact_store.create_request(cluster_elf, stdin, &req_config).await
 }
 
-/// Submit an aggregation proof to the cluster. Returns immediately with a `ProofRequest` handle.
+/// Submit an aggregation proof for `agg_elf` to the cluster. Returns immediately with a
+/// `ProofRequest` handle.
 pub async fn cluster_submit_agg_proof(
     config: &ClusterProofConfig,
+    agg_elf: &[u8],
     timeout_secs: u64,
     agg_mode: SP1ProofMode,
     stdin: SP1Stdin,
@@ -311,7 +334,7 @@ pub async fn cluster_submit_agg_proof(
     tracing::info!("Submitting aggregation proof to cluster");
     let proto_mode = to_proto_proof_mode(agg_mode);
     let req_config = config.build_request_config(proto_mode, timeout_secs);
-    let cluster_elf = ClusterElf::NewElf(AGGREGATION_ELF.to_vec());
+    let cluster_elf = ClusterElf::NewElf(agg_elf.to_vec());
     config.artifact_store.create_request(cluster_elf, stdin, &req_config).await
 }
 
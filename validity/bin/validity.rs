@@ -26,6 +26,10 @@ struct Args {
     /// Path to environment file
     #[arg(long, default_value = ".env")]
     env_file: String,
+
+    /// Reconcile the database with the outputs proposed on-chain and exit.
+    #[arg(long)]
+    reconcile: bool,
 }
 
 #[tokio::main]
@@ -71,6 +75,7 @@ async fn main() -> Result<()> {
         agg_proof_strategy: env_config.agg_proof_strategy,
         agg_proof_mode: env_config.agg_proof_mode,
         submission_interval: env_config.submission_interval,
+        max_pipelined_agg_proofs: env_config.max_pipelined_agg_proofs,
         mock: env_config.mock,
         safe_db_fallback: env_config.safe_db_fallback,
         op_succinct_config_name_hash,
@@ -86,6 +91,8 @@ async fn main() -> Result<()> {
         min_auction_period: env_config.min_auction_period,
         auction_timeout: env_config.auction_timeout,
         tx_confirmation_timeout: env_config.tx_confirmation_timeout,
+        program_schedule_file: env_config.program_schedule_file,
+        ledger_path: env_config.ledger_path,
     };
     proposer_config.log();
 
@@ -104,6 +111,11 @@ async fn main() -> Result<()> {
     )
     .await?;
 
+    if args.reconcile {
+        proposer.reconcile_with_contract().await?;
+        return Ok(());
+    }
+
     // Spawn a thread for the proposer.
     info!("Starting proposer.");
     let proposer_handle = tokio::spawn(async move {
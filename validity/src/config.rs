@@ -1,11 +1,24 @@
 use alloy_primitives::{Address, B256};
 use alloy_provider::Provider;
+use anyhow::{anyhow, Result};
+use op_succinct_client_utils::types::u32_to_u8;
+use op_succinct_elfs::load_elf;
 use op_succinct_host_utils::{
     DisputeGameFactory::DisputeGameFactoryInstance as DisputeGameFactoryContract,
     OPSuccinctL2OutputOracle::OPSuccinctL2OutputOracleInstance as OPSuccinctL2OOContract,
 };
-use sp1_sdk::{network::FulfillmentStrategy, SP1ProofMode, SP1ProvingKey, SP1VerifyingKey};
-use std::sync::Arc;
+use op_succinct_proof_utils::cluster_setup_program_keys;
+use serde::{Deserialize, Serialize};
+use sp1_sdk::{
+    network::FulfillmentStrategy, Elf, HashableKey, NetworkProver, Prover, ProvingKey,
+    SP1ProofMode, SP1ProvingKey, SP1VerifyingKey,
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 
 pub struct ContractConfig<P>
 where
@@ -17,15 +30,26 @@ where
     pub dgf_contract: DisputeGameFactoryContract<P>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CommitmentConfig {
     pub range_vkey_commitment: B256,
     pub agg_vkey_hash: B256,
     pub rollup_config_hash: B256,
 }
 
+impl CommitmentConfig {
+    /// Whether range proofs generated under `other` can be used under `self`. Range proofs only
+    /// commit to the range vkey and the rollup config hash.
+    pub fn same_range_program(&self, other: &CommitmentConfig) -> bool {
+        self.range_vkey_commitment == other.range_vkey_commitment &&
+            self.rollup_config_hash == other.rollup_config_hash
+    }
+}
+
 #[derive(Clone)]
 pub struct ProgramConfig {
+    pub range_elf: &'static [u8],
+    pub agg_elf: &'static [u8],
     pub range_vk: Arc<SP1VerifyingKey>,
     pub range_pk: Arc<SP1ProvingKey>,
     pub agg_vk: Arc<SP1VerifyingKey>,
@@ -34,6 +58,45 @@ pub struct ProgramConfig {
 }
 
 impl ProgramConfig {
+    /// Set up the proving keys for a range and aggregation ELF pair and derive the commitments.
+    ///
+    /// Uses the network prover when one is provided, otherwise sets up the keys locally for
+    /// cluster mode.
+    pub async fn setup(
+        network_prover: Option<&NetworkProver>,
+        range_elf: &'static [u8],
+        agg_elf: &'static [u8],
+        rollup_config_hash: B256,
+    ) -> Result<Self> {
+        let (range_pk, range_vk, agg_pk, agg_vk) = match network_prover {
+            Some(network_prover) => {
+                let range_pk = network_prover.setup(Elf::Static(range_elf)).await?;
+                let range_vk = range_pk.verifying_key().clone();
+                let agg_pk = network_prover.setup(Elf::Static(agg_elf)).await?;
+                let agg_vk = agg_pk.verifying_key().clone();
+                (range_pk, range_vk, agg_pk, agg_vk)
+            }
+            None => cluster_setup_program_keys(range_elf, agg_elf).await?,
+        };
+
+        let range_vkey_commitment = B256::from(u32_to_u8(range_vk.vk.hash_u32()));
+        let agg_vkey_hash = B256::from_str(&agg_vk.bytes32())?;
+
+        Ok(Self {
+            range_elf,
+            agg_elf,
+            range_vk: Arc::new(range_vk),
+            range_pk: Arc::new(range_pk),
+            agg_vk: Arc::new(agg_vk),
+            agg_pk: Arc::new(agg_pk),
+            commitments: CommitmentConfig {
+                range_vkey_commitment,
+                agg_vkey_hash,
+                rollup_config_hash,
+            },
+        })
+    }
+
     pub fn log(&self) {
         tracing::info!(
             range_vkey_commitment = %self.commitments.range_vkey_commitment,
@@ -44,6 +107,219 @@ impl ProgramConfig {
     }
 }
 
+/// When a scheduled program takes over proving from the previous one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgramActivation {
+    /// The program proves every L2 block from the given block number onwards.
+    Block(u64),
+    /// The program takes over once the L2OO config matches its commitments. The activation block
+    /// is resolved at runtime to the block after the latest proposed block.
+    OnChainVkeys,
+}
+
+/// An entry of the JSON file pointed to by `PROGRAM_SCHEDULE_FILE`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProgramScheduleEntry {
+    pub activation: ProgramActivation,
+    pub range_elf_path: PathBuf,
+    pub aggregation_elf_path: PathBuf,
+    /// Defaults to the hash of the rollup config the proposer was started with.
+    #[serde(default)]
+    pub rollup_config_hash: Option<B256>,
+}
+
+impl ProgramScheduleEntry {
+    /// Read the schedule entries from a JSON file.
+    pub fn read_all(path: &Path) -> Result<Vec<Self>> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| anyhow!("Failed to read program schedule {}: {e}", path.display()))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| anyhow!("Failed to parse program schedule {}: {e}", path.display()))
+    }
+
+    /// Load the ELFs of the entry.
+    pub fn load_elfs(&self) -> Result<(&'static [u8], &'static [u8])> {
+        let read = |path: &Path| -> Result<&'static [u8]> {
+            load_elf(path).map_err(|e| anyhow!("Failed to read ELF {}: {e}", path.display()))
+        };
+        Ok((read(&self.range_elf_path)?, read(&self.aggregation_elf_path)?))
+    }
+}
+
+impl AsRef<CommitmentConfig> for ProgramConfig {
+    fn as_ref(&self) -> &CommitmentConfig {
+        &self.commitments
+    }
+}
+
+impl AsRef<CommitmentConfig> for CommitmentConfig {
+    fn as_ref(&self) -> &CommitmentConfig {
+        self
+    }
+}
+
+pub struct ScheduledProgram<C = ProgramConfig> {
+    pub activation: ProgramActivation,
+    pub config: C,
+}
+
+/// The ordered set of programs the proposer can prove with.
+///
+/// Every L2 block is proven by exactly one program: the one with the highest activation block
+/// that is not above it. Programs activated by an on-chain vkey change only take part once the
+/// L2OO config has been observed to match them.
+pub struct ProgramSchedule<C = ProgramConfig> {
+    programs: Vec<ScheduledProgram<C>>,
+    /// Activation blocks of `OnChainVkeys` programs, keyed by index in `programs`.
+    resolved_activations: RwLock<HashMap<usize, u64>>,
+}
+
+impl<C: AsRef<CommitmentConfig> + Clone> ProgramSchedule<C> {
+    pub fn new(programs: Vec<ScheduledProgram<C>>) -> Result<Self> {
+        if !programs.iter().any(|p| p.activation == ProgramActivation::Block(0)) {
+            return Err(anyhow!("The program schedule must contain a program active from block 0."));
+        }
+
+        for (i, program) in programs.iter().enumerate() {
+            if programs[..i].iter().any(|p| p.config.as_ref() == program.config.as_ref()) {
+                return Err(anyhow!("Duplicate program in schedule: {:?}", program.config.as_ref()));
+            }
+        }
+
+        Ok(Self { programs, resolved_activations: RwLock::new(HashMap::new()) })
+    }
+
+    fn activation_block(&self, index: usize) -> Option<u64> {
+        match self.programs[index].activation {
+            ProgramActivation::Block(block) => Some(block),
+            ProgramActivation::OnChainVkeys => {
+                self.resolved_activations.read().unwrap().get(&index).copied()
+            }
+        }
+    }
+
+    /// Index of the program proving `block`.
+    pub fn index_for_block(&self, block: u64) -> usize {
+        let mut best: Option<(usize, u64)> = None;
+        for index in 0..self.programs.len() {
+            if let Some(activation) = self.activation_block(index) {
+                if activation <= block && best.is_none_or(|(_, b)| activation >= b) {
+                    best = Some((index, activation));
+                }
+            }
+        }
+        best.map(|(index, _)| index).expect("schedule contains a program active from block 0")
+    }
+
+    /// The program proving `block`.
+    pub fn program_for_block(&self, block: u64) -> C {
+        self.programs[self.index_for_block(block)].config.clone()
+    }
+
+    /// The program proving the range `(start_block, end_block]`. Ranges never cross an activation
+    /// boundary, so this is the program of the first block in the range.
+    pub fn program_for_range(&self, start_block: i64) -> C {
+        self.program_for_block(start_block as u64 + 1)
+    }
+
+    /// The last block proven by the program proving `block`, if a later program is scheduled.
+    pub fn last_block_of_program_for(&self, block: u64) -> Option<u64> {
+        self.split_points().into_iter().map(|point| point as u64).find(|&point| point >= block)
+    }
+
+    /// The range boundaries implied by the activation blocks. A range must end at a split point
+    /// if it would otherwise cover blocks proven by two different programs.
+    pub fn split_points(&self) -> Vec<i64> {
+        let mut points: Vec<i64> = (0..self.programs.len())
+            .filter_map(|index| self.activation_block(index))
+            .filter(|&block| block > 0)
+            .map(|block| block as i64 - 1)
+            .collect();
+        points.sort_unstable();
+        points.dedup();
+        points
+    }
+
+    /// All scheduled programs, in schedule order.
+    pub fn programs(&self) -> impl Iterator<Item = &C> {
+        self.programs.iter().map(|p| &p.config)
+    }
+
+    /// The distinct range commitments of the schedule. Requests are tagged with these, so this is
+    /// the set to query when looking for requests of any scheduled program.
+    pub fn range_commitments(&self) -> Vec<CommitmentConfig> {
+        let mut commitments: Vec<CommitmentConfig> = Vec::new();
+        for program in self.programs() {
+            if !commitments.iter().any(|c| c.same_range_program(program.as_ref())) {
+                commitments.push(program.as_ref().clone());
+            }
+        }
+        commitments
+    }
+
+    /// Find the program a request was created for.
+    pub fn program_for_commitments(
+        &self,
+        range_vkey_commitment: B256,
+        rollup_config_hash: B256,
+        agg_vkey_hash: Option<B256>,
+    ) -> Option<C> {
+        self.programs()
+            .find(|p| {
+                let commitments = p.as_ref();
+                commitments.range_vkey_commitment == range_vkey_commitment &&
+                    commitments.rollup_config_hash == rollup_config_hash &&
+                    agg_vkey_hash.is_none_or(|hash| commitments.agg_vkey_hash == hash)
+            })
+            .cloned()
+    }
+
+    /// Match the L2OO config against the schedule. If it matches an `OnChainVkeys` program that
+    /// has not been activated yet, the program is activated from the block after
+    /// `latest_proposed_block_number`.
+    ///
+    /// Returns the index of the matching program, if any.
+    pub fn observe_on_chain_config(
+        &self,
+        on_chain: &CommitmentConfig,
+        latest_proposed_block_number: u64,
+    ) -> Option<usize> {
+        let index = self.programs.iter().position(|p| p.config.as_ref() == on_chain)?;
+
+        if self.programs[index].activation == ProgramActivation::OnChainVkeys {
+            let mut resolved = self.resolved_activations.write().unwrap();
+            if !resolved.contains_key(&index) {
+                let activation_block = latest_proposed_block_number + 1;
+                tracing::info!(
+                    program_index = index,
+                    activation_block,
+                    range_vkey_commitment = %on_chain.range_vkey_commitment,
+                    agg_vkey_hash = %on_chain.agg_vkey_hash,
+                    "On-chain config switched to a scheduled program, activating it"
+                );
+                resolved.insert(index, activation_block);
+            }
+        }
+
+        Some(index)
+    }
+
+    pub fn log(&self) {
+        for (index, program) in self.programs.iter().enumerate() {
+            let commitments = program.config.as_ref();
+            tracing::info!(
+                program_index = index,
+                activation = ?program.activation,
+                range_vkey_commitment = %commitments.range_vkey_commitment,
+                agg_vkey_hash = %commitments.agg_vkey_hash,
+                rollup_config_hash = %commitments.rollup_config_hash,
+                "Scheduled program loaded"
+            );
+        }
+    }
+}
+
 pub struct RequesterConfig {
     pub l1_chain_id: i64,
     pub l2_chain_id: i64,
@@ -55,6 +331,9 @@ pub struct RequesterConfig {
     /// The number of blocks in each range proof. Used when gas_limit is 0.
     pub range_proof_interval: u64,
     pub submission_interval: u64,
+    /// The maximum number of pending aggregation proofs the next aggregation proof may chain on.
+    /// If 0, an aggregation proof is only created once the previous one has been relayed.
+    pub max_pipelined_agg_proofs: u64,
     pub max_concurrent_witness_gen: u64,
     pub max_concurrent_proof_requests: u64,
     pub range_proof_strategy: FulfillmentStrategy,
@@ -108,6 +387,14 @@ pub struct RequesterConfig {
     /// signer behavior; raise it (e.g. 180) on networks where mempool inclusion plus the
     /// configured confirmation depth needs more headroom.
     pub tx_confirmation_timeout: u64,
+
+    /// Path to a JSON file with additional programs to schedule after the embedded one, e.g. for
+    /// an upcoming hardfork or ELF upgrade.
+    pub program_schedule_file: Option<PathBuf>,
+
+    /// Optional path to the ledger of bonds and gas per relayed aggregation proof. A CSV export
+    /// is written alongside it.
+    pub ledger_path: Option<PathBuf>,
 }
 
 impl RequesterConfig {
@@ -121,6 +408,7 @@ impl RequesterConfig {
             evm_gas_limit = self.evm_gas_limit,
             range_proof_interval = self.range_proof_interval,
             submission_interval = self.submission_interval,
+            max_pipelined_agg_proofs = self.max_pipelined_agg_proofs,
             max_concurrent_witness_gen = self.max_concurrent_witness_gen,
             max_concurrent_proof_requests = self.max_concurrent_proof_requests,
             range_proof_strategy = ?self.range_proof_strategy,
@@ -141,7 +429,104 @@ impl RequesterConfig {
             min_auction_period = self.min_auction_period,
             auction_timeout = self.auction_timeout,
             tx_confirmation_timeout = self.tx_confirmation_timeout,
+            program_schedule_file = ?self.program_schedule_file,
+            ledger_path = ?self.ledger_path,
             "Validity proposer configuration loaded"
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commitments(byte: u8) -> CommitmentConfig {
+        CommitmentConfig {
+            range_vkey_commitment: B256::repeat_byte(byte),
+            agg_vkey_hash: B256::repeat_byte(byte),
+            rollup_config_hash: B256::ZERO,
+        }
+    }
+
+    /// Programs 1, 2 and 3, active from blocks 0, 100 and 200, and program 4 waiting for the
+    /// on-chain vkeys.
+    fn schedule() -> ProgramSchedule<CommitmentConfig> {
+        ProgramSchedule::new(vec![
+            ScheduledProgram { activation: ProgramActivation::Block(0), config: commitments(1) },
+            ScheduledProgram { activation: ProgramActivation::Block(200), config: commitments(3) },
+            ScheduledProgram { activation: ProgramActivation::Block(100), config: commitments(2) },
+            ScheduledProgram {
+                activation: ProgramActivation::OnChainVkeys,
+                config: commitments(4),
+            },
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_schedule_requires_genesis_program() {
+        let result = ProgramSchedule::new(vec![ScheduledProgram {
+            activation: ProgramActivation::Block(1),
+            config: commitments(1),
+        }]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_schedule_rejects_duplicates() {
+        let result = ProgramSchedule::new(vec![
+            ScheduledProgram { activation: ProgramActivation::Block(0), config: commitments(1) },
+            ScheduledProgram { activation: ProgramActivation::Block(10), config: commitments(1) },
+        ]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_index_for_block() {
+        let schedule = schedule();
+        assert_eq!(schedule.index_for_block(0), 0);
+        assert_eq!(schedule.index_for_block(99), 0);
+        assert_eq!(schedule.index_for_block(100), 2);
+        assert_eq!(schedule.index_for_block(199), 2);
+        assert_eq!(schedule.index_for_block(200), 1);
+        assert_eq!(schedule.index_for_block(u64::MAX), 1);
+    }
+
+    #[test]
+    fn test_split_points() {
+        let schedule = schedule();
+        assert_eq!(schedule.split_points(), vec![99, 199]);
+    }
+
+    #[test]
+    fn test_last_block_of_program_for() {
+        let schedule = schedule();
+        assert_eq!(schedule.last_block_of_program_for(0), Some(99));
+        assert_eq!(schedule.last_block_of_program_for(99), Some(99));
+        assert_eq!(schedule.last_block_of_program_for(100), Some(199));
+        assert_eq!(schedule.last_block_of_program_for(200), None);
+    }
+
+    #[test]
+    fn test_observe_on_chain_config() {
+        let schedule = schedule();
+
+        // Unknown configs match no program.
+        assert_eq!(schedule.observe_on_chain_config(&commitments(9), 250), None);
+
+        // Block-activated programs match without changing the schedule.
+        assert_eq!(schedule.observe_on_chain_config(&commitments(2), 250), Some(2));
+        assert_eq!(schedule.index_for_block(300), 1);
+
+        // The on-chain vkey program activates from the block after the latest proposal.
+        assert_eq!(schedule.observe_on_chain_config(&commitments(4), 250), Some(3));
+        assert_eq!(schedule.index_for_block(250), 1);
+        assert_eq!(schedule.index_for_block(251), 3);
+        assert_eq!(schedule.split_points(), vec![99, 199, 250]);
+        assert_eq!(schedule.last_block_of_program_for(200), Some(250));
+
+        // Later observations keep the first activation block.
+        assert_eq!(schedule.observe_on_chain_config(&commitments(4), 400), Some(3));
+        assert_eq!(schedule.index_for_block(251), 3);
+    }
+}
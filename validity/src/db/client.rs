@@ -357,6 +357,39 @@ impl DriverDBClient {
         Ok(result.count.unwrap_or(0))
     }
 
+    /// Fetch the active (non-failed, non-cancelled) Aggregation proofs with a start block >= the
+    /// given block, range vkey commitment, and aggregation vkey, sorted by start block.
+    pub async fn fetch_active_agg_proofs_from_block(
+        &self,
+        start_block: i64,
+        commitment: &CommitmentConfig,
+        l1_chain_id: i64,
+        l2_chain_id: i64,
+    ) -> Result<Vec<OPSuccinctRequest>, Error> {
+        let status_values: Vec<i16> = vec![
+            RequestStatus::Unrequested as i16,
+            RequestStatus::WitnessGeneration as i16,
+            RequestStatus::Execution as i16,
+            RequestStatus::Prove as i16,
+            RequestStatus::Complete as i16,
+        ];
+        let requests = sqlx::query_as!(
+            OPSuccinctRequest,
+            "SELECT * FROM requests WHERE range_vkey_commitment = $1 AND rollup_config_hash = $2 AND aggregation_vkey_hash = $3 AND status = ANY($4) AND req_type = $5 AND start_block >= $6 AND l1_chain_id = $7 AND l2_chain_id = $8 ORDER BY start_block ASC",
+            &commitment.range_vkey_commitment[..],
+            &commitment.rollup_config_hash[..],
+            &commitment.agg_vkey_hash[..],
+            &status_values[..],
+            RequestType::Aggregation as i16,
+            start_block,
+            l1_chain_id,
+            l2_chain_id,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(requests)
+    }
+
     /// Fetch the sorted list of Aggregation proofs with status Unrequested that have a start_block
     /// >= latest_contract_l2_block.
     ///
@@ -591,6 +624,65 @@ impl DriverDBClient {
         .await
     }
 
+    /// Fetch the relayed Aggregation request with the highest end block.
+    pub async fn fetch_latest_relayed_agg_request(
+        &self,
+        l1_chain_id: i64,
+        l2_chain_id: i64,
+    ) -> Result<Option<OPSuccinctRequest>, Error> {
+        let request = sqlx::query_as!(
+            OPSuccinctRequest,
+            "SELECT * FROM requests WHERE status = $1 AND req_type = $2 AND relay_tx_hash IS NOT NULL AND l1_chain_id = $3 AND l2_chain_id = $4 ORDER BY end_block DESC LIMIT 1",
+            RequestStatus::Relayed as i16,
+            RequestType::Aggregation as i16,
+            l1_chain_id,
+            l2_chain_id,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(request)
+    }
+
+    /// Fetch the completed Aggregation requests with an end block <= the given block, sorted by
+    /// end block. These are either relayed without the database being updated, or obsolete.
+    pub async fn fetch_completed_agg_requests_up_to_block(
+        &self,
+        end_block: i64,
+        l1_chain_id: i64,
+        l2_chain_id: i64,
+    ) -> Result<Vec<OPSuccinctRequest>, Error> {
+        let requests = sqlx::query_as!(
+            OPSuccinctRequest,
+            "SELECT * FROM requests WHERE status = $1 AND req_type = $2 AND end_block <= $3 AND l1_chain_id = $4 AND l2_chain_id = $5 ORDER BY end_block ASC",
+            RequestStatus::Complete as i16,
+            RequestType::Aggregation as i16,
+            end_block,
+            l1_chain_id,
+            l2_chain_id,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(requests)
+    }
+
+    /// Fetch the relayed requests with a relay transaction hash but no contract address.
+    pub async fn fetch_relayed_requests_without_contract_address(
+        &self,
+        l1_chain_id: i64,
+        l2_chain_id: i64,
+    ) -> Result<Vec<OPSuccinctRequest>, Error> {
+        let requests = sqlx::query_as!(
+            OPSuccinctRequest,
+            "SELECT * FROM requests WHERE status = $1 AND relay_tx_hash IS NOT NULL AND contract_address IS NULL AND l1_chain_id = $2 AND l2_chain_id = $3 ORDER BY end_block ASC",
+            RequestStatus::Relayed as i16,
+            l1_chain_id,
+            l2_chain_id,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(requests)
+    }
+
     /// Fetch a single completed aggregation proof after the given start block.
     pub async fn fetch_completed_agg_proof_after_block(
         &self,
@@ -676,21 +768,24 @@ impl DriverDBClient {
         .await
     }
 
-    /// Cancel all prove requests with a different commitment config and same chain id's
+    /// Cancel all prove requests with the same chain id's whose range vkey commitment and rollup
+    /// config hash match none of the given commitment configs.
     pub async fn cancel_prove_requests_with_different_commitment_config(
         &self,
-        commitment: &CommitmentConfig,
+        commitments: &[CommitmentConfig],
         l1_chain_id: i64,
         l2_chain_id: i64,
     ) -> Result<PgQueryResult, Error> {
+        let range_vkey_commitments: Vec<Vec<u8>> =
+            commitments.iter().map(|c| c.range_vkey_commitment.to_vec()).collect();
+        let rollup_config_hashes: Vec<Vec<u8>> =
+            commitments.iter().map(|c| c.rollup_config_hash.to_vec()).collect();
         sqlx::query!(
-            r#"
-            UPDATE requests SET status = $1 WHERE status = $2 AND (range_vkey_commitment != $3 OR rollup_config_hash != $4) AND l1_chain_id = $5 AND l2_chain_id = $6
-            "#,
+            "UPDATE requests SET status = $1 WHERE status = $2 AND NOT EXISTS (SELECT 1 FROM UNNEST($3::bytea[], $4::bytea[]) AS c(range_vkey_commitment, rollup_config_hash) WHERE c.range_vkey_commitment = requests.range_vkey_commitment AND c.rollup_config_hash = requests.rollup_config_hash) AND l1_chain_id = $5 AND l2_chain_id = $6",
             RequestStatus::Cancelled as i16,
             RequestStatus::Prove as i16,
-            &commitment.range_vkey_commitment[..],
-            &commitment.rollup_config_hash[..],
+            &range_vkey_commitments[..],
+            &rollup_config_hashes[..],
             l1_chain_id,
             l2_chain_id,
         )
@@ -1138,6 +1233,62 @@ mod tests {
         assert_eq!(count, 5);
     }
 
+    #[tokio::test]
+    async fn test_fetch_active_agg_proofs_from_block_returns_pending_chain() {
+        let db = TestDb::new().await;
+        let c = db.client();
+
+        let requests = vec![
+            agg_request(300, 400, RequestStatus::Unrequested),
+            agg_request(100, 200, RequestStatus::Complete),
+            agg_request(200, 300, RequestStatus::Prove),
+            agg_request(200, 300, RequestStatus::Failed),
+            agg_request(0, 100, RequestStatus::Relayed),
+        ];
+        insert_requests(c, &requests).await;
+
+        let result = c
+            .fetch_active_agg_proofs_from_block(100, &default_commitment(), L1ID, L2ID)
+            .await
+            .unwrap();
+
+        let ranges: Vec<_> = result.iter().map(|r| (r.start_block, r.end_block)).collect();
+        assert_eq!(ranges, vec![(100, 200), (200, 300), (300, 400)]);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_agg_requests_for_reconciliation() {
+        let db = TestDb::new().await;
+        let c = db.client();
+
+        let mut relayed = agg_request(0, 100, RequestStatus::Relayed);
+        relayed.relay_tx_hash = Some(B256::repeat_byte(0x01).to_vec());
+        let mut relayed_with_address = agg_request(100, 200, RequestStatus::Relayed);
+        relayed_with_address.relay_tx_hash = Some(B256::repeat_byte(0x02).to_vec());
+        relayed_with_address.contract_address = Some(Address::repeat_byte(0x03).to_vec());
+
+        let requests = vec![
+            relayed,
+            relayed_with_address,
+            agg_request(200, 300, RequestStatus::Complete),
+            agg_request(300, 400, RequestStatus::Complete),
+            agg_request(200, 300, RequestStatus::Failed),
+        ];
+        insert_requests(c, &requests).await;
+
+        let latest = c.fetch_latest_relayed_agg_request(L1ID, L2ID).await.unwrap().unwrap();
+        assert_eq!(latest.end_block, 200);
+
+        let completed = c.fetch_completed_agg_requests_up_to_block(300, L1ID, L2ID).await.unwrap();
+        let ranges: Vec<_> = completed.iter().map(|r| (r.start_block, r.end_block)).collect();
+        assert_eq!(ranges, vec![(200, 300)]);
+
+        let missing_address =
+            c.fetch_relayed_requests_without_contract_address(L1ID, L2ID).await.unwrap();
+        assert_eq!(missing_address.len(), 1);
+        assert_eq!(missing_address[0].end_block, 100);
+    }
+
     #[tokio::test]
     async fn test_fetch_first_unrequested_range_proof_returns_lowest_start_block() {
         let db = TestDb::new().await;
@@ -1392,7 +1543,7 @@ mod tests {
             insert_requests(c, &requests).await;
 
             c.cancel_prove_requests_with_different_commitment_config(
-                &default_commitment(),
+                &[default_commitment()],
                 L1ID,
                 L2ID,
             )
@@ -1415,6 +1566,57 @@ mod tests {
                 1
             );
         }
+
+        #[tokio::test]
+        async fn test_cancel_prove_requests_keeps_all_scheduled_commitments() {
+            let db = TestDb::new().await;
+            let c = db.client();
+
+            let scheduled_commitment = B256::repeat_byte(0x11);
+            let unknown_commitment = B256::repeat_byte(0x99);
+            let requests = vec![
+                RequestBuilder::new().range(100, 200).status(RequestStatus::Prove).build(),
+                RequestBuilder::new()
+                    .range(200, 300)
+                    .status(RequestStatus::Prove)
+                    .commitment(scheduled_commitment, B256::ZERO)
+                    .build(),
+                RequestBuilder::new()
+                    .range(300, 400)
+                    .status(RequestStatus::Prove)
+                    .commitment(unknown_commitment, B256::ZERO)
+                    .build(),
+            ];
+            insert_requests(c, &requests).await;
+
+            let scheduled = CommitmentConfig {
+                range_vkey_commitment: scheduled_commitment,
+                agg_vkey_hash: B256::ZERO,
+                rollup_config_hash: B256::ZERO,
+            };
+            c.cancel_prove_requests_with_different_commitment_config(
+                &[default_commitment(), scheduled.clone()],
+                L1ID,
+                L2ID,
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(count(c, RequestStatus::Prove).await, 1);
+            assert_eq!(
+                c.fetch_request_count(RequestStatus::Prove, &scheduled, L1ID, L2ID).await.unwrap(),
+                1
+            );
+
+            let unknown =
+                CommitmentConfig { range_vkey_commitment: unknown_commitment, ..scheduled };
+            assert_eq!(
+                c.fetch_request_count(RequestStatus::Cancelled, &unknown, L1ID, L2ID)
+                    .await
+                    .unwrap(),
+                1
+            );
+        }
     }
 
     // ==================== Commitment Filtering Tests ====================
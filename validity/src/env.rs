@@ -1,4 +1,4 @@
-use std::{env, str::FromStr};
+use std::{env, path::PathBuf, str::FromStr};
 
 use alloy_primitives::Address;
 use anyhow::Result;
@@ -24,6 +24,7 @@ pub struct EnvironmentConfig {
     pub max_concurrent_witness_gen: u64,
     pub max_concurrent_proof_requests: u64,
     pub submission_interval: u64,
+    pub max_pipelined_agg_proofs: u64,
     pub mock: bool,
     pub safe_db_fallback: bool,
     pub op_succinct_config_name: String,
@@ -39,6 +40,8 @@ pub struct EnvironmentConfig {
     pub min_auction_period: u64,
     pub auction_timeout: u64,
     pub tx_confirmation_timeout: u64,
+    pub program_schedule_file: Option<PathBuf>,
+    pub ledger_path: Option<PathBuf>,
 }
 
 /// Helper function to get environment variables with a default value and parse them.
@@ -123,6 +126,7 @@ pub async fn read_proposer_env() -> Result<EnvironmentConfig> {
         max_concurrent_witness_gen: get_env_var("MAX_CONCURRENT_WITNESS_GEN", Some(1))?,
         max_concurrent_proof_requests: get_env_var("MAX_CONCURRENT_PROOF_REQUESTS", Some(1))?,
         submission_interval: get_env_var("SUBMISSION_INTERVAL", Some(1800))?,
+        max_pipelined_agg_proofs: get_env_var("MAX_PIPELINED_AGG_PROOFS", Some(0))?,
         mock: get_env_var("OP_SUCCINCT_MOCK", Some(false))?,
         loop_interval,
         safe_db_fallback: get_env_var("SAFE_DB_FALLBACK", Some(false))?,
@@ -142,6 +146,8 @@ pub async fn read_proposer_env() -> Result<EnvironmentConfig> {
         min_auction_period: get_env_var("MIN_AUCTION_PERIOD", Some(1))?,
         auction_timeout: get_env_var("AUCTION_TIMEOUT", Some(60))?, // 1 minute
         tx_confirmation_timeout: get_env_var("TX_CONFIRMATION_TIMEOUT", Some(60))?,
+        program_schedule_file: env::var("PROGRAM_SCHEDULE_FILE").ok().map(PathBuf::from),
+        ledger_path: env::var("LEDGER_PATH").ok().map(PathBuf::from),
     };
 
     Ok(config)
@@ -53,6 +53,43 @@ pub enum ValidityGauge {
         message = "Number of proof request retries"
     )]
     ProofRequestRetryCount,
+    #[strum(
+        serialize = "succinct_active_program_index",
+        message = "Index in the program schedule of the program proving the next block to propose"
+    )]
+    ActiveProgramIndex,
+    #[strum(
+        serialize = "succinct_on_chain_program_index",
+        message = "Index in the program schedule of the program matching the on-chain config"
+    )]
+    OnChainProgramIndex,
+    #[strum(
+        serialize = "succinct_range_proofs_invalidated",
+        message = "Number of completed range proofs invalidated by a reorg before aggregation"
+    )]
+    RangeProofsInvalidated,
+    #[strum(
+        serialize = "succinct_pipelined_agg_proofs",
+        message = "Number of pending aggregation proofs the latest aggregation proof chains on"
+    )]
+    PipelinedAggProofs,
+
+    // Ledger gauges
+    #[strum(
+        serialize = "succinct_ledger_bonds_posted_eth",
+        message = "Total bonds posted when relaying aggregation proofs in ETH"
+    )]
+    LedgerBondsPostedEth,
+    #[strum(
+        serialize = "succinct_ledger_gas_spent_eth",
+        message = "Total L1 gas spent relaying aggregation proofs in ETH"
+    )]
+    LedgerGasSpentEth,
+    #[strum(
+        serialize = "succinct_ledger_proof_cost_prove",
+        message = "Total cost of the fulfilled network proof requests in PROVE"
+    )]
+    LedgerProofCostProve,
 
     // Error gauges
     #[strum(serialize = "succinct_total_error_count", message = "Number of total errors")]
@@ -96,6 +133,16 @@ pub enum ValidityGauge {
         message = "Number of network prover call timeouts"
     )]
     NetworkCallTimeoutCount,
+    #[strum(
+        serialize = "succinct_conflicting_output_root_count",
+        message = "Number of proposed output roots that conflict with the L2 node"
+    )]
+    ConflictingOutputRootCount,
+    #[strum(
+        serialize = "succinct_output_root_disagreement_count",
+        message = "Number of times the L2 nodes disagreed on an output root"
+    )]
+    OutputRootDisagreementCount,
 }
 
 impl MetricsGauge for ValidityGauge {}
@@ -7,14 +7,17 @@ use std::{
 use alloy_primitives::{Address, B256};
 use alloy_provider::Provider;
 use anyhow::{Context, Result};
-use op_succinct_elfs::AGGREGATION_ELF;
 use op_succinct_host_utils::{
-    fetcher::OPSuccinctDataFetcher, get_agg_proof_stdin, host::OPSuccinctHost,
-    metrics::MetricsGauge, witness_generation::WitnessGenerator,
+    fetcher::OPSuccinctDataFetcher,
+    get_agg_proof_stdin,
+    host::OPSuccinctHost,
+    metrics::MetricsGauge,
+    read_range_outputs,
+    witness_generation::{SharedPreimageCacheScope, WitnessGenOptions, WitnessGenerator},
 };
 use op_succinct_proof_utils::{
-    cluster_submit_agg_proof, cluster_submit_range_proof, get_range_elf_embedded,
-    ClusterProofConfig, ClusterProofHandle, ClusterProofHandleJson,
+    cluster_submit_agg_proof, cluster_submit_range_proof, ClusterProofConfig, ClusterProofHandle,
+    ClusterProofHandleJson,
 };
 use sp1_sdk::{
     network::{proto::types::ExecutionStatus, FulfillmentStrategy},
@@ -25,8 +28,8 @@ use tokio::sync::Mutex;
 use tracing::{info, warn};
 
 use crate::{
-    db::DriverDBClient, OPSuccinctRequest, ProgramConfig, RequestExecutionStatistics,
-    RequestStatus, RequestType, ValidityGauge,
+    db::DriverDBClient, OPSuccinctRequest, ProgramConfig, ProgramSchedule,
+    RequestExecutionStatistics, RequestStatus, RequestType, ValidityGauge,
 };
 
 pub struct OPSuccinctProofRequester<H: OPSuccinctHost> {
@@ -34,7 +37,7 @@ pub struct OPSuccinctProofRequester<H: OPSuccinctHost> {
     pub network_prover: Option<Arc<NetworkProver>>,
     pub fetcher: Arc<OPSuccinctDataFetcher>,
     pub db_client: Arc<DriverDBClient>,
-    pub program_config: ProgramConfig,
+    pub program_schedule: Arc<ProgramSchedule>,
     pub mock: bool,
     pub cluster: bool,
     pub cluster_config: Option<Arc<ClusterProofConfig>>,
@@ -52,6 +55,11 @@ pub struct OPSuccinctProofRequester<H: OPSuccinctHost> {
     pub whitelist: Option<Vec<Address>>,
     pub min_auction_period: u64,
     pub auction_timeout: u64,
+    /// The limits of range proof witness generation, and the preimages shared between the
+    /// witnesses of range proofs, which are usually adjacent.
+    pub witness_gen_options: WitnessGenOptions,
+    /// Shares the preimages of adjacent ranges whose witnesses are generated together.
+    pub shared_cache_scope: SharedPreimageCacheScope,
 }
 
 impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
@@ -61,7 +69,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
         network_prover: Option<Arc<NetworkProver>>,
         fetcher: Arc<OPSuccinctDataFetcher>,
         db_client: Arc<DriverDBClient>,
-        program_config: ProgramConfig,
+        program_schedule: Arc<ProgramSchedule>,
         mock: bool,
         cluster: bool,
         cluster_config: Option<Arc<ClusterProofConfig>>,
@@ -79,6 +87,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
         whitelist: Option<Vec<Address>>,
         min_auction_period: u64,
         auction_timeout: u64,
+        max_concurrent_witness_gen: u64,
     ) -> Result<Self> {
         anyhow::ensure!(
             !(mock && cluster),
@@ -93,7 +102,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
             network_prover,
             fetcher,
             db_client,
-            program_config,
+            program_schedule,
             mock,
             cluster,
             cluster_config,
@@ -111,9 +120,42 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
             whitelist,
             min_auction_period,
             auction_timeout,
+            witness_gen_options: WitnessGenOptions::from_env()?,
+            // Ranges are requested in order, so the ranges whose witnesses are generated
+            // concurrently are adjacent.
+            shared_cache_scope: SharedPreimageCacheScope::from_env(
+                max_concurrent_witness_gen as usize,
+            )?,
         })
     }
 
+    /// Look up the scheduled program a request was created for.
+    pub fn program_for_request(&self, request: &OPSuccinctRequest) -> Result<ProgramConfig> {
+        let agg_vkey_hash = match request.req_type {
+            RequestType::Range => None,
+            RequestType::Aggregation => {
+                Some(B256::from_slice(request.aggregation_vkey_hash.as_ref().ok_or_else(|| {
+                    anyhow::anyhow!("Aggregation request {} has no aggregation vkey.", request.id)
+                })?))
+            }
+        };
+
+        self.program_schedule
+            .program_for_commitments(
+                B256::from_slice(&request.range_vkey_commitment),
+                B256::from_slice(&request.rollup_config_hash),
+                agg_vkey_hash,
+            )
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Request {} (blocks {}-{}) does not belong to any scheduled program.",
+                    request.id,
+                    request.start_block,
+                    request.end_block
+                )
+            })
+    }
+
     /// Returns true if proofs are generated synchronously (mock mode only).
     /// Cluster mode now uses async submit-then-poll and enters the Prove state.
     pub fn is_synchronous_proving(&self) -> bool {
@@ -175,15 +217,21 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                 .await?;
         }
 
-        let witness = self.host.run(&host_args).await?;
+        let shared_cache = self
+            .shared_cache_scope
+            .cache_for_range(request.start_block as u64, request.end_block as u64);
+        let options = self.witness_gen_options.clone().with_shared_cache(shared_cache);
+        let witness = self.host.run_with_options(&host_args, &options).await?;
         let sp1_stdin = self.host.witness_generator().get_sp1_stdin(witness)?;
 
         Ok(sp1_stdin)
     }
 
     /// Generates the witness for an aggregation proof.
+    #[allow(clippy::too_many_arguments)]
     pub async fn agg_proof_witnessgen(
         &self,
+        program: &ProgramConfig,
         start_block: i64,
         end_block: i64,
         checkpointed_l1_block_hash: B256,
@@ -197,7 +245,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
             .get_consecutive_complete_range_proofs(
                 start_block,
                 end_block,
-                &self.program_config.commitments,
+                &program.commitments,
                 l1_chain_id,
                 l2_chain_id,
             )
@@ -205,6 +253,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
 
         // Deserialize the proofs and extract the boot infos and proofs.
         let mut boot_infos = Vec::with_capacity(range_proofs.len());
+        let mut extended_outputs = Vec::with_capacity(range_proofs.len());
         let mut proofs = Vec::with_capacity(range_proofs.len());
 
         for proof in range_proofs.iter() {
@@ -216,7 +265,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                 )
             })?;
 
-            let mut proof_with_pv: SP1ProofWithPublicValues = bincode::deserialize(proof_bytes)
+            let proof_with_pv: SP1ProofWithPublicValues = bincode::deserialize(proof_bytes)
                 .map_err(|e| {
                     anyhow::anyhow!(
                         "Failed to deserialize range proof for blocks {}-{}: {e:?}",
@@ -225,7 +274,9 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                     )
                 })?;
 
-            boot_infos.push(proof_with_pv.public_values.read());
+            let (boot_info, extended) = read_range_outputs(proof_with_pv.public_values.as_slice())?;
+            boot_infos.push(boot_info);
+            extended_outputs.push(extended);
             proofs.push(proof_with_pv.proof.clone());
         }
 
@@ -243,8 +294,9 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
         let stdin = get_agg_proof_stdin(
             proofs,
             boot_infos,
+            extended_outputs,
             headers,
-            &self.program_config.range_vk,
+            &program.range_vk,
             checkpointed_l1_block_hash,
             prover_address,
         )
@@ -254,14 +306,18 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
     }
 
     /// Requests a range proof via the network prover.
-    pub async fn request_range_proof(&self, stdin: SP1Stdin) -> Result<B256> {
+    pub async fn request_range_proof(
+        &self,
+        program: &ProgramConfig,
+        stdin: SP1Stdin,
+    ) -> Result<B256> {
         let network_prover = self
             .network_prover
             .as_ref()
             .context("network_prover required for request_range_proof")?;
 
         let proof_id = match network_prover
-            .prove(&self.program_config.range_pk, stdin)
+            .prove(&program.range_pk, stdin)
             .compressed()
             .strategy(self.range_strategy)
             .skip_simulation(true)
@@ -285,14 +341,18 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
     }
 
     /// Requests an aggregation proof via the network prover.
-    pub async fn request_agg_proof(&self, stdin: SP1Stdin) -> Result<B256> {
+    pub async fn request_agg_proof(
+        &self,
+        program: &ProgramConfig,
+        stdin: SP1Stdin,
+    ) -> Result<B256> {
         let network_prover = self
             .network_prover
             .as_ref()
             .context("network_prover required for request_agg_proof")?;
 
         let proof_id = match network_prover
-            .prove(&self.program_config.agg_pk, stdin)
+            .prove(&program.agg_pk, stdin)
             .mode(self.agg_mode)
             .strategy(self.agg_strategy)
             .timeout(Duration::from_secs(self.proving_timeout))
@@ -317,6 +377,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
     /// Generates a mock range proof and writes the execution statistics to the database.
     pub async fn generate_mock_range_proof(
         &self,
+        program: &ProgramConfig,
         request: &OPSuccinctRequest,
         stdin: SP1Stdin,
     ) -> Result<SP1ProofWithPublicValues> {
@@ -335,7 +396,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
 
         let start_time = Instant::now();
         let (pv, report) = match network_prover
-            .execute(Elf::Static(get_range_elf_embedded()), stdin)
+            .execute(Elf::Static(program.range_elf), stdin)
             .calculate_gas(true)
             .deferred_proof_verification(false)
             .await
@@ -369,7 +430,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
             .await?;
 
         Ok(SP1ProofWithPublicValues::create_mock_proof(
-            &self.program_config.range_vk,
+            &program.range_vk,
             pv,
             SP1ProofMode::Compressed,
             SP1_CIRCUIT_VERSION,
@@ -379,6 +440,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
     /// Generates a mock aggregation proof.
     pub async fn generate_mock_agg_proof(
         &self,
+        program: &ProgramConfig,
         request: &OPSuccinctRequest,
         stdin: SP1Stdin,
     ) -> Result<SP1ProofWithPublicValues> {
@@ -389,7 +451,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
 
         let start_time = Instant::now();
         let (pv, report) = match network_prover
-            .execute(Elf::Static(AGGREGATION_ELF), stdin)
+            .execute(Elf::Static(program.agg_elf), stdin)
             .calculate_gas(true)
             .deferred_proof_verification(false)
             .await
@@ -423,7 +485,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
             .await?;
 
         Ok(SP1ProofWithPublicValues::create_mock_proof(
-            &self.program_config.agg_vk,
+            &program.agg_vk,
             pv,
             self.agg_mode,
             SP1_CIRCUIT_VERSION,
@@ -460,6 +522,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
         let l2_chain_id = self.fetcher.l2_provider.get_chain_id().await?;
 
         if request.end_block - request.start_block > 1 && request.req_type == RequestType::Range {
+            let program = self.program_for_request(&request)?;
             let num_failed_requests = self
                 .db_client
                 .fetch_failed_request_count_by_block_range(
@@ -467,7 +530,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                     request.end_block,
                     request.l1_chain_id,
                     request.l2_chain_id,
-                    &self.program_config.commitments,
+                    &program.commitments,
                 )
                 .await?;
 
@@ -480,8 +543,8 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                         request.mode,
                         request.start_block,
                         mid_block,
-                        self.program_config.commitments.range_vkey_commitment,
-                        self.program_config.commitments.rollup_config_hash,
+                        program.commitments.range_vkey_commitment,
+                        program.commitments.rollup_config_hash,
                         l1_chain_id as i64,
                         l2_chain_id as i64,
                         self.fetcher.clone(),
@@ -491,8 +554,8 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                         request.mode,
                         mid_block,
                         request.end_block,
-                        self.program_config.commitments.range_vkey_commitment,
-                        self.program_config.commitments.rollup_config_hash,
+                        program.commitments.range_vkey_commitment,
+                        program.commitments.rollup_config_hash,
                         l1_chain_id as i64,
                         l2_chain_id as i64,
                         self.fetcher.clone(),
@@ -523,11 +586,16 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
     }
 
     /// Generates the stdin needed for a proof.
-    async fn generate_proof_stdin(&self, request: &OPSuccinctRequest) -> Result<SP1Stdin> {
+    async fn generate_proof_stdin(
+        &self,
+        program: &ProgramConfig,
+        request: &OPSuccinctRequest,
+    ) -> Result<SP1Stdin> {
         let stdin = match request.req_type {
             RequestType::Range => self.range_proof_witnessgen(request).await?,
             RequestType::Aggregation => {
                 self.agg_proof_witnessgen(
+                    program,
                     request.start_block,
                     request.end_block,
                     B256::from_slice(request.checkpointed_l1_block_hash.as_ref().ok_or_else(
@@ -552,6 +620,8 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
     /// Note: Any error from this function will cause the proof to be retried.
     #[tracing::instrument(name = "proof_requester.make_proof_request", skip(self, request))]
     pub async fn make_proof_request(&self, request: OPSuccinctRequest) -> Result<()> {
+        let program = self.program_for_request(&request)?;
+
         self.db_client.update_request_status(request.id, RequestStatus::WitnessGeneration).await?;
 
         info!(
@@ -564,7 +634,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
 
         let witnessgen_duration = Instant::now();
         // Generate the stdin needed for the proof. If this fails, retry the request.
-        let stdin = match self.generate_proof_stdin(&request).await {
+        let stdin = match self.generate_proof_stdin(&program, &request).await {
             Ok(stdin) => stdin,
             Err(e) => {
                 ValidityGauge::WitnessgenErrorCount.increment(1.0);
@@ -591,7 +661,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
         match request.req_type {
             RequestType::Range => {
                 if self.mock {
-                    let proof = self.generate_mock_range_proof(&request, stdin).await?;
+                    let proof = self.generate_mock_range_proof(&program, &request, stdin).await?;
                     let proof_bytes = bincode::serialize(&proof)?;
                     self.db_client.update_proof_to_complete(request.id, &proof_bytes).await?;
                 } else if self.cluster {
@@ -599,9 +669,13 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                         .cluster_config
                         .as_ref()
                         .context("cluster_config required for cluster range proof")?;
-                    let result =
-                        cluster_submit_range_proof(cluster_config, self.proving_timeout, stdin)
-                            .await;
+                    let result = cluster_submit_range_proof(
+                        cluster_config,
+                        program.range_elf,
+                        self.proving_timeout,
+                        stdin,
+                    )
+                    .await;
                     // Box::pin erases the concrete Future type to break async type
                     // recursion that otherwise exceeds the default recursion_limit.
                     Box::pin(self.submit_cluster_proof(
@@ -612,7 +686,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                     ))
                     .await?;
                 } else {
-                    let proof_id = self.request_range_proof(stdin).await?;
+                    let proof_id = self.request_range_proof(&program, stdin).await?;
                     self.db_client.update_request_to_prove(request.id, proof_id).await?;
 
                     info!(
@@ -631,7 +705,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
             }
             RequestType::Aggregation => {
                 if self.mock {
-                    let proof = self.generate_mock_agg_proof(&request, stdin).await?;
+                    let proof = self.generate_mock_agg_proof(&program, &request, stdin).await?;
                     self.db_client.update_proof_to_complete(request.id, &proof.bytes()).await?;
                 } else if self.cluster {
                     let cluster_config = self
@@ -640,6 +714,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                         .context("cluster_config required for cluster agg proof")?;
                     let result = cluster_submit_agg_proof(
                         cluster_config,
+                        program.agg_elf,
                         self.proving_timeout,
                         self.agg_mode,
                         stdin,
@@ -654,7 +729,7 @@ impl<H: OPSuccinctHost> OPSuccinctProofRequester<H> {
                     ))
                     .await?;
                 } else {
-                    let proof_id = self.request_agg_proof(stdin).await?;
+                    let proof_id = self.request_agg_proof(&program, stdin).await?;
                     self.db_client.update_request_to_prove(request.id, proof_id).await?;
 
                     info!(
@@ -1,24 +1,35 @@
-use std::{collections::HashMap, ops::Range, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    sync::Arc,
+    time::Duration,
+};
 
 use alloy_eips::BlockId;
 use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{network::ReceiptResponse, Provider};
+use alloy_sol_types::SolEvent;
 use anyhow::{anyhow, Context, Result};
 use chrono::Utc;
 use futures_util::{stream, StreamExt, TryStreamExt};
-use op_succinct_client_utils::{boot::hash_rollup_config, types::u32_to_u8};
+use op_succinct_client_utils::boot::{hash_rollup_config, BootInfoStruct};
 use op_succinct_elfs::AGGREGATION_ELF;
 use op_succinct_host_utils::{
     fetcher::OPSuccinctDataFetcher,
     host::OPSuccinctHost,
+    ledger::{wei_to_eth, Ledger, LedgerTxKind},
     metrics::MetricsGauge,
     network::{determine_network_mode, get_network_signer},
+    quorum::QuorumError,
+    witness_generation::WitnessGenerationError,
     DisputeGameFactory::DisputeGameFactoryInstance as DisputeGameFactoryContract,
-    OPSuccinctL2OutputOracle::OPSuccinctL2OutputOracleInstance as OPSuccinctL2OOContract,
+    OPSuccinctL2OutputOracle::{
+        OPSuccinctL2OutputOracleInstance as OPSuccinctL2OOContract, OutputProposed,
+    },
 };
 use op_succinct_proof_utils::{
-    cluster_poll_proof, cluster_setup_keys, get_range_elf_embedded, is_cluster_mode,
-    reconstruct_proof_request, ClusterProofConfig, ClusterProofHandle, ClusterProofHandleJson,
+    cluster_poll_proof, get_range_elf_embedded, is_cluster_mode, reconstruct_proof_request,
+    ClusterProofConfig, ClusterProofHandle, ClusterProofHandleJson,
 };
 use op_succinct_signer_utils::SignerLock;
 use sp1_sdk::{
@@ -26,22 +37,27 @@ use sp1_sdk::{
         proto::types::{ExecutionStatus, FulfillmentStatus},
         NetworkMode,
     },
-    Elf, HashableKey, NetworkProver, Prover, ProverClient, ProvingKey, SP1Proof,
-    SP1ProofWithPublicValues,
+    NetworkProver, ProverClient, SP1Proof, SP1ProofWithPublicValues,
 };
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
 use crate::{
+    check_range_proof_canonical,
     db::{DriverDBClient, OPSuccinctRequest, RequestMode, RequestStatus, RequestType},
     find_gaps, get_latest_proposed_block_number, get_ranges_to_prove_by_blocks,
-    get_ranges_to_prove_by_gas, CommitmentConfig, ContractConfig, OPSuccinctProofRequester,
-    ProgramConfig, RequestExecutionStatistics, RequesterConfig, ValidityGauge,
+    get_ranges_to_prove_by_gas, next_aggregation_start, split_ranges_at, CommitmentConfig,
+    ContractConfig, OPSuccinctProofRequester, ProgramActivation, ProgramConfig, ProgramSchedule,
+    ProgramScheduleEntry, RequestExecutionStatistics, RequesterConfig, ScheduledProgram,
+    ValidityGauge,
 };
 
 /// Number of consecutive poll failures before a cluster proof is marked as permanently failed.
 const MAX_CONSECUTIVE_POLL_FAILURES: u32 = 3;
 
+/// Maximum number of L1 blocks to query for `OutputProposed` events in a single request.
+const RECONCILIATION_MAX_BLOCK_RANGE: u64 = 10_000;
+
 /// Configuration for the driver.
 pub struct DriverConfig {
     pub network_prover: Option<Arc<NetworkProver>>,
@@ -50,6 +66,20 @@ pub struct DriverConfig {
     pub signer: SignerLock,
     pub loop_interval: u64,
 }
+
+/// Summary of a reconciliation pass of the database against the outputs proposed on-chain.
+#[derive(Debug, Default)]
+pub struct ReconciliationReport {
+    /// Number of `OutputProposed` events scanned.
+    pub outputs_scanned: usize,
+    /// Number of completed aggregation requests marked as relayed.
+    pub marked_relayed: usize,
+    /// Number of proposed output roots that don't match the L2 node.
+    pub conflicting_output_roots: usize,
+    /// Number of relayed requests whose contract address was backfilled.
+    pub contract_addresses_backfilled: usize,
+}
+
 /// Type alias for a map of task IDs to their join handles and associated requests
 pub type TaskMap = HashMap<i64, (tokio::task::JoinHandle<Result<()>>, OPSuccinctRequest)>;
 
@@ -59,10 +89,15 @@ where
 {
     driver_config: DriverConfig,
     contract_config: ContractConfig<P>,
-    program_config: ProgramConfig,
+    program_schedule: Arc<ProgramSchedule>,
     requester_config: RequesterConfig,
     proof_requester: Arc<OPSuccinctProofRequester<H>>,
     tasks: Arc<Mutex<TaskMap>>,
+    /// IDs of completed range proofs whose L1 head is finalized and which were found to match
+    /// the canonical chain, so they don't need to be checked again.
+    verified_range_proofs: Arc<Mutex<HashSet<i64>>>,
+    /// Bonds and gas per relayed aggregation proof.
+    ledger: Ledger,
 }
 
 impl<P, H: OPSuccinctHost> Proposer<P, H>
@@ -105,31 +140,23 @@ where
         let cluster_handles: Arc<Mutex<HashMap<i64, ClusterProofHandle>>> =
             Arc::new(Mutex::new(HashMap::new()));
 
-        let (range_pk, range_vk, agg_pk, agg_vk, network_prover) = if is_cluster {
-            let (range_pk, range_vk, agg_pk, agg_vk) = cluster_setup_keys().await?;
-            (range_pk, range_vk, agg_pk, agg_vk, None)
+        let network_prover = if is_cluster {
+            None
         } else {
             let network_signer = get_network_signer(requester_config.use_kms_requester).await?;
             let network_mode = determine_network_mode(
                 requester_config.range_proof_strategy,
                 requester_config.agg_proof_strategy,
             )?;
-            let network_prover = Arc::new(
+            Some(Arc::new(
                 ProverClient::builder()
                     .network_for(network_mode)
                     .signer(network_signer)
                     .build()
                     .await,
-            );
-            let range_pk = network_prover.setup(Elf::Static(get_range_elf_embedded())).await?;
-            let range_vk = range_pk.verifying_key().clone();
-            let agg_pk = network_prover.setup(Elf::Static(AGGREGATION_ELF)).await?;
-            let agg_vk = agg_pk.verifying_key().clone();
-            (range_pk, range_vk, agg_pk, agg_vk, Some(network_prover))
+            ))
         };
 
-        let range_vkey_commitment = B256::from(u32_to_u8(range_vk.vk.hash_u32()));
-        let agg_vkey_hash = B256::from_str(&agg_vk.bytes32())?;
         let rollup_config_hash = hash_rollup_config(
             fetcher
                 .rollup_config
@@ -137,25 +164,43 @@ where
                 .ok_or_else(|| anyhow!("Rollup config must be set to initialize the proposer."))?,
         );
 
-        let program_config = ProgramConfig {
-            range_vk: Arc::new(range_vk),
-            range_pk: Arc::new(range_pk),
-            agg_vk: Arc::new(agg_vk),
-            agg_pk: Arc::new(agg_pk),
-            commitments: CommitmentConfig {
-                range_vkey_commitment,
-                agg_vkey_hash,
-                rollup_config_hash,
-            },
-        };
+        // The embedded programs prove from genesis. Programs from the schedule file take over at
+        // their activation.
+        let program_config = ProgramConfig::setup(
+            network_prover.as_deref(),
+            get_range_elf_embedded(),
+            AGGREGATION_ELF,
+            rollup_config_hash,
+        )
+        .await?;
         program_config.log();
 
+        let mut programs = vec![ScheduledProgram {
+            activation: ProgramActivation::Block(0),
+            config: program_config,
+        }];
+        if let Some(path) = requester_config.program_schedule_file.as_ref() {
+            for entry in ProgramScheduleEntry::read_all(path)? {
+                let (range_elf, agg_elf) = entry.load_elfs()?;
+                let config = ProgramConfig::setup(
+                    network_prover.as_deref(),
+                    range_elf,
+                    agg_elf,
+                    entry.rollup_config_hash.unwrap_or(rollup_config_hash),
+                )
+                .await?;
+                programs.push(ScheduledProgram { activation: entry.activation, config });
+            }
+        }
+        let program_schedule = Arc::new(ProgramSchedule::new(programs)?);
+        program_schedule.log();
+
         let proof_requester = Arc::new(OPSuccinctProofRequester::new(
             host,
             network_prover.clone(),
             fetcher.clone(),
             db_client.clone(),
-            program_config.clone(),
+            program_schedule.clone(),
             requester_config.mock,
             is_cluster,
             cluster_config,
@@ -173,6 +218,7 @@ where
             requester_config.whitelist.clone(),
             requester_config.min_auction_period,
             requester_config.auction_timeout,
+            requester_config.max_concurrent_witness_gen,
         )?);
 
         let l2oo_contract =
@@ -181,6 +227,8 @@ where
         let dgf_contract =
             DisputeGameFactoryContract::new(requester_config.dgf_address, provider.clone());
 
+        let ledger = Ledger::open(requester_config.ledger_path.clone())?;
+
         let proposer = Proposer {
             driver_config: DriverConfig {
                 network_prover,
@@ -195,10 +243,12 @@ where
                 l2oo_contract,
                 dgf_contract,
             },
-            program_config,
+            program_schedule,
             requester_config,
             proof_requester,
             tasks: Arc::new(Mutex::new(HashMap::new())),
+            verified_range_proofs: Arc::new(Mutex::new(HashSet::new())),
+            ledger,
         };
         Ok(proposer)
     }
@@ -232,33 +282,44 @@ where
             }
         };
 
-        // Get all active (non-failed) requests with the same commitment config and start block >=
-        // latest_proposed_block_number. These requests are non-overlapping.
-        let mut requests = self
-            .driver_config
-            .driver_db_client
-            .fetch_ranges_after_block(
-                &[
-                    RequestStatus::Unrequested,
-                    RequestStatus::WitnessGeneration,
-                    RequestStatus::Execution,
-                    RequestStatus::Prove,
-                    RequestStatus::Complete,
-                ],
-                latest_proposed_block_number as i64,
-                &self.program_config.commitments,
-                self.requester_config.l1_chain_id,
-                self.requester_config.l2_chain_id,
-            )
-            .await?;
+        // Get all active (non-failed) requests with start block >= latest_proposed_block_number
+        // that were created for the program currently scheduled for their range. These requests
+        // are non-overlapping.
+        let mut requests = Vec::new();
+        for commitments in self.program_schedule.range_commitments() {
+            let ranges = self
+                .driver_config
+                .driver_db_client
+                .fetch_ranges_after_block(
+                    &[
+                        RequestStatus::Unrequested,
+                        RequestStatus::WitnessGeneration,
+                        RequestStatus::Execution,
+                        RequestStatus::Prove,
+                        RequestStatus::Complete,
+                    ],
+                    latest_proposed_block_number as i64,
+                    &commitments,
+                    self.requester_config.l1_chain_id,
+                    self.requester_config.l2_chain_id,
+                )
+                .await?;
+            requests.extend(
+                ranges.into_iter().filter(|r| self.is_scheduled_for_range(&commitments, r.0)),
+            );
+        }
 
         // Sort the requests by start block.
         requests.sort_by_key(|r| r.0);
 
-        let disjoint_ranges = find_gaps(
-            latest_proposed_block_number as i64,
-            finalized_block_number as i64,
-            &requests,
+        // Ranges never cross a program activation boundary.
+        let disjoint_ranges = split_ranges_at(
+            &find_gaps(
+                latest_proposed_block_number as i64,
+                finalized_block_number as i64,
+                &requests,
+            ),
+            &self.program_schedule.split_points(),
         );
 
         let ranges_to_prove = if self.requester_config.evm_gas_limit > 0 {
@@ -305,12 +366,14 @@ where
                     } else {
                         RequestMode::Real
                     };
+                    let commitments =
+                        self.program_schedule.program_for_range(range.start).commitments;
                     OPSuccinctRequest::create_range_request(
                         mode,
                         range.start,
                         range.end,
-                        self.program_config.commitments.range_vkey_commitment,
-                        self.program_config.commitments.rollup_config_hash,
+                        commitments.range_vkey_commitment,
+                        commitments.rollup_config_hash,
                         self.requester_config.l1_chain_id,
                         self.requester_config.l2_chain_id,
                         self.driver_config.fetcher.clone(),
@@ -347,16 +410,7 @@ where
             return Ok(());
         }
 
-        let prove_requests = self
-            .driver_config
-            .driver_db_client
-            .fetch_requests_by_status(
-                RequestStatus::Prove,
-                &self.program_config.commitments,
-                self.requester_config.l1_chain_id,
-                self.requester_config.l2_chain_id,
-            )
-            .await?;
+        let prove_requests = self.fetch_scheduled_requests_by_status(RequestStatus::Prove).await?;
 
         for request in prove_requests {
             if self.proof_requester.cluster {
@@ -509,6 +563,16 @@ where
                 {
                     let execution_statistics = RequestExecutionStatistics::from(&proof_request);
 
+                    // Record the proof cost against the proof's block range. Range proof costs
+                    // are rolled up into the aggregation proof's entry once it is relayed. A price
+                    // of 0 means the network didn't report the price the request was fulfilled at.
+                    self.ledger.record_proof(
+                        &format!("{}-{}", request.start_block, request.end_block),
+                        proof_request.gas_used(),
+                        Some(proof_request.gas_price()).filter(|&price| price != 0),
+                    );
+                    self.update_ledger_metrics();
+
                     // Write the execution data to the database.
                     self.driver_config
                         .driver_db_client
@@ -815,48 +879,186 @@ where
         Ok(())
     }
 
+    /// Verify that the completed range proofs past the latest proposed block still commit to the
+    /// canonical chain. A range proof goes stale if the L1 head it was generated against or the
+    /// L2 blocks it proves were reorged out after it completed. Stale range proofs are set to
+    /// Failed, so that the range is re-requested instead of being aggregated.
+    ///
+    /// Range proofs with a finalized L1 head that pass the check are cached and not re-checked.
+    #[tracing::instrument(name = "proposer.verify_completed_range_proofs", skip(self))]
+    pub async fn verify_completed_range_proofs(&self) -> Result<()> {
+        let latest_proposed_block_number = get_latest_proposed_block_number(
+            self.contract_config.l2oo_address,
+            self.driver_config.fetcher.as_ref(),
+        )
+        .await? as i64;
+
+        let program = self.program_schedule.program_for_range(latest_proposed_block_number);
+
+        let completed_range_proofs = self
+            .driver_config
+            .driver_db_client
+            .get_consecutive_complete_range_proofs(
+                latest_proposed_block_number,
+                i64::MAX,
+                &program.commitments,
+                self.requester_config.l1_chain_id,
+                self.requester_config.l2_chain_id,
+            )
+            .await?;
+
+        let mut verified = self.verified_range_proofs.lock().await;
+
+        // Drop cached range proofs that have been aggregated or are no longer complete.
+        let completed_ids: HashSet<i64> = completed_range_proofs.iter().map(|r| r.id).collect();
+        verified.retain(|id| completed_ids.contains(id));
+
+        let unverified: Vec<_> =
+            completed_range_proofs.iter().filter(|r| !verified.contains(&r.id)).collect();
+        if unverified.is_empty() {
+            return Ok(());
+        }
+
+        let fetcher = &self.driver_config.fetcher;
+        let finalized_l1_block_number = fetcher.get_l1_header(BlockId::finalized()).await?.number;
+
+        for request in unverified {
+            let proof_bytes = request.proof.as_ref().ok_or_else(|| {
+                anyhow!(
+                    "Range proof for blocks {}-{} is missing proof data.",
+                    request.start_block,
+                    request.end_block
+                )
+            })?;
+            let mut proof_with_pv: SP1ProofWithPublicValues = bincode::deserialize(proof_bytes)
+                .map_err(|e| {
+                    anyhow!(
+                        "Failed to deserialize range proof for blocks {}-{}: {e:?}",
+                        request.start_block,
+                        request.end_block
+                    )
+                })?;
+            let boot_info: BootInfoStruct = proof_with_pv.public_values.read();
+
+            let canonical_pre_root =
+                fetcher.get_l2_output_at_block(request.start_block as u64).await?.output_root;
+            let canonical_post_root =
+                fetcher.get_l2_output_at_block(request.end_block as u64).await?.output_root;
+
+            // Look up the proof's L1 head by hash, then check it is the canonical block at its
+            // height. A missing block means the L1 head was reorged out and pruned.
+            let l1_head_block_number = fetcher
+                .l1_provider
+                .get_block_by_hash(boot_info.l1Head)
+                .await?
+                .map(|block| block.header.number);
+            let canonical_l1_head = match l1_head_block_number {
+                Some(number) => fetcher
+                    .l1_provider
+                    .get_block_by_number(number.into())
+                    .await?
+                    .map(|block| block.header.hash),
+                None => None,
+            };
+
+            match check_range_proof_canonical(
+                &boot_info,
+                request.end_block as u64,
+                canonical_pre_root,
+                canonical_post_root,
+                canonical_l1_head,
+            ) {
+                Ok(()) => {
+                    if l1_head_block_number.is_some_and(|n| n <= finalized_l1_block_number) {
+                        verified.insert(request.id);
+                    }
+                }
+                Err(reason) => {
+                    warn!(
+                        request_id = request.id,
+                        start_block = request.start_block,
+                        end_block = request.end_block,
+                        ?reason,
+                        "Completed range proof no longer matches the canonical chain; setting to Failed."
+                    );
+                    self.driver_config
+                        .driver_db_client
+                        .update_request_status(request.id, RequestStatus::Failed)
+                        .await?;
+                    ValidityGauge::RangeProofsInvalidated.increment(1.0);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Create aggregation proofs based on the completed range proofs. The range proofs must be
     /// contiguous and have the same range vkey commitment. Assumes that the range proof retry
     /// logic guarantees that there is not two potential contiguous chains of range proofs.
     ///
-    /// Only creates an Aggregation proof if there's not an Aggregation proof in progress with the
-    /// same start block.
+    /// If aggregation proofs starting at the latest proposed block are still pending, the next
+    /// aggregation proof starts at the end block of the last pending one, up to
+    /// `max_pipelined_agg_proofs` pending proofs. Only creates an Aggregation proof if there's not
+    /// an Aggregation proof in progress with the same start block.
     #[tracing::instrument(name = "proposer.create_aggregation_proofs", skip(self))]
     pub async fn create_aggregation_proofs(&self) -> Result<()> {
-        // Check if there's an Aggregation proof with the same start block AND range verification
-        // key commitment AND aggregation vkey. If so, return.
         let latest_proposed_block_number = get_latest_proposed_block_number(
             self.contract_config.l2oo_address,
             self.driver_config.fetcher.as_ref(),
         )
         .await? as i64;
 
-        // Get all active Aggregation proofs with the same start block, range vkey commitment, and
-        // aggregation vkey.
-        let active_agg_proofs_count = self
+        // The aggregation proof is generated by the program proving the next block to propose.
+        let program = self.program_schedule.program_for_range(latest_proposed_block_number);
+
+        // Get all active Aggregation proofs from the latest proposed block with the same range
+        // vkey commitment and aggregation vkey.
+        let active_agg_proofs: Vec<(i64, i64)> = self
             .driver_config
             .driver_db_client
-            .fetch_active_agg_proofs_count(
-                latest_proposed_block_number as i64,
-                &self.program_config.commitments,
+            .fetch_active_agg_proofs_from_block(
+                latest_proposed_block_number,
+                &program.commitments,
                 self.requester_config.l1_chain_id,
                 self.requester_config.l2_chain_id,
             )
-            .await?;
+            .await?
+            .iter()
+            .map(|request| (request.start_block, request.end_block))
+            .collect();
+
+        let Some(next) = next_aggregation_start(
+            latest_proposed_block_number,
+            &active_agg_proofs,
+            self.requester_config.max_pipelined_agg_proofs,
+        ) else {
+            debug!(
+                "The maximum number of pending Aggregation proofs with the same range vkey commitment and aggregation vkey is already queued."
+            );
+            return Ok(());
+        };
+        ValidityGauge::PipelinedAggProofs.set(next.pipeline_depth as f64);
 
-        if active_agg_proofs_count > 0 {
-            tracing::debug!("There is already an Aggregation proof queued with the same start block, range vkey commitment, and aggregation vkey.");
+        let start_block = next.start_block;
+
+        // Don't pipeline across a program boundary: the next program's aggregation proof is only
+        // created once the contract has reached its first block.
+        if self.program_schedule.program_for_range(start_block).commitments != program.commitments {
+            debug!(start_block, "Next aggregation proof belongs to the next scheduled program.");
             return Ok(());
         }
 
-        // Get the completed range proofs with a start block greater than the latest proposed block
-        // number. These blocks are sorted.
+        // Get the completed range proofs with a start block greater than the start block. These
+        // blocks are sorted. Their output roots were checked against the canonical chain in
+        // `verify_completed_range_proofs`, so a pipelined aggregation proof chains on the output
+        // root the pending proposal will post.
         let completed_range_proofs = self
             .driver_config
             .driver_db_client
             .fetch_completed_ranges(
-                &self.program_config.commitments,
-                latest_proposed_block_number as i64,
+                &program.commitments,
+                start_block,
                 self.requester_config.l1_chain_id,
                 self.requester_config.l2_chain_id,
             )
@@ -867,8 +1069,8 @@ where
             .get_highest_proven_contiguous_block(completed_range_proofs)?
         {
             Some(block) => block,
-            None => return Ok(()), /* No completed range proofs contiguous to the latest proposed
-                                    * block number, so no need to create an aggregation proof. */
+            None => return Ok(()), /* No completed range proofs contiguous to the start block, so
+                                    * no need to create an aggregation proof. */
         };
 
         // Get the submission interval from the contract.
@@ -882,10 +1084,17 @@ where
 
         debug!("Submission interval for aggregation proof: {}.", submission_interval);
 
-        // If the highest proven contiguous block number is greater than the latest proposed block
-        // number plus the submission interval, create an aggregation proof.
-        if (highest_proven_contiguous_block_number - latest_proposed_block_number) >=
-            submission_interval
+        // The last aggregation proof of a program ends at the next program's activation boundary,
+        // even if that is shorter than the submission interval.
+        let program_end = self
+            .program_schedule
+            .last_block_of_program_for(start_block as u64 + 1)
+            .map(|block| block as i64);
+
+        // If the aggregation proof before an active pipelined one failed, it is re-created ending
+        // exactly where the pipelined one starts, once the range proofs reach it.
+        if let Some(end_block) =
+            next.end_block(highest_proven_contiguous_block_number, submission_interval, program_end)
         {
             // If an aggregation request with the same start block and end block and commitment
             // config exists, there's no need to checkpoint the L1 block hash.
@@ -894,9 +1103,9 @@ where
                 .driver_config
                 .driver_db_client
                 .fetch_failed_agg_request_with_checkpointed_block_hash(
-                    latest_proposed_block_number,
-                    highest_proven_contiguous_block_number,
-                    &self.program_config.commitments,
+                    start_block,
+                    end_block,
+                    &program.commitments,
                     self.requester_config.l1_chain_id,
                     self.requester_config.l2_chain_id,
                 )
@@ -986,11 +1195,11 @@ where
             // hash.
             let agg_request = OPSuccinctRequest::new_agg_request(
                 if self.requester_config.mock { RequestMode::Mock } else { RequestMode::Real },
-                latest_proposed_block_number,
-                highest_proven_contiguous_block_number,
-                self.program_config.commitments.range_vkey_commitment,
-                self.program_config.commitments.agg_vkey_hash,
-                self.program_config.commitments.rollup_config_hash,
+                start_block,
+                end_block,
+                program.commitments.range_vkey_commitment,
+                program.commitments.agg_vkey_hash,
+                program.commitments.rollup_config_hash,
                 self.requester_config.l1_chain_id,
                 self.requester_config.l2_chain_id,
                 checkpointed_l1_block_number,
@@ -1003,6 +1212,7 @@ where
             info!(
                 start_block = agg_request.start_block,
                 end_block = agg_request.end_block,
+                pipeline_depth = next.pipeline_depth,
                 "Aggregation proof request created and inserted into database"
             );
         }
@@ -1019,32 +1229,10 @@ where
     /// loop.
     #[tracing::instrument(name = "proposer.request_queued_proofs", skip(self))]
     async fn request_queued_proofs(&self) -> Result<()> {
-        let commitments = self.program_config.commitments.clone();
-        let l1_chain_id = self.requester_config.l1_chain_id;
-        let l2_chain_id = self.requester_config.l2_chain_id;
-
-        let witness_gen_count = self
-            .driver_config
-            .driver_db_client
-            .fetch_request_count(
-                RequestStatus::WitnessGeneration,
-                &commitments,
-                l1_chain_id,
-                l2_chain_id,
-            )
-            .await?;
-
-        let execution_count = self
-            .driver_config
-            .driver_db_client
-            .fetch_request_count(RequestStatus::Execution, &commitments, l1_chain_id, l2_chain_id)
-            .await?;
-
-        let prove_count = self
-            .driver_config
-            .driver_db_client
-            .fetch_request_count(RequestStatus::Prove, &commitments, l1_chain_id, l2_chain_id)
-            .await?;
+        let witness_gen_count =
+            self.fetch_scheduled_request_count(RequestStatus::WitnessGeneration).await?;
+        let execution_count = self.fetch_scheduled_request_count(RequestStatus::Execution).await?;
+        let prove_count = self.fetch_scheduled_request_count(RequestStatus::Prove).await?;
 
         // If there are already MAX_CONCURRENT_PROOF_REQUESTS proofs in WitnessGeneration, Execute,
         // and Prove status, return.
@@ -1096,12 +1284,14 @@ where
         )
         .await?;
 
+        let program = self.program_schedule.program_for_range(latest_proposed_block_number as i64);
+
         let unreq_agg_request = self
             .driver_config
             .driver_db_client
             .fetch_unrequested_agg_proof(
                 latest_proposed_block_number as i64,
-                &self.program_config.commitments,
+                &program.commitments,
                 self.requester_config.l1_chain_id,
                 self.requester_config.l2_chain_id,
             )
@@ -1116,14 +1306,17 @@ where
                 .get_consecutive_complete_range_proofs(
                     unreq_agg_request.start_block,
                     unreq_agg_request.end_block,
-                    &self.program_config.commitments,
+                    &program.commitments,
                     self.requester_config.l1_chain_id,
                     self.requester_config.l2_chain_id,
                 )
                 .await?;
 
             // Validate the aggregation proof request
-            match self.validate_aggregation_request(&range_proofs, &unreq_agg_request).await {
+            match self
+                .validate_aggregation_request(&program, &range_proofs, &unreq_agg_request)
+                .await
+            {
                 true => {
                     debug!(
                         "Aggregation request validated successfully: start_block={}, end_block={}",
@@ -1142,22 +1335,32 @@ where
             }
         }
 
-        let unreq_range_request = self
-            .driver_config
-            .driver_db_client
-            .fetch_first_unrequested_range_proof(
-                latest_proposed_block_number as i64,
-                &self.program_config.commitments,
-                self.requester_config.l1_chain_id,
-                self.requester_config.l2_chain_id,
-            )
-            .await?;
+        // Request the lowest unrequested range across the programs scheduled for their ranges.
+        let mut next_range_request: Option<OPSuccinctRequest> = None;
+        for commitments in self.program_schedule.range_commitments() {
+            let unreq_range_request = self
+                .driver_config
+                .driver_db_client
+                .fetch_first_unrequested_range_proof(
+                    latest_proposed_block_number as i64,
+                    &commitments,
+                    self.requester_config.l1_chain_id,
+                    self.requester_config.l2_chain_id,
+                )
+                .await?;
 
-        if let Some(unreq_range_request) = unreq_range_request {
-            return Ok(Some(unreq_range_request));
+            if let Some(request) = unreq_range_request {
+                if self.is_scheduled_for_range(&commitments, request.start_block) &&
+                    next_range_request
+                        .as_ref()
+                        .is_none_or(|next| request.start_block < next.start_block)
+                {
+                    next_range_request = Some(request);
+                }
+            }
         }
 
-        Ok(None)
+        Ok(next_range_request)
     }
 
     /// Validates an aggregation proof request by checking that:
@@ -1166,6 +1369,7 @@ where
     /// 3. The range proofs cover the entire block range
     pub async fn validate_aggregation_request(
         &self,
+        program: &ProgramConfig,
         range_proofs: &[OPSuccinctRequest],
         agg_request: &OPSuccinctRequest,
     ) -> bool {
@@ -1187,7 +1391,7 @@ where
             warn!(
                 start_block = ?agg_request.start_block,
                 end_block = ?agg_request.end_block,
-                commitments = ?self.program_config.commitments,
+                commitments = ?program.commitments,
                 "No consecutive span proof range found for request"
             );
             return false;
@@ -1203,7 +1407,7 @@ where
             warn!(
                 expected_start_block = ?agg_request.start_block,
                 actual_start_block = ?first_range_proof_request.start_block,
-                commitments = ?self.program_config.commitments,
+                commitments = ?program.commitments,
                 "Range proofs start block does not match aggregation request"
             );
 
@@ -1214,7 +1418,7 @@ where
             warn!(
                 expected_end_block = ?agg_request.end_block,
                 actual_end_block = ?last_range_proof_request.end_block,
-                commitments = ?self.program_config.commitments,
+                commitments = ?program.commitments,
                 "Range proofs end block does not match aggregation request"
             );
             return false;
@@ -1267,6 +1471,8 @@ where
         )
         .await?;
 
+        let program = self.program_schedule.program_for_range(latest_proposed_block_number as i64);
+
         // See if there is an aggregation proof that is complete for this start block. NOTE: There
         // should only be one "pending" aggregation proof at a time for a specific start block.
         let completed_agg_proof = self
@@ -1274,7 +1480,7 @@ where
             .driver_db_client
             .fetch_completed_agg_proof_after_block(
                 latest_proposed_block_number as i64,
-                &self.program_config.commitments,
+                &program.commitments,
                 self.requester_config.l1_chain_id,
                 self.requester_config.l2_chain_id,
             )
@@ -1286,6 +1492,19 @@ where
             None => return Ok(()),
         };
 
+        // The contract only accepts the proof once its config has been switched to the program
+        // that generated it.
+        let on_chain_commitments = self.fetch_on_chain_commitments().await?;
+        if on_chain_commitments != program.commitments {
+            warn!(
+                end_block = completed_agg_proof.end_block,
+                expected = ?program.commitments,
+                on_chain = ?on_chain_commitments,
+                "Aggregation proof is ready, waiting for the on-chain config to switch to its program"
+            );
+            return Ok(());
+        }
+
         // Relay the aggregation proof.
         let transaction_hash = match self.relay_aggregation_proof(&completed_agg_proof).await {
             Ok(transaction_hash) => transaction_hash,
@@ -1307,6 +1526,35 @@ where
             )
             .await?;
 
+        // Attribute the cost of the range proofs the aggregation proof consumed to its entry.
+        match self
+            .proof_requester
+            .db_client
+            .get_consecutive_complete_range_proofs(
+                completed_agg_proof.start_block,
+                completed_agg_proof.end_block,
+                &program.commitments,
+                self.requester_config.l1_chain_id,
+                self.requester_config.l2_chain_id,
+            )
+            .await
+        {
+            Ok(range_proofs) => {
+                let ledger_id = format!(
+                    "{}-{}",
+                    completed_agg_proof.start_block, completed_agg_proof.end_block
+                );
+                for range_proof in &range_proofs {
+                    self.ledger.roll_up(
+                        &format!("{}-{}", range_proof.start_block, range_proof.end_block),
+                        &ledger_id,
+                    );
+                }
+                self.update_ledger_metrics();
+            }
+            Err(e) => warn!(?e, "Failed to fetch range proofs, range proof costs not rolled up"),
+        }
+
         Ok(())
     }
 
@@ -1325,6 +1573,9 @@ where
             .get_l2_output_at_block(completed_agg_proof.end_block as u64)
             .await?;
 
+        let ledger_id =
+            format!("{}-{}", completed_agg_proof.start_block, completed_agg_proof.end_block);
+
         // If the DisputeGameFactory address is set, use it to create a new validity dispute game
         // that will resolve with the proof. Note: In the DGF setting, the proof immediately
         // resolves the game. Otherwise, propose the L2 output.
@@ -1354,7 +1605,8 @@ where
                 .value(init_bond)
                 .into_transaction_request();
 
-            self.driver_config
+            let receipt = self
+                .driver_config
                 .signer
                 .send_transaction_request_with_timeout(
                     self.driver_config.fetcher.as_ref().rpc_config.l1_rpc.clone(),
@@ -1362,7 +1614,11 @@ where
                     self.requester_config.tx_confirmation_timeout,
                 )
                 .await
-                .map_err(|e| anyhow!("Failed to relay aggregation proof onchain. end_block: {}, checkpointed_l1_block_number: {}, error: {}", completed_agg_proof.end_block, completed_agg_proof.checkpointed_l1_block_number.unwrap(), e))?
+                .map_err(|e| anyhow!("Failed to relay aggregation proof onchain. end_block: {}, checkpointed_l1_block_number: {}, error: {}", completed_agg_proof.end_block, completed_agg_proof.checkpointed_l1_block_number.unwrap(), e))?;
+            if receipt.status() {
+                self.ledger.record_bond(&ledger_id, init_bond);
+            }
+            receipt
         } else {
             // Propose the L2 output to the L2OutputOracle directly.
             let transaction_request = self
@@ -1388,6 +1644,15 @@ where
                 .await?
         };
 
+        self.ledger.record_tx(
+            &ledger_id,
+            LedgerTxKind::Relay,
+            receipt.transaction_hash(),
+            receipt.gas_used(),
+            receipt.effective_gas_price(),
+        );
+        self.update_ledger_metrics();
+
         // If the transaction reverted, log the error.
         if !receipt.status() {
             return Err(anyhow!("Transaction reverted: {:?}", receipt));
@@ -1396,87 +1661,106 @@ where
         Ok(receipt.transaction_hash())
     }
 
-    /// Validate the requester config matches the contract.
-    async fn validate_contract_config(&self) -> Result<()> {
+    /// Set the ledger gauges from the ledger totals.
+    fn update_ledger_metrics(&self) {
+        let totals = self.ledger.totals();
+        ValidityGauge::LedgerBondsPostedEth.set(wei_to_eth(totals.bond_posted_wei));
+        ValidityGauge::LedgerGasSpentEth.set(wei_to_eth(totals.gas_spent_wei));
+        ValidityGauge::LedgerProofCostProve.set(wei_to_eth(totals.proof_cost_prove_wei));
+    }
+
+    /// Fetch the commitments of the configured `OpSuccinctConfig` from the contract.
+    async fn fetch_on_chain_commitments(&self) -> Result<CommitmentConfig> {
         let config_name = self.requester_config.op_succinct_config_name_hash;
 
         let contract_config =
             self.contract_config.l2oo_contract.opSuccinctConfigs(config_name).call().await?;
 
+        Ok(CommitmentConfig {
+            range_vkey_commitment: contract_config.range_vkey_commitment(),
+            agg_vkey_hash: contract_config.aggregation_vkey(),
+            rollup_config_hash: contract_config.rollup_config_hash(),
+        })
+    }
+
+    /// Validate the contract config matches one of the scheduled programs.
+    ///
+    /// Switching the contract to a program activated by an on-chain vkey change activates it.
+    async fn validate_contract_config(&self) -> Result<()> {
+        let on_chain_commitments = self.fetch_on_chain_commitments().await?;
+        let latest_proposed_block_number = get_latest_proposed_block_number(
+            self.contract_config.l2oo_address,
+            self.driver_config.fetcher.as_ref(),
+        )
+        .await?;
+
+        if let Some(index) = self
+            .program_schedule
+            .observe_on_chain_config(&on_chain_commitments, latest_proposed_block_number)
+        {
+            ValidityGauge::OnChainProgramIndex.set(index as f64);
+            ValidityGauge::ActiveProgramIndex.set(
+                self.program_schedule.index_for_block(latest_proposed_block_number + 1) as f64,
+            );
+            return Ok(());
+        }
+
+        // Report the mismatch against the program proving the next block to propose.
+        let program = self.program_schedule.program_for_block(latest_proposed_block_number + 1);
+
         // Extract the OpSuccinctConfig fields with meaningful names.
-        let contract_agg_vkey_hash = contract_config.aggregation_vkey();
-        let contract_range_vkey_commitment = contract_config.range_vkey_commitment();
-        let contract_rollup_config_hash = contract_config.rollup_config_hash();
+        let contract_agg_vkey_hash = on_chain_commitments.agg_vkey_hash;
+        let contract_range_vkey_commitment = on_chain_commitments.range_vkey_commitment;
+        let contract_rollup_config_hash = on_chain_commitments.rollup_config_hash;
 
         let rollup_config_hash_match =
-            contract_rollup_config_hash == self.program_config.commitments.rollup_config_hash;
-        let agg_vkey_hash_match =
-            contract_agg_vkey_hash == self.program_config.commitments.agg_vkey_hash;
+            contract_rollup_config_hash == program.commitments.rollup_config_hash;
+        let agg_vkey_hash_match = contract_agg_vkey_hash == program.commitments.agg_vkey_hash;
         let range_vkey_commitment_match =
-            contract_range_vkey_commitment == self.program_config.commitments.range_vkey_commitment;
+            contract_range_vkey_commitment == program.commitments.range_vkey_commitment;
+
+        tracing::error!(
+            rollup_config_hash_match = rollup_config_hash_match,
+            agg_vkey_hash_match = agg_vkey_hash_match,
+            range_vkey_commitment_match = range_vkey_commitment_match,
+            "Config mismatches detected."
+        );
 
-        if !rollup_config_hash_match || !agg_vkey_hash_match || !range_vkey_commitment_match {
+        if !rollup_config_hash_match {
             tracing::error!(
-                rollup_config_hash_match = rollup_config_hash_match,
-                agg_vkey_hash_match = agg_vkey_hash_match,
-                range_vkey_commitment_match = range_vkey_commitment_match,
-                "Config mismatches detected."
+                received = ?contract_rollup_config_hash,
+                expected = ?program.commitments.rollup_config_hash,
+                "Rollup config hash mismatch"
             );
+        }
 
-            if !rollup_config_hash_match {
-                tracing::error!(
-                    received = ?contract_rollup_config_hash,
-                    expected = ?self.program_config.commitments.rollup_config_hash,
-                    "Rollup config hash mismatch"
-                );
-            }
-
-            if !agg_vkey_hash_match {
-                tracing::error!(
-                    received = ?contract_agg_vkey_hash,
-                    expected = ?self.program_config.commitments.agg_vkey_hash,
-                    "Aggregation vkey hash mismatch"
-                );
-            }
-
-            if !range_vkey_commitment_match {
-                tracing::error!(
-                    received = ?contract_range_vkey_commitment,
-                    expected = ?self.program_config.commitments.range_vkey_commitment,
-                    "Range vkey commitment mismatch"
-                );
-            }
+        if !agg_vkey_hash_match {
+            tracing::error!(
+                received = ?contract_agg_vkey_hash,
+                expected = ?program.commitments.agg_vkey_hash,
+                "Aggregation vkey hash mismatch"
+            );
+        }
 
-            return Err(anyhow::anyhow!("Config mismatches detected. Please run {{cargo run --bin config --release -- --env-file ENV_FILE}} to get the expected config for your contract."));
+        if !range_vkey_commitment_match {
+            tracing::error!(
+                received = ?contract_range_vkey_commitment,
+                expected = ?program.commitments.range_vkey_commitment,
+                "Range vkey commitment mismatch"
+            );
         }
 
-        Ok(())
+        Err(anyhow::anyhow!("Config mismatches detected. Please run {{cargo run --bin config --release -- --env-file ENV_FILE}} to get the expected config for your contract."))
     }
 
     /// Set orphaned tasks to status FAILED. If a task is in the database in status Execution or
     /// WitnessGeneration but not in the tasks map, set it to status FAILED.
     async fn set_orphaned_tasks_to_failed(&self) -> Result<()> {
-        let witnessgen_requests = self
-            .driver_config
-            .driver_db_client
-            .fetch_requests_by_status(
-                RequestStatus::WitnessGeneration,
-                &self.program_config.commitments,
-                self.requester_config.l1_chain_id,
-                self.requester_config.l2_chain_id,
-            )
-            .await?;
+        let witnessgen_requests =
+            self.fetch_scheduled_requests_by_status(RequestStatus::WitnessGeneration).await?;
 
-        let execution_requests = self
-            .driver_config
-            .driver_db_client
-            .fetch_requests_by_status(
-                RequestStatus::Execution,
-                &self.program_config.commitments,
-                self.requester_config.l1_chain_id,
-                self.requester_config.l2_chain_id,
-            )
-            .await?;
+        let execution_requests =
+            self.fetch_scheduled_requests_by_status(RequestStatus::Execution).await?;
 
         let requests = [witnessgen_requests, execution_requests].concat();
 
@@ -1524,13 +1808,17 @@ where
                                 error = ?e,
                                 "Task failed with error"
                             );
+                            // A range whose witness generation ran out of time or memory is
+                            // split right away, as an unexecutable range would be.
+                            let execution_status = if WitnessGenerationError::find(&e).is_some() {
+                                ExecutionStatus::Unexecutable
+                            } else {
+                                ExecutionStatus::UnspecifiedExecutionStatus
+                            };
                             // Now safe to retry as original task is cleaned up
                             match self
                                 .proof_requester
-                                .handle_failed_request(
-                                    request,
-                                    ExecutionStatus::UnspecifiedExecutionStatus as i32,
-                                )
+                                .handle_failed_request(request, execution_status as i32)
                                 .await
                             {
                                 Ok(_) => {
@@ -1581,9 +1869,10 @@ where
     /// This function performs several key tasks:
     /// 1. Validates that the proposer's config matches the contract
     /// 2. Deletes unrecoverable requests (UNREQUESTED, EXECUTION, WITNESS_GENERATION)
-    /// 3. Cancels PROVE requests with mismatched commitment configs
-    /// 4. Identifies gaps between the latest proposed block and finalized block
-    /// 5. Creates new range proof requests to cover those gaps
+    /// 3. Cancels PROVE requests whose commitment config matches no scheduled program
+    /// 4. Reconciles relayed aggregation requests with the outputs proposed on-chain
+    /// 5. Identifies gaps between the latest proposed block and finalized block
+    /// 6. Creates new range proof requests to cover those gaps
     ///
     /// The goal is to ensure the database is in a clean state and all block ranges
     /// between the latest proposed block and finalized block have corresponding requests.
@@ -1609,12 +1898,12 @@ where
             )
             .await?;
 
-        // Cancel all requests in PROVE state for the same chain id's that have a different
-        // commitment config.
+        // Cancel all requests in PROVE state for the same chain id's that have a commitment config
+        // not matching any scheduled program.
         self.driver_config
             .driver_db_client
             .cancel_prove_requests_with_different_commitment_config(
-                &self.program_config.commitments,
+                &self.program_schedule.range_commitments(),
                 self.requester_config.l1_chain_id,
                 self.requester_config.l2_chain_id,
             )
@@ -1622,9 +1911,190 @@ where
 
         info!("Deleted all unrequested, execution, and witness generation requests and canceled all prove requests with different commitment configs.");
 
+        // Catch up on relays that landed without the database being updated. A failure here
+        // doesn't prevent the proposer from starting.
+        if let Err(e) = self.reconcile_with_contract().await {
+            warn!("Failed to reconcile the database with on-chain proposals: {:?}", e);
+        }
+
         Ok(())
     }
 
+    /// Reconcile the database with the outputs proposed to the L2OO.
+    ///
+    /// If a relay transaction landed but the request wasn't updated to Relayed (e.g. the proposer
+    /// was restarted while waiting for confirmations), or outputs were proposed by someone else,
+    /// the database and the contract disagree. This scans the `OutputProposed` events since the
+    /// last known relay or the earliest checkpoint of a completed aggregation request, which also
+    /// covers validity dispute games as they propose through the L2OO, and:
+    /// - Marks completed aggregation requests ending at a proposed block as Relayed with the
+    ///   proposing transaction hash, if the proposed output root matches the L2 node.
+    /// - Flags proposed output roots that conflict with the L2 node.
+    ///
+    /// The scan is skipped when nothing was relayed yet and no aggregation request is completed,
+    /// e.g. on a fresh database, as there is nothing to reconcile.
+    ///
+    /// It also backfills the contract address of relayed requests from the `OutputProposed` log
+    /// of their relay transaction.
+    #[tracing::instrument(name = "proposer.reconcile_with_contract", skip(self))]
+    pub async fn reconcile_with_contract(&self) -> Result<ReconciliationReport> {
+        let db_client = &self.driver_config.driver_db_client;
+        let fetcher = &self.driver_config.fetcher;
+        let l1_chain_id = self.requester_config.l1_chain_id;
+        let l2_chain_id = self.requester_config.l2_chain_id;
+        let mut report = ReconciliationReport::default();
+
+        let latest_proposed_block_number =
+            get_latest_proposed_block_number(self.contract_config.l2oo_address, fetcher.as_ref())
+                .await? as i64;
+
+        // Completed aggregation requests the contract has already moved past.
+        let unrelayed_agg_requests = db_client
+            .fetch_completed_agg_requests_up_to_block(
+                latest_proposed_block_number,
+                l1_chain_id,
+                l2_chain_id,
+            )
+            .await?;
+
+        // Scan from the L1 block of the last known relay. A completed aggregation request can't
+        // have been relayed before its checkpointed L1 block, so scan from there if it's earlier.
+        let last_relay_tx_hash = db_client
+            .fetch_latest_relayed_agg_request(l1_chain_id, l2_chain_id)
+            .await?
+            .and_then(|request| request.relay_tx_hash);
+        let last_relay_block = match last_relay_tx_hash {
+            Some(tx_hash) => fetcher
+                .l1_provider
+                .get_transaction_receipt(B256::from_slice(&tx_hash))
+                .await?
+                .and_then(|receipt| receipt.block_number()),
+            None => None,
+        };
+        let earliest_checkpoint = unrelayed_agg_requests
+            .iter()
+            .filter_map(|request| request.checkpointed_l1_block_number)
+            .min()
+            .map(|block| block as u64);
+        let from_block = match (last_relay_block, earliest_checkpoint) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (Some(block), None) | (None, Some(block)) => Some(block),
+            (None, None) => None,
+        };
+
+        // Map of proposed L2 block numbers to the transaction that proposed them, for outputs
+        // matching the L2 node.
+        let mut proposals: HashMap<i64, B256> = HashMap::new();
+        // With nothing relayed and no completed aggregation request there is nothing to
+        // reconcile. Scanning the full history instead would read the output of every proposal,
+        // which fails on non-archive L2 nodes.
+        if let Some(from_block) = from_block {
+            let to_block = fetcher.get_l1_header(BlockId::latest()).await?.number;
+            let mut start = from_block;
+            while start <= to_block {
+                let end = (start + RECONCILIATION_MAX_BLOCK_RANGE - 1).min(to_block);
+                let events = self
+                    .contract_config
+                    .l2oo_contract
+                    .OutputProposed_filter()
+                    .from_block(start)
+                    .to_block(end)
+                    .query()
+                    .await?;
+
+                for (event, log) in events {
+                    report.outputs_scanned += 1;
+                    let l2_block_number = event.l2BlockNumber.to::<u64>();
+                    let canonical_output_root =
+                        fetcher.get_l2_output_at_block(l2_block_number).await?.output_root;
+
+                    if event.outputRoot != canonical_output_root {
+                        report.conflicting_output_roots += 1;
+                        ValidityGauge::ConflictingOutputRootCount.increment(1.0);
+                        tracing::error!(
+                            l2_block_number,
+                            proposed_output_root = ?event.outputRoot,
+                            canonical_output_root = ?canonical_output_root,
+                            tx_hash = ?log.transaction_hash,
+                            "Proposed output root conflicts with the L2 node"
+                        );
+                        continue;
+                    }
+
+                    if let Some(tx_hash) = log.transaction_hash {
+                        proposals.insert(l2_block_number as i64, tx_hash);
+                    }
+                }
+
+                start = end + 1;
+            }
+        } else {
+            debug!("No relayed or completed aggregation requests, skipping proposal scan");
+        }
+
+        for request in &unrelayed_agg_requests {
+            let Some(&tx_hash) = proposals.get(&request.end_block) else {
+                debug!(
+                    request_id = request.id,
+                    start_block = request.start_block,
+                    end_block = request.end_block,
+                    "Completed aggregation request is behind the contract but was not proposed"
+                );
+                continue;
+            };
+
+            db_client
+                .update_request_to_relayed(request.id, tx_hash, self.contract_config.l2oo_address)
+                .await?;
+            report.marked_relayed += 1;
+
+            info!(
+                request_id = request.id,
+                start_block = request.start_block,
+                end_block = request.end_block,
+                ?tx_hash,
+                "Marked aggregation request as relayed from on-chain proposal"
+            );
+        }
+
+        // Backfill the contract address of relayed requests from the L2OO that emitted the
+        // `OutputProposed` log of the relay, which may predate an L2OO migration. Relays through
+        // the DGF or a multisig still propose to the L2OO, which is the address the relay paths
+        // record.
+        for request in db_client
+            .fetch_relayed_requests_without_contract_address(l1_chain_id, l2_chain_id)
+            .await?
+        {
+            let Some(tx_hash) = request.relay_tx_hash.as_deref().map(B256::from_slice) else {
+                continue;
+            };
+            let Some(receipt) = fetcher.l1_provider.get_transaction_receipt(tx_hash).await? else {
+                warn!(request_id = request.id, ?tx_hash, "Relay transaction receipt not found");
+                continue;
+            };
+            let Some(contract_address) =
+                receipt.inner.logs().iter().find_map(|log| {
+                    OutputProposed::decode_log(&log.inner).ok().map(|_| log.address())
+                })
+            else {
+                warn!(request_id = request.id, ?tx_hash, "Relay transaction proposed no output");
+                continue;
+            };
+            db_client.update_request_to_relayed(request.id, tx_hash, contract_address).await?;
+            report.contract_addresses_backfilled += 1;
+        }
+
+        info!(
+            outputs_scanned = report.outputs_scanned,
+            marked_relayed = report.marked_relayed,
+            conflicting_output_roots = report.conflicting_output_roots,
+            contract_addresses_backfilled = report.contract_addresses_backfilled,
+            "Reconciled the database with on-chain proposals"
+        );
+
+        Ok(report)
+    }
+
     /// Fetch and log the proposer metrics.
     async fn log_proposer_metrics(&self) -> Result<()> {
         // Get the latest proposed block number on the contract.
@@ -1634,12 +2104,13 @@ where
         )
         .await?;
 
-        // Get all completed range proofs from the database.
+        // Get all completed range proofs of the program proving the next block from the database.
+        let program = self.program_schedule.program_for_range(latest_proposed_block_number as i64);
         let completed_range_proofs = self
             .driver_config
             .driver_db_client
             .fetch_completed_ranges(
-                &self.program_config.commitments,
+                &program.commitments,
                 latest_proposed_block_number as i64,
                 self.requester_config.l1_chain_id,
                 self.requester_config.l2_chain_id,
@@ -1651,12 +2122,6 @@ where
             .get_highest_proven_contiguous_block(completed_range_proofs)?
             .map_or(latest_proposed_block_number, |block| block as u64);
 
-        // Fetch request counts for different statuses
-        let commitments = &self.program_config.commitments;
-        let l1_chain_id = self.requester_config.l1_chain_id;
-        let l2_chain_id = self.requester_config.l2_chain_id;
-        let db_client = &self.driver_config.driver_db_client;
-
         // Define statuses and their corresponding variable names
         let (
             num_unrequested_requests,
@@ -1664,33 +2129,10 @@ where
             num_execution_requests,
             num_witness_generation_requests,
         ) = (
-            db_client
-                .fetch_request_count(
-                    RequestStatus::Unrequested,
-                    commitments,
-                    l1_chain_id,
-                    l2_chain_id,
-                )
-                .await?,
-            db_client
-                .fetch_request_count(RequestStatus::Prove, commitments, l1_chain_id, l2_chain_id)
-                .await?,
-            db_client
-                .fetch_request_count(
-                    RequestStatus::Execution,
-                    commitments,
-                    l1_chain_id,
-                    l2_chain_id,
-                )
-                .await?,
-            db_client
-                .fetch_request_count(
-                    RequestStatus::WitnessGeneration,
-                    commitments,
-                    l1_chain_id,
-                    l2_chain_id,
-                )
-                .await?,
+            self.fetch_scheduled_request_count(RequestStatus::Unrequested).await?,
+            self.fetch_scheduled_request_count(RequestStatus::Prove).await?,
+            self.fetch_scheduled_request_count(RequestStatus::Execution).await?,
+            self.fetch_scheduled_request_count(RequestStatus::WitnessGeneration).await?,
         );
 
         // Log metrics
@@ -1734,6 +2176,8 @@ where
 
         // Initialize the metrics gauges.
         ValidityGauge::init_all();
+        self.update_ledger_metrics();
+        self.ledger.spawn_writer();
 
         // Loop interval in seconds.
         loop {
@@ -1748,6 +2192,9 @@ where
                     tracing::error!("Error in proposer loop: {:?}", e);
                     // Update the error gauge
                     ValidityGauge::TotalErrorCount.increment(1.0);
+                    if QuorumError::is_disagreement(&e) {
+                        ValidityGauge::OutputRootDisagreementCount.increment(1.0);
+                    }
                     // Pause for 10 seconds before restarting
                     tracing::debug!("Pausing for 10 seconds before restarting the process");
                     tokio::time::sleep(Duration::from_secs(10)).await;
@@ -1776,6 +2223,9 @@ where
         // Add new range requests to the database.
         self.add_new_ranges().await?;
 
+        // Fail completed range proofs that were invalidated by an L1 or L2 reorg.
+        self.verify_completed_range_proofs().await?;
+
         // Create aggregation proofs based on the completed range proofs. Checkpoints the block hash
         // associated with the aggregation proof in advance.
         self.create_aggregation_proofs().await?;
@@ -1795,6 +2245,56 @@ where
         Ok(())
     }
 
+    /// Whether requests with `commitments` starting at `start_block` belong to the program
+    /// scheduled for that range. Requests of a program that has since been superseded for the
+    /// range are ignored.
+    fn is_scheduled_for_range(&self, commitments: &CommitmentConfig, start_block: i64) -> bool {
+        self.program_schedule
+            .program_for_range(start_block)
+            .commitments
+            .same_range_program(commitments)
+    }
+
+    /// Fetch the requests with the given status across all scheduled programs.
+    async fn fetch_scheduled_requests_by_status(
+        &self,
+        status: RequestStatus,
+    ) -> Result<Vec<OPSuccinctRequest>> {
+        let mut requests = Vec::new();
+        for commitments in self.program_schedule.range_commitments() {
+            requests.extend(
+                self.driver_config
+                    .driver_db_client
+                    .fetch_requests_by_status(
+                        status,
+                        &commitments,
+                        self.requester_config.l1_chain_id,
+                        self.requester_config.l2_chain_id,
+                    )
+                    .await?,
+            );
+        }
+        Ok(requests)
+    }
+
+    /// Fetch the number of requests with the given status across all scheduled programs.
+    async fn fetch_scheduled_request_count(&self, status: RequestStatus) -> Result<i64> {
+        let mut count = 0;
+        for commitments in self.program_schedule.range_commitments() {
+            count += self
+                .driver_config
+                .driver_db_client
+                .fetch_request_count(
+                    status,
+                    &commitments,
+                    self.requester_config.l1_chain_id,
+                    self.requester_config.l2_chain_id,
+                )
+                .await?;
+        }
+        Ok(count)
+    }
+
     /// Get the highest block number at the end of the largest contiguous range of completed range
     /// proofs. Returns None if there are no completed range proofs.
     fn get_highest_proven_contiguous_block(
@@ -1,8 +1,10 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use alloy_primitives::B256;
 use base64::{engine::general_purpose, Engine as _};
-use op_succinct_client_utils::precompiles::cycle_tracker::keys;
+use op_succinct_host_utils::stats::{
+    block_cycle_stats, precompile_stats, BlockCycleStats, PrecompileStats,
+};
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use sp1_sdk::{
@@ -118,12 +120,10 @@ pub struct RequestExecutionStatistics {
     pub oracle_verify_cycles: u64,
     pub derivation_cycles: u64,
     pub blob_verification_cycles: u64,
-    pub bn_add_cycles: u64,
-    pub bn_mul_cycles: u64,
-    pub bn_pair_cycles: u64,
-    pub kzg_eval_cycles: u64,
-    pub ec_recover_cycles: u64,
-    pub p256_verify_cycles: u64,
+    /// The cycles and calls of each precompile, keyed by tracker name.
+    pub precompiles: BTreeMap<String, PrecompileStats>,
+    /// The execution cycles of each block, sorted by block number.
+    pub blocks: Vec<BlockCycleStats>,
 }
 
 impl RequestExecutionStatistics {
@@ -137,12 +137,8 @@ impl RequestExecutionStatistics {
             oracle_verify_cycles: get_cycles("oracle-verify"),
             derivation_cycles: get_cycles("payload-derivation"),
             blob_verification_cycles: get_cycles("blob-verification"),
-            bn_add_cycles: get_cycles(keys::BN_ADD),
-            bn_mul_cycles: get_cycles(keys::BN_MUL),
-            bn_pair_cycles: get_cycles(keys::BN_PAIR),
-            kzg_eval_cycles: get_cycles(keys::KZG_EVAL),
-            ec_recover_cycles: get_cycles(keys::EC_RECOVER),
-            p256_verify_cycles: get_cycles(keys::P256_VERIFY),
+            precompiles: precompile_stats(&execution_report),
+            blocks: block_cycle_stats(&execution_report),
         }
     }
 }
@@ -156,12 +152,8 @@ impl From<&ProofRequest> for RequestExecutionStatistics {
             oracle_verify_cycles: 0,
             derivation_cycles: 0,
             blob_verification_cycles: 0,
-            bn_add_cycles: 0,
-            bn_mul_cycles: 0,
-            bn_pair_cycles: 0,
-            kzg_eval_cycles: 0,
-            ec_recover_cycles: 0,
-            p256_verify_cycles: 0,
+            precompiles: BTreeMap::new(),
+            blocks: Vec::new(),
         }
     }
 }
@@ -1,4 +1,6 @@
+use alloy_primitives::B256;
 use anyhow::{anyhow, Result};
+use op_succinct_client_utils::boot::BootInfoStruct;
 use op_succinct_host_utils::fetcher::BlockInfo;
 use std::{collections::HashMap, ops::Range};
 
@@ -37,6 +39,37 @@ pub fn find_gaps(overall_start: i64, overall_end: i64, ranges: &[(i64, i64)]) ->
     merge_ranges(gaps)
 }
 
+/// Splits ranges so that none of them crosses one of the given split points.
+///
+/// Used to keep range proofs from covering blocks proven by two different programs. A range
+/// `(start, end]` is split at `point` when `start < point < end`.
+///
+/// # Example
+///
+/// ```
+/// use op_succinct_validity::split_ranges_at;
+///
+/// let ranges = [0..10, 20..30];
+/// let split = split_ranges_at(&ranges, &[5, 20, 25]);
+/// assert_eq!(split, [0..5, 5..10, 20..25, 25..30]);
+/// ```
+pub fn split_ranges_at(ranges: &[Range<i64>], split_points: &[i64]) -> Vec<Range<i64>> {
+    let mut split = Vec::with_capacity(ranges.len());
+
+    for range in ranges {
+        let mut current_start = range.start;
+        for &point in split_points {
+            if current_start < point && point < range.end {
+                split.push(current_start..point);
+                current_start = point;
+            }
+        }
+        split.push(current_start..range.end);
+    }
+
+    split
+}
+
 /// Determines the block ranges to be proven based on a set of ranges and a gas limit.
 ///
 /// Given a set of block ranges that overlap at most on the boundaries and a gas limit, this
@@ -169,6 +202,122 @@ pub fn get_ranges_to_prove_by_blocks(
     ranges
 }
 
+/// Where the next aggregation proof starts when aggregation proofs are pipelined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NextAggregation {
+    /// The start block of the next aggregation proof.
+    pub start_block: i64,
+    /// The start block of an active aggregation proof after `start_block`, if any. The next
+    /// aggregation proof must not extend past it, so that the later proof can still be relayed.
+    pub max_end_block: Option<i64>,
+    /// The number of pending aggregation proofs the next aggregation proof chains on.
+    pub pipeline_depth: u64,
+}
+
+impl NextAggregation {
+    /// The end block of the next aggregation proof, or `None` if it shouldn't be created yet.
+    ///
+    /// Before an active pipelined proof, the proof must end exactly where the pipelined one
+    /// starts: ending short of it would leave a gap too small to propose, so the pipelined proof
+    /// could never be relayed. Otherwise, the proof ends at the highest contiguous proven block
+    /// once it spans the submission interval or reaches `program_end`, the last block of the
+    /// program.
+    pub fn end_block(
+        &self,
+        highest_proven_contiguous_block: i64,
+        submission_interval: i64,
+        program_end: Option<i64>,
+    ) -> Option<i64> {
+        if let Some(max_end_block) = self.max_end_block {
+            return (highest_proven_contiguous_block >= max_end_block).then_some(max_end_block);
+        }
+
+        let spans_interval =
+            highest_proven_contiguous_block - self.start_block >= submission_interval;
+        let reached_program_end = program_end == Some(highest_proven_contiguous_block);
+        (spans_interval || reached_program_end).then_some(highest_proven_contiguous_block)
+    }
+}
+
+/// Plans the next aggregation proof from the latest proposed block and the `(start, end)` ranges
+/// of the active aggregation proofs, sorted by start block.
+///
+/// The next aggregation proof starts at the end of the chain of pending aggregation proofs
+/// beginning at the latest proposed block. Returns `None` if that chain is already longer than
+/// `max_pipelined_agg_proofs`.
+pub fn next_aggregation_start(
+    latest_proposed_block: i64,
+    active_agg_proofs: &[(i64, i64)],
+    max_pipelined_agg_proofs: u64,
+) -> Option<NextAggregation> {
+    let mut start_block = latest_proposed_block;
+    let mut pipeline_depth = 0;
+
+    while let Some(&(_, end)) = active_agg_proofs.iter().find(|(start, _)| *start == start_block) {
+        if pipeline_depth >= max_pipelined_agg_proofs {
+            return None;
+        }
+        pipeline_depth += 1;
+        start_block = end;
+    }
+
+    let max_end_block =
+        active_agg_proofs.iter().map(|(start, _)| *start).find(|start| *start > start_block);
+
+    Some(NextAggregation { start_block, max_end_block, pipeline_depth })
+}
+
+/// Why a completed range proof no longer commits to the canonical chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StaleRangeProof {
+    /// The proof ends at a different L2 block than its request.
+    BlockNumberMismatch { proven: u64, expected: u64 },
+    /// The L2 output at the start block differs from the one the proof starts from.
+    PreRootMismatch { proven: B256, canonical: B256 },
+    /// The L2 output at the end block differs from the one the proof ends at.
+    PostRootMismatch { proven: B256, canonical: B256 },
+    /// The proof's L1 head is not the canonical L1 block at its height (`None` if the L1 head
+    /// could not be found at all).
+    L1HeadReorged { proven: B256, canonical: Option<B256> },
+}
+
+/// Checks the boot info committed to by a range proof `(start, end_block]` against the canonical
+/// L2 outputs at its start and end block and the canonical L1 block hash at the height of its L1
+/// head.
+pub fn check_range_proof_canonical(
+    boot_info: &BootInfoStruct,
+    end_block: u64,
+    canonical_pre_root: B256,
+    canonical_post_root: B256,
+    canonical_l1_head: Option<B256>,
+) -> Result<(), StaleRangeProof> {
+    if boot_info.l2BlockNumber != end_block {
+        return Err(StaleRangeProof::BlockNumberMismatch {
+            proven: boot_info.l2BlockNumber,
+            expected: end_block,
+        });
+    }
+    if boot_info.l2PreRoot != canonical_pre_root {
+        return Err(StaleRangeProof::PreRootMismatch {
+            proven: boot_info.l2PreRoot,
+            canonical: canonical_pre_root,
+        });
+    }
+    if boot_info.l2PostRoot != canonical_post_root {
+        return Err(StaleRangeProof::PostRootMismatch {
+            proven: boot_info.l2PostRoot,
+            canonical: canonical_post_root,
+        });
+    }
+    if canonical_l1_head != Some(boot_info.l1Head) {
+        return Err(StaleRangeProof::L1HeadReorged {
+            proven: boot_info.l1Head,
+            canonical: canonical_l1_head,
+        });
+    }
+    Ok(())
+}
+
 /// Merges overlapping or contiguous ranges into a single range.
 ///
 /// Takes a vector of ranges and combines any that overlap or touch, returning
@@ -244,6 +393,27 @@ mod tests {
     test_merge_ranges!(test_merge_ranges_overlap, vec![3..6, 4..7], vec![3..7]);
     test_merge_ranges!(test_merge_ranges_disjoint, vec![3..5, 7..9], vec![3..5, 7..9]);
 
+    // Tests for split_ranges_at
+    macro_rules! test_split_ranges_at {
+        ($name:ident, $ranges:expr, $split_points:expr, $expected:expr) => {
+            #[test]
+            fn $name() {
+                let result = split_ranges_at($ranges, $split_points);
+                assert_eq!(result, $expected);
+            }
+        };
+    }
+
+    test_split_ranges_at!(test_split_ranges_at_no_points, &[0..10], &[], &[0..10]);
+    test_split_ranges_at!(test_split_ranges_at_inside, &[0..10], &[4], &[0..4, 4..10]);
+    test_split_ranges_at!(test_split_ranges_at_edges, &[0..10], &[0, 10], &[0..10]);
+    test_split_ranges_at!(
+        test_split_ranges_at_multiple,
+        &[0..10, 20..30],
+        &[3, 7, 25, 40],
+        &[0..3, 3..7, 7..10, 20..25, 25..30]
+    );
+
     // Tests for get_ranges_to_prove_by_blocks
     macro_rules! test_get_ranges_to_prove_by_blocks {
         ($name:ident, $disjoint_ranges:expr, $range_proof_interval:expr, $expected:expr) => {
@@ -516,4 +686,129 @@ mod tests {
         let result = get_ranges_to_prove_by_blocks(&[0..5, 10..15, 20..25], 30);
         assert_eq!(result, vec![0..5, 10..15]);
     }
+
+    // Tests for next_aggregation_start
+
+    #[test]
+    fn test_next_aggregation_no_pending() {
+        let result = next_aggregation_start(100, &[], 1);
+        assert_eq!(
+            result,
+            Some(NextAggregation { start_block: 100, max_end_block: None, pipeline_depth: 0 })
+        );
+    }
+
+    #[test]
+    fn test_next_aggregation_pipelining_disabled() {
+        assert_eq!(next_aggregation_start(100, &[(100, 200)], 0), None);
+    }
+
+    #[test]
+    fn test_next_aggregation_chains_on_pending() {
+        let result = next_aggregation_start(100, &[(100, 200), (200, 300)], 2);
+        assert_eq!(
+            result,
+            Some(NextAggregation { start_block: 300, max_end_block: None, pipeline_depth: 2 })
+        );
+    }
+
+    #[test]
+    fn test_next_aggregation_pipeline_full() {
+        assert_eq!(next_aggregation_start(100, &[(100, 200), (200, 300)], 1), None);
+    }
+
+    #[test]
+    fn test_next_aggregation_caps_at_orphaned_pipelined_proof() {
+        // The aggregation proof for 100-200 failed, but the pipelined one for 200-300 is active.
+        let result = next_aggregation_start(100, &[(200, 300)], 1);
+        assert_eq!(
+            result,
+            Some(NextAggregation { start_block: 100, max_end_block: Some(200), pipeline_depth: 0 })
+        );
+        let next = result.unwrap();
+
+        // Ending at 180 would leave 180-200, shorter than the submission interval, so wait.
+        assert_eq!(next.end_block(180, 60, None), None);
+        assert_eq!(next.end_block(200, 60, None), Some(200));
+        assert_eq!(next.end_block(250, 60, None), Some(200));
+    }
+
+    #[test]
+    fn test_next_aggregation_end_block() {
+        let next = NextAggregation { start_block: 100, max_end_block: None, pipeline_depth: 0 };
+        assert_eq!(next.end_block(150, 60, None), None);
+        assert_eq!(next.end_block(160, 60, None), Some(160));
+        assert_eq!(next.end_block(150, 60, Some(150)), Some(150));
+    }
+
+    // Tests for check_range_proof_canonical
+
+    fn boot_info(l2_block_number: u64) -> BootInfoStruct {
+        BootInfoStruct {
+            l1Head: B256::repeat_byte(0x01),
+            l2PreRoot: B256::repeat_byte(0x02),
+            l2PostRoot: B256::repeat_byte(0x03),
+            l2BlockNumber: l2_block_number,
+            rollupConfigHash: B256::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_range_proof_canonical() {
+        let boot_info = boot_info(100);
+        let result = check_range_proof_canonical(
+            &boot_info,
+            100,
+            boot_info.l2PreRoot,
+            boot_info.l2PostRoot,
+            Some(boot_info.l1Head),
+        );
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_range_proof_post_root_reorged() {
+        let boot_info = boot_info(100);
+        let canonical = B256::repeat_byte(0xff);
+        let result = check_range_proof_canonical(
+            &boot_info,
+            100,
+            boot_info.l2PreRoot,
+            canonical,
+            Some(boot_info.l1Head),
+        );
+        assert_eq!(
+            result,
+            Err(StaleRangeProof::PostRootMismatch { proven: boot_info.l2PostRoot, canonical })
+        );
+    }
+
+    #[test]
+    fn test_range_proof_l1_head_reorged() {
+        let boot_info = boot_info(100);
+        let result = check_range_proof_canonical(
+            &boot_info,
+            100,
+            boot_info.l2PreRoot,
+            boot_info.l2PostRoot,
+            None,
+        );
+        assert_eq!(
+            result,
+            Err(StaleRangeProof::L1HeadReorged { proven: boot_info.l1Head, canonical: None })
+        );
+    }
+
+    #[test]
+    fn test_range_proof_wrong_block() {
+        let boot_info = boot_info(99);
+        let result = check_range_proof_canonical(
+            &boot_info,
+            100,
+            boot_info.l2PreRoot,
+            boot_info.l2PostRoot,
+            Some(boot_info.l1Head),
+        );
+        assert_eq!(result, Err(StaleRangeProof::BlockNumberMismatch { proven: 99, expected: 100 }));
+    }
 }